@@ -0,0 +1,167 @@
+//! Command-line arguments. Every subcommand maps to one program instruction
+//! or account query in `main`.
+
+use clap::{Args, Parser, Subcommand};
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Parser)]
+#[command(name = "shadowvault-cli", version, about = "Operate ShadowVault vaults")]
+pub struct Cli {
+    /// RPC endpoint
+    #[arg(long, short = 'u', global = true, env = "SHADOWVAULT_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    pub url: String,
+
+    /// Signer keypair file; pays fees and signs as owner/user/executor
+    #[arg(long, short = 'k', global = true, env = "SHADOWVAULT_KEYPAIR", default_value = "~/.config/solana/id.json")]
+    pub keypair: String,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Create the signer's next vault
+    InitVault {
+        /// 32-byte encrypted strategy commitment, hex
+        #[arg(long, value_parser = parse_hex32)]
+        strategy_hash: [u8; 32],
+    },
+    /// Deposit tokens into a vault
+    Deposit {
+        #[command(flatten)]
+        target: VaultMint,
+        #[arg(long)]
+        amount: u64,
+        /// Allowlist merkle proof nodes, hex (repeat per node)
+        #[arg(long = "proof", value_parser = parse_hex32)]
+        proof: Vec<[u8; 32]>,
+    },
+    /// Withdraw tokens to the owner's token account
+    Withdraw {
+        #[command(flatten)]
+        target: VaultMint,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Submit a trade intent; `--slices` above 1 makes it a TWAP intent,
+    /// `--limit-price` a limit order, `--stop-loss`/`--take-profit` a
+    /// conditional intent (both: an OCO pair)
+    SubmitIntent {
+        #[arg(long)]
+        vault: Pubkey,
+        #[arg(long)]
+        token_in: Pubkey,
+        #[arg(long)]
+        token_out: Pubkey,
+        #[arg(long)]
+        amount: u64,
+        #[arg(long, default_value_t = 100)]
+        max_slippage_bps: u16,
+        #[arg(long, default_value_t = 0)]
+        strategy_type: u8,
+        #[arg(long, default_value_t = 1)]
+        slices: u16,
+        /// Minimum seconds between TWAP slices
+        #[arg(long, default_value_t = 0)]
+        interval_secs: i64,
+        /// Least token_out per token_in
+        #[arg(long)]
+        limit_price: Option<f64>,
+        /// Trigger when the feed price falls to or below this
+        #[arg(long)]
+        stop_loss: Option<f64>,
+        /// Trigger when the feed price rises to or above this
+        #[arg(long)]
+        take_profit: Option<f64>,
+        /// Unix time a limit or conditional intent expires (0 = never)
+        #[arg(long, default_value_t = 0)]
+        expires_at: i64,
+    },
+    /// Trigger a conditional intent whose price feed crossed a trigger (any signer)
+    TriggerIntent {
+        #[arg(long)]
+        intent: Pubkey,
+    },
+    /// Cancel one of the signer's open intents, refunding any keeper tip
+    CancelIntent {
+        #[arg(long)]
+        intent: Pubkey,
+    },
+    /// Add a keeper tip to one of the signer's open intents, in lamports or `--mint` tokens
+    TipIntent {
+        #[arg(long)]
+        intent: Pubkey,
+        #[arg(long)]
+        amount: u64,
+        /// Tip in this token instead of lamports
+        #[arg(long)]
+        mint: Option<Pubkey>,
+    },
+    /// Execute a pending intent, or the next slice of a TWAP intent (signer must be a registered executor);
+    /// a token tip needs the signer's ATA for the tip mint
+    Execute {
+        #[arg(long)]
+        intent: Pubkey,
+        #[arg(long, value_parser = parse_hex32)]
+        encrypted_params: [u8; 32],
+        #[arg(long, value_parser = parse_hex64)]
+        mpc_proof: [u8; 64],
+        #[arg(long, value_parser = parse_hex32)]
+        computation_id: [u8; 32],
+        /// Part of a limit order to fill; defaults to all that remains
+        #[arg(long)]
+        fill_amount: Option<u64>,
+    },
+    /// Set pause flags (owner or guardian)
+    Pause(PauseArgs),
+    /// Clear pause flags (owner or guardian)
+    Unpause(PauseArgs),
+    /// Print a vault's state
+    ShowVault {
+        #[arg(long)]
+        vault: Pubkey,
+    },
+    /// List open trade intents on a vault
+    ListIntents {
+        #[arg(long)]
+        vault: Pubkey,
+    },
+    /// Print a vault's recent executions, newest first
+    RecentExecutions {
+        #[arg(long)]
+        vault: Pubkey,
+    },
+}
+
+#[derive(Args)]
+pub struct VaultMint {
+    #[arg(long)]
+    pub vault: Pubkey,
+    #[arg(long)]
+    pub mint: Pubkey,
+}
+
+#[derive(Args)]
+pub struct PauseArgs {
+    #[arg(long)]
+    pub vault: Pubkey,
+    /// Comma-separated: deposits, intents, executions, withdrawals, or all
+    #[arg(long, default_value = "all", value_parser = crate::output::parse_pause_flags)]
+    pub flags: u8,
+}
+
+pub fn parse_hex<const N: usize>(s: &str) -> Result<[u8; N], String> {
+    let bytes = hex::decode(s.trim_start_matches("0x")).map_err(|e| e.to_string())?;
+    bytes
+        .try_into()
+        .map_err(|b: Vec<u8>| format!("expected {N} bytes, got {}", b.len()))
+}
+
+pub fn parse_hex32(s: &str) -> Result<[u8; 32], String> {
+    parse_hex(s)
+}
+
+pub fn parse_hex64(s: &str) -> Result<[u8; 64], String> {
+    parse_hex(s)
+}
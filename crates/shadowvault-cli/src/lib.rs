@@ -0,0 +1,10 @@
+// ============================================================================
+// ShadowVault CLI - argument parsing and JSON output
+// ============================================================================
+//
+// The binary in `main.rs` wires these to RPC; keeping them in the library
+// lets the tests exercise parsing and rendering without a validator.
+// ============================================================================
+
+pub mod args;
+pub mod output;
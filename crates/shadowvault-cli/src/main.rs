@@ -0,0 +1,294 @@
+// ============================================================================
+// ShadowVault CLI - operate vaults from the command line
+// ============================================================================
+//
+// Every subcommand prints a single JSON object on stdout; errors go to stderr
+// with a non-zero exit code. Defaults target a local test validator.
+//
+//   shadowvault-cli init-vault --strategy-hash <hex32>
+//   shadowvault-cli deposit --vault <pk> --mint <pk> --amount <n>
+//   shadowvault-cli pause --vault <pk> --flags deposits,withdrawals
+//   shadowvault-cli show-vault --vault <pk>
+// ============================================================================
+
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
+use serde_json::{json, Value};
+use shadowvault_cli::args::{Cli, Command, PauseArgs};
+use shadowvault_cli::output;
+use shadowvault_client::state::{IntentKind, PRICE_SCALE};
+use shadowvault_client::{accounts, instructions, pda, views};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+fn main() {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(value) => println!("{}", serde_json::to_string_pretty(&value).unwrap()),
+        Err(e) => {
+            eprintln!("{}", json!({ "error": format!("{e:#}") }));
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<Value> {
+    let rpc = RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed());
+
+    // read-only commands need no keypair
+    match cli.command {
+        Command::ShowVault { vault } => {
+            let state = accounts::fetch_vault(&rpc, &vault)?;
+            return Ok(output::vault(&vault, &state));
+        }
+        Command::ListIntents { vault } => {
+            let intents = accounts::fetch_intents_for_vault(&rpc, &vault)?;
+            return Ok(json!({
+                "vault": vault.to_string(),
+                "intents": output::open_intents(&intents),
+            }));
+        }
+        Command::RecentExecutions { vault } => {
+            let history = accounts::fetch_execution_history(&rpc, &vault)?;
+            return Ok(json!({
+                "vault": vault.to_string(),
+                "total": history.as_ref().map_or(0, |h| h.total),
+                "executions": history
+                    .iter()
+                    .flat_map(|h| h.recent())
+                    .map(output::execution)
+                    .collect::<Vec<_>>(),
+            }));
+        }
+        _ => {}
+    }
+
+    let signer = load_keypair(&cli.keypair)?;
+    let me = signer.pubkey();
+
+    match cli.command {
+        Command::InitVault { strategy_hash } => {
+            let vault_id = accounts::next_vault_id(&rpc, &me)?;
+            let (vault, _) = pda::vault(&me, vault_id);
+            let ix = instructions::initialize_vault(&me, &me, vault_id, strategy_hash);
+            let signature = send(&rpc, &signer, ix)?;
+            Ok(json!({ "signature": signature, "vault": vault.to_string(), "vault_id": vault_id }))
+        }
+        Command::Deposit { target, amount, proof } => {
+            let ix = instructions::deposit(&me, &target.vault, &target.mint, amount, proof);
+            let signature = send(&rpc, &signer, ix)?;
+            let position = accounts::fetch_position(&rpc, &target.vault, &target.mint, &me)?;
+            Ok(json!({
+                "signature": signature,
+                "vault": target.vault.to_string(),
+                "shares": position.map_or(0, |p| p.shares),
+            }))
+        }
+        Command::Withdraw { target, amount } => {
+            let ix = instructions::withdraw(&me, &target.vault, &target.mint, amount);
+            let signature = send(&rpc, &signer, ix)?;
+            let outcome = views::withdraw_outcome(&rpc, &signature.parse()?)?;
+            if outcome.breaker_tripped {
+                return Err(anyhow!(
+                    "circuit breaker tripped: vault paused, nothing withdrawn (transaction {signature})"
+                ));
+            }
+            Ok(json!({ "signature": signature, "vault": target.vault.to_string(), "amount": outcome.withdrawn }))
+        }
+        Command::SubmitIntent {
+            vault,
+            token_in,
+            token_out,
+            amount,
+            max_slippage_bps,
+            strategy_type,
+            slices,
+            interval_secs,
+            limit_price,
+            stop_loss,
+            take_profit,
+            expires_at,
+        } => {
+            let intent_id = accounts::next_intent_id(&rpc, &me, &vault)?;
+            let conditional = stop_loss.is_some() || take_profit.is_some();
+            if [slices > 1, limit_price.is_some(), conditional].iter().filter(|&&set| set).count() > 1 {
+                return Err(anyhow!(
+                    "--slices, --limit-price and --stop-loss/--take-profit are mutually exclusive"
+                ));
+            }
+            let ix = if conditional {
+                instructions::submit_conditional_intent(
+                    &me,
+                    &vault,
+                    intent_id,
+                    &token_in,
+                    &token_out,
+                    amount,
+                    max_slippage_bps,
+                    strategy_type,
+                    stop_loss.map_or(0, scale_price),
+                    take_profit.map_or(0, scale_price),
+                    expires_at,
+                )
+            } else if let Some(price) = limit_price {
+                instructions::submit_limit_intent(
+                    &me,
+                    &vault,
+                    intent_id,
+                    &token_in,
+                    &token_out,
+                    amount,
+                    max_slippage_bps,
+                    strategy_type,
+                    scale_price(price),
+                    expires_at,
+                )
+            } else if slices > 1 {
+                instructions::submit_twap_intent(
+                    &me,
+                    &vault,
+                    intent_id,
+                    &token_in,
+                    &token_out,
+                    amount,
+                    max_slippage_bps,
+                    strategy_type,
+                    slices,
+                    interval_secs,
+                )
+            } else {
+                instructions::submit_trade_intent(
+                    &me,
+                    &vault,
+                    intent_id,
+                    &token_in,
+                    &token_out,
+                    amount,
+                    max_slippage_bps,
+                    strategy_type,
+                )
+            };
+            let signature = send(&rpc, &signer, ix)?;
+            let (intent, _) = pda::intent(&me, &vault, intent_id);
+            Ok(json!({ "signature": signature, "intent": intent.to_string(), "intent_id": intent_id }))
+        }
+        Command::Execute {
+            intent: intent_key,
+            encrypted_params,
+            mpc_proof,
+            computation_id,
+            fill_amount,
+        } => {
+            let intent = accounts::fetch_intent(&rpc, &intent_key)?;
+            if intent.kind == IntentKind::Twap {
+                let ix = instructions::execute_twap_slice(&me, &intent, encrypted_params, mpc_proof);
+                let signature = send(&rpc, &signer, ix)?;
+                let (fill, _) = pda::twap_fill(&intent_key, intent.slices_done);
+                return Ok(json!({
+                    "signature": signature,
+                    "intent": intent_key.to_string(),
+                    "slice": intent.slices_done,
+                    "fill": fill.to_string(),
+                }));
+            }
+            let mut ix = instructions::execute_trade(
+                &me,
+                &intent.vault,
+                &intent.user,
+                intent.intent_id,
+                &intent.token_in,
+                &intent.token_out,
+                intent.strategy_type,
+                encrypted_params,
+                mpc_proof,
+                computation_id,
+                fill_amount.unwrap_or(intent.remaining_amount),
+            );
+            ix.accounts.extend(instructions::tip_accounts(&intent, &me));
+            let signature = send(&rpc, &signer, ix)?;
+            let (result, _) = pda::result(&intent_key);
+            Ok(json!({ "signature": signature, "intent": intent_key.to_string(), "result": result.to_string() }))
+        }
+        Command::TriggerIntent { intent: intent_key } => {
+            let intent = accounts::fetch_intent(&rpc, &intent_key)?;
+            let ix = instructions::trigger_intent(&me, &intent);
+            let signature = send(&rpc, &signer, ix)?;
+            let intent = accounts::fetch_intent(&rpc, &intent_key)?;
+            Ok(json!({
+                "signature": signature,
+                "intent": intent_key.to_string(),
+                "triggered": intent.triggered.map(|leg| format!("{leg:?}")),
+            }))
+        }
+        Command::CancelIntent { intent: intent_key } => {
+            let intent = accounts::fetch_intent(&rpc, &intent_key)?;
+            let mut ix = instructions::cancel_intent(&me, &intent.vault, intent.intent_id);
+            ix.accounts.extend(instructions::tip_accounts(&intent, &me));
+            let signature = send(&rpc, &signer, ix)?;
+            Ok(json!({
+                "signature": signature,
+                "intent": intent_key.to_string(),
+                "tip_refunded": intent.tip_amount,
+            }))
+        }
+        Command::TipIntent {
+            intent: intent_key,
+            amount,
+            mint,
+        } => {
+            let intent = accounts::fetch_intent(&rpc, &intent_key)?;
+            let ix = match mint {
+                Some(mint) => instructions::tip_intent_tokens(&me, &intent.vault, intent.intent_id, &mint, amount),
+                None => instructions::tip_intent_lamports(&me, &intent.vault, intent.intent_id, amount),
+            };
+            let signature = send(&rpc, &signer, ix)?;
+            let intent = accounts::fetch_intent(&rpc, &intent_key)?;
+            Ok(json!({
+                "signature": signature,
+                "intent": intent_key.to_string(),
+                "tip_amount": intent.tip_amount,
+            }))
+        }
+        Command::Pause(args) => toggle_pause(&rpc, &signer, args, true),
+        Command::Unpause(args) => toggle_pause(&rpc, &signer, args, false),
+        Command::ShowVault { .. } | Command::ListIntents { .. } | Command::RecentExecutions { .. } => {
+            unreachable!("handled above")
+        }
+    }
+}
+
+/// Decimal price to `PRICE_SCALE` units
+fn scale_price(price: f64) -> u64 {
+    (price * PRICE_SCALE as f64).round() as u64
+}
+
+fn toggle_pause(rpc: &RpcClient, signer: &Keypair, args: PauseArgs, pause: bool) -> Result<Value> {
+    let ix = instructions::pause_vault(&signer.pubkey(), &args.vault, args.flags, pause);
+    let signature = send(rpc, signer, ix)?;
+    let state = accounts::fetch_vault(rpc, &args.vault)?;
+    Ok(json!({
+        "signature": signature,
+        "vault": args.vault.to_string(),
+        "pause_flags": output::pause_flag_names(state.pause_flags),
+    }))
+}
+
+fn send(rpc: &RpcClient, signer: &Keypair, ix: Instruction) -> Result<String> {
+    let blockhash = rpc.get_latest_blockhash().context("fetching blockhash")?;
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&signer.pubkey()), &[signer], blockhash);
+    let signature = rpc
+        .send_and_confirm_transaction(&tx)
+        .context("sending transaction")?;
+    Ok(signature.to_string())
+}
+
+fn load_keypair(path: &str) -> Result<Keypair> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => format!("{}/{rest}", std::env::var("HOME").unwrap_or_default()),
+        None => path.to_string(),
+    };
+    read_keypair_file(&path).map_err(|e| anyhow!("reading keypair {path}: {e}"))
+}
@@ -0,0 +1,111 @@
+//! JSON rendering of program accounts. Keys print as base58, hashes as hex.
+
+use serde_json::{json, Value};
+use shadowvault_client::state::{ExecutionRecord, PauseFlags, TradeIntent, VaultAccount, PRICE_SCALE};
+use solana_sdk::pubkey::Pubkey;
+
+const FLAG_NAMES: [(u8, &str); 4] = [
+    (PauseFlags::DEPOSITS, "deposits"),
+    (PauseFlags::INTENTS, "intents"),
+    (PauseFlags::EXECUTIONS, "executions"),
+    (PauseFlags::WITHDRAWALS, "withdrawals"),
+];
+
+/// Parses a comma-separated flag list (`deposits,withdrawals`), `all`, or a
+/// raw bitmask
+pub fn parse_pause_flags(s: &str) -> Result<u8, String> {
+    if let Ok(bits) = s.parse::<u8>() {
+        return Ok(bits);
+    }
+    s.split(',').try_fold(0u8, |acc, name| {
+        let name = name.trim();
+        if name == "all" {
+            return Ok(acc | PauseFlags::ALL);
+        }
+        FLAG_NAMES
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(bit, _)| acc | bit)
+            .ok_or_else(|| format!("unknown pause flag `{name}`"))
+    })
+}
+
+pub fn pause_flag_names(flags: u8) -> Vec<&'static str> {
+    FLAG_NAMES
+        .iter()
+        .filter(|(bit, _)| flags & bit != 0)
+        .map(|(_, n)| *n)
+        .collect()
+}
+
+pub fn vault(address: &Pubkey, v: &VaultAccount) -> Value {
+    json!({
+        "address": address.to_string(),
+        "owner": v.owner.to_string(),
+        "vault_id": v.vault_id,
+        "guardian": v.guardian.to_string(),
+        "encrypted_strategy_hash": hex::encode(v.encrypted_strategy_hash),
+        "tvl": v.tvl,
+        "execution_count": v.execution_count,
+        "pause_flags": pause_flag_names(v.pause_flags),
+        "circuit_breaker": {
+            "window_secs": v.circuit_breaker.window_secs,
+            "threshold_bps": v.circuit_breaker.threshold_bps,
+            "window_start": v.circuit_breaker.window_start,
+            "window_outflow": v.circuit_breaker.window_outflow,
+        },
+        "custody_mint_count": v.custody_mint_count,
+        "allowlist_enabled": v.allowlist_enabled,
+        "allowlist_root": hex::encode(v.allowlist_root),
+        "event_seq": v.event_seq,
+    })
+}
+
+pub fn intent(address: &Pubkey, i: &TradeIntent) -> Value {
+    json!({
+        "address": address.to_string(),
+        "user": i.user.to_string(),
+        "vault": i.vault.to_string(),
+        "intent_id": i.intent_id,
+        "token_in": i.token_in.to_string(),
+        "token_out": i.token_out.to_string(),
+        "amount": i.amount,
+        "max_slippage_bps": i.max_slippage_bps,
+        "strategy_type": i.strategy_type,
+        "timestamp": i.timestamp,
+        "kind": format!("{:?}", i.kind),
+        "slices": i.slices,
+        "slices_done": i.slices_done,
+        "remaining_amount": i.remaining_amount,
+        "next_execution_ts": i.next_execution_ts,
+        "limit_price": i.limit_price as f64 / PRICE_SCALE as f64,
+        "expires_at": i.expires_at,
+        "cancelled": i.cancelled,
+        "stop_loss_price": i.stop_loss_price as f64 / PRICE_SCALE as f64,
+        "take_profit_price": i.take_profit_price as f64 / PRICE_SCALE as f64,
+        "triggered": i.triggered.map(|leg| format!("{leg:?}")),
+        "tip_mint": i.tip_mint.map(|m| m.to_string()),
+        "tip_amount": i.tip_amount,
+    })
+}
+
+/// Renders the intents that can still fill: not cancelled, amount left
+pub fn open_intents(intents: &[(Pubkey, TradeIntent)]) -> Vec<Value> {
+    intents
+        .iter()
+        .filter(|(_, i)| !i.cancelled && !i.is_filled())
+        .map(|(k, i)| intent(k, i))
+        .collect()
+}
+
+pub fn execution(r: &ExecutionRecord) -> Value {
+    json!({
+        "intent": r.intent.to_string(),
+        "token_in": r.token_in.to_string(),
+        "token_out": r.token_out.to_string(),
+        "executed_amount": r.executed_amount,
+        "received_amount": r.received_amount,
+        "price": r.price as f64 / PRICE_SCALE as f64,
+        "slot": r.slot,
+    })
+}
@@ -0,0 +1,136 @@
+use clap::Parser;
+use shadowvault::state::{IntentKind, PauseFlags, TradeIntent, TriggerLeg, PRICE_SCALE};
+use shadowvault_cli::args::{Cli, Command};
+use shadowvault_cli::output;
+use solana_sdk::pubkey::Pubkey;
+
+fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+    Cli::try_parse_from(std::iter::once("shadowvault-cli").chain(args.iter().copied()))
+}
+
+fn intent(remaining_amount: u64, cancelled: bool) -> TradeIntent {
+    TradeIntent {
+        user: Pubkey::new_unique(),
+        vault: Pubkey::new_unique(),
+        intent_id: 3,
+        token_in: Pubkey::new_unique(),
+        token_out: Pubkey::new_unique(),
+        amount: 1_000,
+        max_slippage_bps: 100,
+        strategy_type: 0,
+        timestamp: 0,
+        bump: 255,
+        kind: IntentKind::Limit,
+        slices: 1,
+        slice_interval_secs: 0,
+        remaining_amount,
+        slices_done: 0,
+        next_execution_ts: 0,
+        limit_price: PRICE_SCALE * 3 / 2,
+        expires_at: 0,
+        cancelled,
+        stop_loss_price: 0,
+        take_profit_price: 0,
+        triggered: None,
+        tip_mint: None,
+        tip_amount: 0,
+    }
+}
+
+#[test]
+fn deposit_parses_repeated_proofs_and_global_flags() {
+    let (vault, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let node = "ab".repeat(32);
+    let cli = parse(&[
+        "-u",
+        "http://rpc",
+        "deposit",
+        "--vault",
+        &vault.to_string(),
+        "--mint",
+        &mint.to_string(),
+        "--amount",
+        "500",
+        "--proof",
+        &node,
+        "--proof",
+        &format!("0x{node}"),
+        "--keypair",
+        "/tmp/id.json",
+    ])
+    .unwrap();
+    assert_eq!((cli.url.as_str(), cli.keypair.as_str()), ("http://rpc", "/tmp/id.json"));
+    let Command::Deposit { target, amount, proof } = cli.command else {
+        panic!("expected deposit");
+    };
+    assert_eq!((target.vault, target.mint, amount), (vault, mint, 500));
+    assert_eq!(proof, vec![[0xab; 32]; 2]);
+}
+
+#[test]
+fn submit_intent_defaults_and_hex_validation() {
+    let vault = Pubkey::new_unique().to_string();
+    let (token_in, token_out) = (Pubkey::new_unique().to_string(), Pubkey::new_unique().to_string());
+    let base = ["submit-intent", "--vault", &vault, "--token-in", &token_in, "--token-out", &token_out];
+
+    let cli = parse(&[&base[..], &["--amount", "100"]].concat()).unwrap();
+    let Command::SubmitIntent {
+        max_slippage_bps,
+        slices,
+        limit_price,
+        stop_loss,
+        expires_at,
+        ..
+    } = cli.command
+    else {
+        panic!("expected submit-intent");
+    };
+    assert_eq!((max_slippage_bps, slices, expires_at), (100, 1, 0));
+    assert_eq!((limit_price, stop_loss), (None, None));
+
+    assert!(parse(&[&base[..], &["--amount", "-1"]].concat()).is_err());
+    assert!(parse(&base).is_err());
+
+    let err = parse(&["init-vault", "--strategy-hash", &"00".repeat(31)]).err().unwrap();
+    assert!(err.to_string().contains("expected 32 bytes, got 31"));
+    assert!(parse(&["init-vault", "--strategy-hash", "zz"]).is_err());
+}
+
+#[test]
+fn pause_flags_parse_names_all_and_bitmasks() {
+    let vault = Pubkey::new_unique().to_string();
+    let flags = |extra: &[&str]| match parse(&[&["pause", "--vault", &vault][..], extra].concat()).unwrap().command {
+        Command::Pause(args) => args.flags,
+        _ => panic!("expected pause"),
+    };
+    assert_eq!(flags(&[]), PauseFlags::ALL);
+    assert_eq!(flags(&["--flags", "deposits, withdrawals"]), PauseFlags::DEPOSITS | PauseFlags::WITHDRAWALS);
+    assert_eq!(flags(&["--flags", "6"]), 6);
+
+    let err = parse(&["unpause", "--vault", &vault, "--flags", "deposits,trades"]).err().unwrap();
+    assert!(err.to_string().contains("unknown pause flag `trades`"));
+
+    assert_eq!(output::pause_flag_names(PauseFlags::ALL), ["deposits", "intents", "executions", "withdrawals"]);
+    assert!(output::pause_flag_names(0).is_empty());
+}
+
+#[test]
+fn list_intents_renders_only_open_intents() {
+    let (open, filled, cancelled) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut tipped = intent(400, false);
+    tipped.tip_mint = Some(Pubkey::new_unique());
+    tipped.triggered = Some(TriggerLeg::TakeProfit);
+    let intents = [(open, tipped.clone()), (filled, intent(0, false)), (cancelled, intent(400, true))];
+
+    let rendered = output::open_intents(&intents);
+    assert_eq!(rendered.len(), 1);
+    let json = &rendered[0];
+    assert_eq!(json["address"], open.to_string());
+    assert_eq!(json["intent_id"], 3);
+    assert_eq!(json["kind"], "Limit");
+    assert_eq!(json["remaining_amount"], 400);
+    assert_eq!(json["limit_price"], 1.5);
+    assert_eq!(json["stop_loss_price"], 0.0);
+    assert_eq!(json["triggered"], "TakeProfit");
+    assert_eq!(json["tip_mint"], tipped.tip_mint.unwrap().to_string());
+}
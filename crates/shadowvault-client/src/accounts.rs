@@ -0,0 +1,158 @@
+//! Fetch and decode program accounts. Decoding checks the Anchor account
+//! discriminator, so passing the wrong address fails loudly instead of
+//! returning garbage.
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use shadowvault::state::{
+    CustodyLedger, ExecutionHistory, ExecutionResult, OwnerVaultCounter, PriceFeed, ProtocolConfig,
+    StrategyConfig, TradeIntent, TradePair, TwapFill, UserIntentCounter, UserPosition, VaultAccount,
+    WithdrawalRateLimit,
+};
+use shadowvault::ID;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{ClientError, Result};
+use crate::pda;
+
+/// Decodes raw account data as `T`, checking its discriminator
+pub fn decode<T: AccountDeserialize>(address: &Pubkey, data: &[u8]) -> Result<T> {
+    let mut data = data;
+    T::try_deserialize(&mut data).map_err(|e| ClientError::AccountDecode(*address, e.to_string()))
+}
+
+/// Fetches and decodes one account; errors if it does not exist
+pub fn fetch<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    fetch_optional(rpc, address)?.ok_or(ClientError::AccountNotFound(*address))
+}
+
+/// Fetches and decodes one account; `None` if it does not exist
+pub fn fetch_optional<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<Option<T>> {
+    let account = rpc
+        .get_account_with_commitment(address, rpc.commitment())?
+        .value;
+    account.map(|a| decode(address, &a.data)).transpose()
+}
+
+pub fn fetch_protocol_config(rpc: &RpcClient) -> Result<ProtocolConfig> {
+    fetch(rpc, &pda::protocol_config().0)
+}
+
+pub fn fetch_vault(rpc: &RpcClient, vault: &Pubkey) -> Result<VaultAccount> {
+    fetch(rpc, vault)
+}
+
+pub fn fetch_intent(rpc: &RpcClient, intent: &Pubkey) -> Result<TradeIntent> {
+    fetch(rpc, intent)
+}
+
+pub fn fetch_result(rpc: &RpcClient, result: &Pubkey) -> Result<ExecutionResult> {
+    fetch(rpc, result)
+}
+
+pub fn fetch_twap_fill(rpc: &RpcClient, intent: &Pubkey, slice: u16) -> Result<TwapFill> {
+    fetch(rpc, &pda::twap_fill(intent, slice).0)
+}
+
+/// The vault's recent executions; `None` before its first execution
+pub fn fetch_execution_history(rpc: &RpcClient, vault: &Pubkey) -> Result<Option<ExecutionHistory>> {
+    fetch_optional(rpc, &pda::execution_history(vault).0)
+}
+
+pub fn fetch_ledger(rpc: &RpcClient, vault: &Pubkey, mint: &Pubkey) -> Result<CustodyLedger> {
+    fetch(rpc, &pda::ledger(vault, mint).0)
+}
+
+pub fn fetch_position(
+    rpc: &RpcClient,
+    vault: &Pubkey,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Result<Option<UserPosition>> {
+    fetch_optional(rpc, &pda::position(vault, mint, owner).0)
+}
+
+pub fn fetch_rate_limit(rpc: &RpcClient, vault: &Pubkey, mint: &Pubkey) -> Result<Option<WithdrawalRateLimit>> {
+    fetch_optional(rpc, &pda::rate_limit(vault, mint).0)
+}
+
+pub fn fetch_trade_pair(
+    rpc: &RpcClient,
+    vault: &Pubkey,
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+) -> Result<Option<TradePair>> {
+    fetch_optional(rpc, &pda::trade_pair(vault, token_in, token_out).0)
+}
+
+pub fn fetch_strategy_config(rpc: &RpcClient, vault: &Pubkey, strategy_type: u8) -> Result<Option<StrategyConfig>> {
+    fetch_optional(rpc, &pda::strategy_config(vault, strategy_type).0)
+}
+
+pub fn fetch_price_feed(rpc: &RpcClient, token_in: &Pubkey, token_out: &Pubkey) -> Result<Option<PriceFeed>> {
+    fetch_optional(rpc, &pda::price_feed(token_in, token_out).0)
+}
+
+/// Id the owner's next vault will take; 0 before their first vault
+pub fn next_vault_id(rpc: &RpcClient, owner: &Pubkey) -> Result<u64> {
+    let counter: Option<OwnerVaultCounter> = fetch_optional(rpc, &pda::owner_counter(owner).0)?;
+    Ok(counter.map_or(0, |c| c.next_vault_id))
+}
+
+/// Id the user's next intent on `vault` will take
+pub fn next_intent_id(rpc: &RpcClient, user: &Pubkey, vault: &Pubkey) -> Result<u64> {
+    let counter: Option<UserIntentCounter> = fetch_optional(rpc, &pda::intent_counter(user, vault).0)?;
+    Ok(counter.map_or(0, |c| c.next_intent_id))
+}
+
+/// All accounts of type `T` matching `filters`; the discriminator filter is
+/// added here
+fn fetch_filtered<T>(rpc: &RpcClient, mut filters: Vec<RpcFilterType>) -> Result<Vec<(Pubkey, T)>>
+where
+    T: AccountDeserialize + Discriminator,
+{
+    filters.insert(0, RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &T::discriminator())));
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(rpc.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    rpc.get_program_accounts_with_config(&ID, config)?
+        .into_iter()
+        .map(|(address, account)| Ok((address, decode(&address, &account.data)?)))
+        .collect()
+}
+
+/// All accounts of type `T` whose bytes at `offset` (after the 8-byte
+/// discriminator) equal `key`
+fn fetch_by_key<T>(rpc: &RpcClient, offset: usize, key: &Pubkey) -> Result<Vec<(Pubkey, T)>>
+where
+    T: AccountDeserialize + Discriminator,
+{
+    fetch_filtered(
+        rpc,
+        vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8 + offset, key.as_ref()))],
+    )
+}
+
+/// Every trade intent in the program
+pub fn fetch_all_intents(rpc: &RpcClient) -> Result<Vec<(Pubkey, TradeIntent)>> {
+    fetch_filtered(rpc, Vec::new())
+}
+
+/// Trade intents on a vault, open or not (`TradeIntent.vault` follows `user`)
+pub fn fetch_intents_for_vault(rpc: &RpcClient, vault: &Pubkey) -> Result<Vec<(Pubkey, TradeIntent)>> {
+    fetch_by_key(rpc, 32, vault)
+}
+
+/// Vaults owned by `owner` (`VaultAccount.owner` is the first field)
+pub fn fetch_vaults_for_owner(rpc: &RpcClient, owner: &Pubkey) -> Result<Vec<(Pubkey, VaultAccount)>> {
+    fetch_by_key(rpc, 0, owner)
+}
@@ -0,0 +1,40 @@
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("RPC request failed: {0}")]
+    Rpc(Box<solana_client::client_error::ClientError>),
+
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+
+    #[error("account {0} could not be decoded: {1}")]
+    AccountDecode(Pubkey, String),
+
+    #[error("transaction has no status metadata")]
+    MissingMeta,
+
+    #[error("transaction encoding is not binary; request base64")]
+    UnsupportedEncoding,
+
+    #[error("invalid address in transaction metadata: {0}")]
+    InvalidAddress(String),
+
+    #[error("simulation failed: {0}")]
+    Simulation(String),
+
+    #[error("instruction returned no data")]
+    MissingReturnData,
+
+    #[error("return data could not be decoded: {0}")]
+    ReturnDataDecode(String),
+}
+
+impl From<solana_client::client_error::ClientError> for ClientError {
+    fn from(e: solana_client::client_error::ClientError) -> Self {
+        ClientError::Rpc(Box::new(e))
+    }
+}
@@ -0,0 +1,105 @@
+//! Event extraction from confirmed transactions. Core vault events travel as
+//! event CPI inner instructions; admin/risk events as `Program data:` logs.
+//! Both are attributed to this program before decoding, so another program
+//! cannot inject look-alike events.
+
+use base64::Engine;
+use shadowvault::event_decoder::{decode_event, decode_inner_instructions, ShadowVaultEvent};
+use shadowvault::ID;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiInnerInstructions, UiInstruction,
+    UiLoadedAddresses, UiTransactionStatusMeta,
+};
+use std::str::FromStr;
+
+use crate::error::{ClientError, Result};
+
+/// All shadowvault events in a transaction fetched with base64 (or base58)
+/// encoding: event CPI payloads first, then log events. Within one vault,
+/// order by `seq`. Failed transactions yield no events.
+pub fn parse_transaction(tx: &EncodedConfirmedTransactionWithStatusMeta) -> Result<Vec<ShadowVaultEvent>> {
+    let meta = tx.transaction.meta.as_ref().ok_or(ClientError::MissingMeta)?;
+    if meta.err.is_some() {
+        return Ok(Vec::new());
+    }
+
+    let decoded = tx
+        .transaction
+        .transaction
+        .decode()
+        .ok_or(ClientError::UnsupportedEncoding)?;
+    let mut account_keys = decoded.message.static_account_keys().to_vec();
+    account_keys.extend(loaded_addresses(meta)?);
+
+    let mut events = parse_inner_instructions(&account_keys, meta);
+    let logs: Option<&Vec<String>> = meta.log_messages.as_ref().into();
+    events.extend(parse_logs(logs.map_or(&[][..], |l| l.as_slice())));
+    Ok(events)
+}
+
+/// Address lookup table keys, in the order the runtime appends them
+fn loaded_addresses(meta: &UiTransactionStatusMeta) -> Result<Vec<Pubkey>> {
+    let loaded: Option<&UiLoadedAddresses> = meta.loaded_addresses.as_ref().into();
+    let Some(loaded) = loaded else {
+        return Ok(Vec::new());
+    };
+    loaded
+        .writable
+        .iter()
+        .chain(loaded.readonly.iter())
+        .map(|k| Pubkey::from_str(k).map_err(|_| ClientError::InvalidAddress(k.clone())))
+        .collect()
+}
+
+/// Event CPI payloads from the transaction's inner instructions
+pub fn parse_inner_instructions(account_keys: &[Pubkey], meta: &UiTransactionStatusMeta) -> Vec<ShadowVaultEvent> {
+    let inner: Option<&Vec<UiInnerInstructions>> = meta.inner_instructions.as_ref().into();
+    let compiled: Vec<(Pubkey, Vec<u8>)> = inner
+        .into_iter()
+        .flatten()
+        .flat_map(|set| set.instructions.iter())
+        .filter_map(|ix| match ix {
+            UiInstruction::Compiled(c) => {
+                let program_id = *account_keys.get(c.program_id_index as usize)?;
+                let data = solana_sdk::bs58::decode(&c.data).into_vec().ok()?;
+                Some((program_id, data))
+            }
+            UiInstruction::Parsed(_) => None,
+        })
+        .collect();
+
+    decode_inner_instructions(compiled.iter().map(|(p, d)| (p, d.as_slice())))
+}
+
+/// `emit!` events from program logs. Tracks the invoke stack so only
+/// `Program data:` lines written while this program is executing count.
+pub fn parse_logs(logs: &[String]) -> Vec<ShadowVaultEvent> {
+    let program_id = ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        if let Some(data) = rest.strip_prefix("data: ") {
+            if stack.last() == Some(&program_id.as_str()) {
+                if let Some(event) = base64::engine::general_purpose::STANDARD
+                    .decode(data)
+                    .ok()
+                    .and_then(|bytes| decode_event(&bytes))
+                {
+                    events.push(event);
+                }
+            }
+        } else if let Some((id, tail)) = rest.split_once(' ') {
+            if tail.starts_with("invoke [") {
+                stack.push(id);
+            } else if tail == "success" || tail.starts_with("failed") {
+                stack.pop();
+            }
+        }
+    }
+    events
+}
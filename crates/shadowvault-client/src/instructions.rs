@@ -0,0 +1,840 @@
+//! Typed builders for every instruction in `#[program] shadowvault`. Each
+//! builder derives the PDAs and ATAs the instruction needs, so callers pass
+//! only the keys that carry intent (signers, vault, mints) plus arguments.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token;
+use shadowvault::state::TradeIntent;
+use shadowvault::{accounts, instruction, ID};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+// ============================================================================
+// Vault lifecycle
+// ============================================================================
+
+/// Creates the owner's next vault. `vault_id` must equal the owner's
+/// `OwnerVaultCounter::next_vault_id` (0 before the first vault); see
+/// `accounts::next_vault_id`.
+pub fn initialize_vault(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    vault_id: u64,
+    encrypted_strategy_hash: [u8; 32],
+) -> Instruction {
+    build(
+        accounts::InitializeVault {
+            payer: *payer,
+            owner: *owner,
+            config: pda::protocol_config().0,
+            owner_counter: pda::owner_counter(owner).0,
+            vault: pda::vault(owner, vault_id).0,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::InitializeVault { encrypted_strategy_hash },
+    )
+}
+
+pub fn deposit(
+    user: &Pubkey,
+    vault: &Pubkey,
+    token_mint: &Pubkey,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    build(
+        accounts::Deposit {
+            user: *user,
+            config: pda::protocol_config().0,
+            token_mint: *token_mint,
+            user_ata: get_associated_token_address(user, token_mint),
+            vault: *vault,
+            vault_ata: get_associated_token_address(vault, token_mint),
+            ledger: pda::ledger(vault, token_mint).0,
+            position: pda::position(vault, token_mint, user).0,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::Deposit { amount, proof },
+    )
+}
+
+pub fn deposit_for(
+    payer: &Pubkey,
+    vault: &Pubkey,
+    token_mint: &Pubkey,
+    beneficiary: &Pubkey,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    build(
+        accounts::DepositFor {
+            payer: *payer,
+            config: pda::protocol_config().0,
+            token_mint: *token_mint,
+            payer_ata: get_associated_token_address(payer, token_mint),
+            vault: *vault,
+            vault_ata: get_associated_token_address(vault, token_mint),
+            ledger: pda::ledger(vault, token_mint).0,
+            position: pda::position(vault, token_mint, beneficiary).0,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::DepositFor {
+            amount,
+            beneficiary: *beneficiary,
+            proof,
+        },
+    )
+}
+
+/// Market intent; `intent_id` is the user's next id on the vault
+/// (`accounts::next_intent_id`)
+#[allow(clippy::too_many_arguments)]
+pub fn submit_trade_intent(
+    user: &Pubkey,
+    vault: &Pubkey,
+    intent_id: u64,
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    amount: u64,
+    max_slippage_bps: u16,
+    strategy_type: u8,
+) -> Instruction {
+    build(
+        accounts::SubmitTradeIntent {
+            user: *user,
+            config: pda::protocol_config().0,
+            vault: *vault,
+            intent_counter: pda::intent_counter(user, vault).0,
+            intent: pda::intent(user, vault, intent_id).0,
+            pair: pda::trade_pair(vault, token_in, token_out).0,
+            strategy: pda::strategy_config(vault, strategy_type).0,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SubmitTradeIntent {
+            token_in: *token_in,
+            token_out: *token_out,
+            amount,
+            max_slippage_bps,
+            strategy_type,
+        },
+    )
+}
+
+/// TWAP/DCA intent: `amount` in `slices` parts at least `interval_secs` apart
+#[allow(clippy::too_many_arguments)]
+pub fn submit_twap_intent(
+    user: &Pubkey,
+    vault: &Pubkey,
+    intent_id: u64,
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    amount: u64,
+    max_slippage_bps: u16,
+    strategy_type: u8,
+    slices: u16,
+    interval_secs: i64,
+) -> Instruction {
+    build(
+        accounts::SubmitTradeIntent {
+            user: *user,
+            config: pda::protocol_config().0,
+            vault: *vault,
+            intent_counter: pda::intent_counter(user, vault).0,
+            intent: pda::intent(user, vault, intent_id).0,
+            pair: pda::trade_pair(vault, token_in, token_out).0,
+            strategy: pda::strategy_config(vault, strategy_type).0,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SubmitTwapIntent {
+            token_in: *token_in,
+            token_out: *token_out,
+            amount,
+            max_slippage_bps,
+            strategy_type,
+            slices,
+            interval_secs,
+        },
+    )
+}
+
+/// Limit order: fills only at `limit_price` (token_out per token_in, scaled
+/// by `PRICE_SCALE`) or better; `expires_at` 0 never expires
+#[allow(clippy::too_many_arguments)]
+pub fn submit_limit_intent(
+    user: &Pubkey,
+    vault: &Pubkey,
+    intent_id: u64,
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    amount: u64,
+    max_slippage_bps: u16,
+    strategy_type: u8,
+    limit_price: u64,
+    expires_at: i64,
+) -> Instruction {
+    build(
+        accounts::SubmitTradeIntent {
+            user: *user,
+            config: pda::protocol_config().0,
+            vault: *vault,
+            intent_counter: pda::intent_counter(user, vault).0,
+            intent: pda::intent(user, vault, intent_id).0,
+            pair: pda::trade_pair(vault, token_in, token_out).0,
+            strategy: pda::strategy_config(vault, strategy_type).0,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SubmitLimitIntent {
+            token_in: *token_in,
+            token_out: *token_out,
+            amount,
+            max_slippage_bps,
+            strategy_type,
+            limit_price,
+            expires_at,
+        },
+    )
+}
+
+/// Stop-loss and/or take-profit intent, triggered off the pair's price
+/// feed; 0 omits a leg, both set make an OCO pair
+#[allow(clippy::too_many_arguments)]
+pub fn submit_conditional_intent(
+    user: &Pubkey,
+    vault: &Pubkey,
+    intent_id: u64,
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    amount: u64,
+    max_slippage_bps: u16,
+    strategy_type: u8,
+    stop_loss_price: u64,
+    take_profit_price: u64,
+    expires_at: i64,
+) -> Instruction {
+    build(
+        accounts::SubmitTradeIntent {
+            user: *user,
+            config: pda::protocol_config().0,
+            vault: *vault,
+            intent_counter: pda::intent_counter(user, vault).0,
+            intent: pda::intent(user, vault, intent_id).0,
+            pair: pda::trade_pair(vault, token_in, token_out).0,
+            strategy: pda::strategy_config(vault, strategy_type).0,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SubmitConditionalIntent {
+            token_in: *token_in,
+            token_out: *token_out,
+            amount,
+            max_slippage_bps,
+            strategy_type,
+            stop_loss_price,
+            take_profit_price,
+            expires_at,
+        },
+    )
+}
+
+/// Triggers `intent`, a fetched conditional intent, against its pair's
+/// price feed; any keeper may sign
+pub fn trigger_intent(keeper: &Pubkey, intent: &TradeIntent) -> Instruction {
+    build(
+        accounts::TriggerIntent {
+            keeper: *keeper,
+            config: pda::protocol_config().0,
+            vault: intent.vault,
+            intent: pda::intent(&intent.user, &intent.vault, intent.intent_id).0,
+            price_feed: pda::price_feed(&intent.token_in, &intent.token_out).0,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::TriggerIntent {},
+    )
+}
+
+/// Cancels `user`'s open intent `intent_id` on `vault`. A token tip is
+/// refunded only with `tip_accounts(intent, user)` appended.
+pub fn cancel_intent(user: &Pubkey, vault: &Pubkey, intent_id: u64) -> Instruction {
+    build(
+        accounts::CancelIntent {
+            user: *user,
+            vault: *vault,
+            intent: pda::intent(user, vault, intent_id).0,
+            token_program: token::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::CancelIntent {},
+    )
+}
+
+/// Adds `amount` lamports to the keeper tip on `user`'s intent `intent_id`
+pub fn tip_intent_lamports(user: &Pubkey, vault: &Pubkey, intent_id: u64, amount: u64) -> Instruction {
+    build(
+        accounts::TipIntentLamports {
+            user: *user,
+            config: pda::protocol_config().0,
+            vault: *vault,
+            intent: pda::intent(user, vault, intent_id).0,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::TipIntentLamports { amount },
+    )
+}
+
+/// Adds `amount` of `tip_mint` from the user's ATA to the keeper tip on
+/// `user`'s intent `intent_id`, escrowed in the intent's ATA
+pub fn tip_intent_tokens(
+    user: &Pubkey,
+    vault: &Pubkey,
+    intent_id: u64,
+    tip_mint: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let intent = pda::intent(user, vault, intent_id).0;
+    build(
+        accounts::TipIntentTokens {
+            user: *user,
+            config: pda::protocol_config().0,
+            vault: *vault,
+            intent,
+            tip_mint: *tip_mint,
+            user_ata: get_associated_token_address(user, tip_mint),
+            intent_ata: get_associated_token_address(&intent, tip_mint),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::TipIntentTokens { amount },
+    )
+}
+
+/// Remaining accounts that pay out `intent`'s token tip to `recipient`'s
+/// ATA and close the escrow to the intent owner: append to `execute_trade`
+/// (executor) or `cancel_intent` (user). Empty for lamport tips and
+/// untipped intents.
+pub fn tip_accounts(intent: &TradeIntent, recipient: &Pubkey) -> Vec<AccountMeta> {
+    match intent.tip_mint {
+        Some(mint) if intent.tip_amount > 0 => {
+            let intent_key = pda::intent(&intent.user, &intent.vault, intent.intent_id).0;
+            vec![
+                AccountMeta::new(get_associated_token_address(&intent_key, &mint), false),
+                AccountMeta::new(get_associated_token_address(recipient, &mint), false),
+                AccountMeta::new(intent.user, false),
+            ]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Executes `user`'s pending intent `intent_id` on `vault`.
+/// `token_in`/`token_out` and `strategy_type` are the intent's; custody
+/// accounts are the vault's ATAs for the mints. `fill_amount` is the
+/// intent's `remaining_amount` for market and conditional intents. Limit
+/// intents, and any intent on a vault with the circuit breaker enabled,
+/// fill with `execute_trade_with_feed`. Append
+/// `tip_accounts(intent, executor)` to collect a token tip.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_trade(
+    executor: &Pubkey,
+    vault: &Pubkey,
+    user: &Pubkey,
+    intent_id: u64,
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    strategy_type: u8,
+    encrypted_params: [u8; 32],
+    mpc_proof: [u8; 64],
+    computation_id: [u8; 32],
+    fill_amount: u64,
+) -> Instruction {
+    build_execute_trade(
+        executor,
+        vault,
+        pda::intent(user, vault, intent_id).0,
+        token_in,
+        token_out,
+        strategy_type,
+        None,
+        instruction::ExecuteTrade {
+            encrypted_params,
+            mpc_proof,
+            computation_id,
+            fill_amount,
+        },
+    )
+}
+
+/// `execute_trade` passing the pair's `PriceFeed`, which prices limit fills
+/// and values shortfalls for the circuit breaker. `fill_amount` is any part
+/// of `remaining_amount` for a limit intent
+#[allow(clippy::too_many_arguments)]
+pub fn execute_trade_with_feed(
+    executor: &Pubkey,
+    vault: &Pubkey,
+    user: &Pubkey,
+    intent_id: u64,
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    strategy_type: u8,
+    encrypted_params: [u8; 32],
+    mpc_proof: [u8; 64],
+    computation_id: [u8; 32],
+    fill_amount: u64,
+) -> Instruction {
+    build_execute_trade(
+        executor,
+        vault,
+        pda::intent(user, vault, intent_id).0,
+        token_in,
+        token_out,
+        strategy_type,
+        Some(pda::price_feed(token_in, token_out).0),
+        instruction::ExecuteTrade {
+            encrypted_params,
+            mpc_proof,
+            computation_id,
+            fill_amount,
+        },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_execute_trade(
+    executor: &Pubkey,
+    vault: &Pubkey,
+    intent: Pubkey,
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    strategy_type: u8,
+    price_feed: Option<Pubkey>,
+    data: instruction::ExecuteTrade,
+) -> Instruction {
+    build(
+        accounts::ExecuteTrade {
+            authority: *executor,
+            config: pda::protocol_config().0,
+            vault: *vault,
+            intent,
+            strategy: pda::strategy_config(vault, strategy_type).0,
+            price_feed,
+            vault_token_in: get_associated_token_address(vault, token_in),
+            vault_token_out: get_associated_token_address(vault, token_out),
+            result: pda::result(&intent).0,
+            history: pda::execution_history(vault).0,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        data,
+    )
+}
+
+/// Executes the next slice of `intent`, a fetched TWAP intent; its
+/// `slices_done` picks the fill record. Includes `tip_accounts` for the
+/// executor.
+pub fn execute_twap_slice(
+    executor: &Pubkey,
+    intent: &TradeIntent,
+    encrypted_params: [u8; 32],
+    mpc_proof: [u8; 64],
+) -> Instruction {
+    let vault = &intent.vault;
+    let intent_key = pda::intent(&intent.user, vault, intent.intent_id).0;
+    let mut ix = build(
+        accounts::ExecuteTwapSlice {
+            authority: *executor,
+            config: pda::protocol_config().0,
+            vault: *vault,
+            intent: intent_key,
+            strategy: pda::strategy_config(vault, intent.strategy_type).0,
+            vault_token_in: get_associated_token_address(vault, &intent.token_in),
+            vault_token_out: get_associated_token_address(vault, &intent.token_out),
+            fill: pda::twap_fill(&intent_key, intent.slices_done).0,
+            history: pda::execution_history(vault).0,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::ExecuteTwapSlice {
+            encrypted_params,
+            mpc_proof,
+        },
+    );
+    ix.accounts.extend(tip_accounts(intent, executor));
+    ix
+}
+
+/// Owner withdrawal to the owner's ATA for `token_mint`
+pub fn withdraw(owner: &Pubkey, vault: &Pubkey, token_mint: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::Withdraw {
+            authority: *owner,
+            config: pda::protocol_config().0,
+            token_mint: *token_mint,
+            vault: *vault,
+            owner: *owner,
+            vault_ata: get_associated_token_address(vault, token_mint),
+            recipient_ata: get_associated_token_address(owner, token_mint),
+            rate_limit: pda::rate_limit(vault, token_mint).0,
+            ledger: pda::ledger(vault, token_mint).0,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::Withdraw { amount },
+    )
+}
+
+// ============================================================================
+// Vault risk controls
+// ============================================================================
+
+/// Sets (`pause = true`) or clears the given `PauseFlags` bits
+pub fn pause_vault(authority: &Pubkey, vault: &Pubkey, flags: u8, pause: bool) -> Instruction {
+    build(
+        accounts::TogglePause {
+            authority: *authority,
+            vault: *vault,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::PauseVault { flags, pause },
+    )
+}
+
+pub fn set_guardian(owner: &Pubkey, vault: &Pubkey, guardian: &Pubkey) -> Instruction {
+    build(
+        accounts::SetGuardian {
+            owner: *owner,
+            vault: *vault,
+        },
+        instruction::SetGuardian { guardian: *guardian },
+    )
+}
+
+pub fn configure_circuit_breaker(
+    owner: &Pubkey,
+    vault: &Pubkey,
+    window_secs: i64,
+    threshold_bps: u16,
+) -> Instruction {
+    build(
+        accounts::ConfigureCircuitBreaker {
+            owner: *owner,
+            vault: *vault,
+        },
+        instruction::ConfigureCircuitBreaker {
+            window_secs,
+            threshold_bps,
+        },
+    )
+}
+
+pub fn set_withdrawal_limit(
+    owner: &Pubkey,
+    vault: &Pubkey,
+    token_mint: &Pubkey,
+    max_amount: u64,
+    max_bps: u16,
+) -> Instruction {
+    build(
+        accounts::SetWithdrawalLimit {
+            owner: *owner,
+            vault: *vault,
+            token_mint: *token_mint,
+            rate_limit: pda::rate_limit(vault, token_mint).0,
+            ledger: pda::ledger(vault, token_mint).0,
+            system_program: system_program::ID,
+        },
+        instruction::SetWithdrawalLimit { max_amount, max_bps },
+    )
+}
+
+/// Reconciles every custody mint of the vault. `mints` must cover all of
+/// the vault's ledgers (`custody_mint_count`); order does not matter, the
+/// builder sorts them as the program requires.
+pub fn reconcile(caller: &Pubkey, vault: &Pubkey, mints: &[Pubkey], resync: bool) -> Instruction {
+    let mut ix = build(
+        accounts::Reconcile {
+            caller: *caller,
+            vault: *vault,
+        },
+        instruction::Reconcile { resync },
+    );
+
+    let mut mints = mints.to_vec();
+    mints.sort();
+    mints.dedup();
+    for mint in mints {
+        ix.accounts.push(AccountMeta {
+            pubkey: pda::ledger(vault, &mint).0,
+            is_signer: false,
+            is_writable: resync,
+        });
+        ix.accounts.push(AccountMeta::new_readonly(
+            get_associated_token_address(vault, &mint),
+            false,
+        ));
+    }
+    ix
+}
+
+/// Moves `token_mint`'s parked surplus into its ledger balance
+pub fn allocate_surplus(owner: &Pubkey, vault: &Pubkey, token_mint: &Pubkey) -> Instruction {
+    build(
+        accounts::AllocateSurplus {
+            owner: *owner,
+            vault: *vault,
+            token_mint: *token_mint,
+            ledger: pda::ledger(vault, token_mint).0,
+            vault_ata: get_associated_token_address(vault, token_mint),
+        },
+        instruction::AllocateSurplus {},
+    )
+}
+
+pub fn set_allowlist(owner: &Pubkey, vault: &Pubkey, root: [u8; 32], enabled: bool) -> Instruction {
+    build(
+        accounts::SetAllowlist {
+            owner: *owner,
+            vault: *vault,
+        },
+        instruction::SetAllowlist { root, enabled },
+    )
+}
+
+pub fn set_trade_pair(
+    owner: &Pubkey,
+    vault: &Pubkey,
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    enabled: bool,
+    max_amount: u64,
+    max_slippage_bps: u16,
+) -> Instruction {
+    build(
+        accounts::SetTradePair {
+            owner: *owner,
+            vault: *vault,
+            token_in_mint: *token_in,
+            token_out_mint: *token_out,
+            pair: pda::trade_pair(vault, token_in, token_out).0,
+            system_program: system_program::ID,
+        },
+        instruction::SetTradePair {
+            enabled,
+            max_amount,
+            max_slippage_bps,
+        },
+    )
+}
+
+/// `allowed_pairs` lists `TradePair` addresses (see `pda::trade_pair`);
+/// empty admits every pair the vault enables
+#[allow(clippy::too_many_arguments)]
+pub fn set_strategy_config(
+    owner: &Pubkey,
+    vault: &Pubkey,
+    strategy_type: u8,
+    enabled: bool,
+    allowed_pairs: Vec<Pubkey>,
+    max_amount: u64,
+    max_slippage_bps: u16,
+    cooldown_secs: i64,
+) -> Instruction {
+    build(
+        accounts::SetStrategyConfig {
+            owner: *owner,
+            vault: *vault,
+            strategy: pda::strategy_config(vault, strategy_type).0,
+            system_program: system_program::ID,
+        },
+        instruction::SetStrategyConfig {
+            strategy_type,
+            enabled,
+            allowed_pairs,
+            max_amount,
+            max_slippage_bps,
+            cooldown_secs,
+        },
+    )
+}
+
+// ============================================================================
+// Protocol administration
+// ============================================================================
+
+pub fn initialize_protocol(admin: &Pubkey, protocol_fee_bps: u16, fee_recipient: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeProtocol {
+            admin: *admin,
+            program_data: pda::program_data().0,
+            config: pda::protocol_config().0,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeProtocol {
+            protocol_fee_bps,
+            fee_recipient: *fee_recipient,
+        },
+    )
+}
+
+fn update_protocol_config(admin: &Pubkey) -> accounts::UpdateProtocolConfig {
+    accounts::UpdateProtocolConfig {
+        admin: *admin,
+        config: pda::protocol_config().0,
+    }
+}
+
+pub fn set_protocol_fee(admin: &Pubkey, protocol_fee_bps: u16, fee_recipient: &Pubkey) -> Instruction {
+    build(
+        update_protocol_config(admin),
+        instruction::SetProtocolFee {
+            protocol_fee_bps,
+            fee_recipient: *fee_recipient,
+        },
+    )
+}
+
+pub fn set_protocol_pause(admin: &Pubkey, paused: bool) -> Instruction {
+    build(update_protocol_config(admin), instruction::SetProtocolPause { paused })
+}
+
+pub fn set_executor(admin: &Pubkey, executor: &Pubkey, enabled: bool) -> Instruction {
+    build(
+        update_protocol_config(admin),
+        instruction::SetExecutor {
+            executor: *executor,
+            enabled,
+        },
+    )
+}
+
+pub fn transfer_protocol_admin(admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    build(
+        update_protocol_config(admin),
+        instruction::TransferProtocolAdmin { new_admin: *new_admin },
+    )
+}
+
+/// Posts `price` (token_out per token_in, scaled by `PRICE_SCALE`)
+pub fn set_price_feed(admin: &Pubkey, token_in: &Pubkey, token_out: &Pubkey, price: u64) -> Instruction {
+    build(
+        accounts::SetPriceFeed {
+            admin: *admin,
+            config: pda::protocol_config().0,
+            token_in_mint: *token_in,
+            token_out_mint: *token_out,
+            price_feed: pda::price_feed(token_in, token_out).0,
+            system_program: system_program::ID,
+        },
+        instruction::SetPriceFeed { price },
+    )
+}
+
+pub fn accept_protocol_admin(new_admin: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptProtocolAdmin {
+            new_admin: *new_admin,
+            config: pda::protocol_config().0,
+        },
+        instruction::AcceptProtocolAdmin {},
+    )
+}
+
+// ============================================================================
+// Views (simulate; results come back as return data, see `views`)
+// ============================================================================
+
+/// Summarizes the vault and the ledgers of `mints`, in the order given
+pub fn get_vault_summary(vault: &Pubkey, mints: &[Pubkey]) -> Instruction {
+    let mut ix = build(
+        accounts::GetVaultSummary {
+            config: pda::protocol_config().0,
+            vault: *vault,
+        },
+        instruction::GetVaultSummary {},
+    );
+    for mint in mints {
+        ix.accounts
+            .push(AccountMeta::new_readonly(pda::ledger(vault, mint).0, false));
+    }
+    ix
+}
+
+pub fn get_position(vault: &Pubkey, token_mint: &Pubkey, owner: &Pubkey) -> Instruction {
+    build(
+        accounts::GetPosition {
+            vault: *vault,
+            token_mint: *token_mint,
+            ledger: pda::ledger(vault, token_mint).0,
+            position: pda::position(vault, token_mint, owner).0,
+        },
+        instruction::GetPosition { owner: *owner },
+    )
+}
+
+pub fn preview_deposit(vault: &Pubkey, token_mint: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::PreviewDeposit {
+            config: pda::protocol_config().0,
+            vault: *vault,
+            token_mint: *token_mint,
+            ledger: pda::ledger(vault, token_mint).0,
+        },
+        instruction::PreviewDeposit { amount },
+    )
+}
+
+pub fn preview_withdraw(vault: &Pubkey, token_mint: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::PreviewWithdraw {
+            config: pda::protocol_config().0,
+            vault: *vault,
+            token_mint: *token_mint,
+            ledger: pda::ledger(vault, token_mint).0,
+            vault_ata: get_associated_token_address(vault, token_mint),
+            rate_limit: pda::rate_limit(vault, token_mint).0,
+        },
+        instruction::PreviewWithdraw { amount },
+    )
+}
@@ -0,0 +1,26 @@
+// ============================================================================
+// ShadowVault Client - Rust SDK for the ShadowVault program
+// ============================================================================
+//
+// Everything an off-chain consumer needs without re-deriving program details:
+// - pda: seed derivation for every program account (mirrors state.rs)
+// - instructions: typed builders for every instruction in `#[program]`
+// - accounts: fetch and decode program accounts over RPC
+// - events: extract events from confirmed transaction metadata
+// - views: simulate view instructions and decode their return data
+//
+// Seeds, discriminators and account layouts come from the `shadowvault`
+// crate itself, so the client cannot drift from the deployed program.
+// ============================================================================
+
+pub mod accounts;
+pub mod error;
+pub mod events;
+pub mod instructions;
+pub mod pda;
+pub mod views;
+
+pub use error::{ClientError, Result};
+pub use shadowvault::event_decoder::ShadowVaultEvent;
+pub use shadowvault::state;
+pub use shadowvault::ID as PROGRAM_ID;
@@ -0,0 +1,106 @@
+//! Program-derived addresses. Seeds mirror the `#[account(seeds = ...)]`
+//! constraints in the program; prefixes come from `state.rs` constants.
+
+use shadowvault::state::{
+    CustodyLedger, ExecutionHistory, ExecutionResult, OwnerVaultCounter, PriceFeed, ProtocolConfig,
+    StrategyConfig, TradeIntent, TradePair, TwapFill, UserIntentCounter, UserPosition, VaultAccount,
+    WithdrawalRateLimit,
+};
+use shadowvault::ID;
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::pubkey::Pubkey;
+
+/// Protocol config singleton: [b"protocol_config"]
+pub fn protocol_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ProtocolConfig::SEED_PREFIX], &ID)
+}
+
+/// This program's ProgramData under the upgradeable loader: [program_id]
+pub fn program_data() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ID.as_ref()], &bpf_loader_upgradeable::ID)
+}
+
+/// Per-owner vault id allocator: [b"owner_vaults", owner]
+pub fn owner_counter(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[OwnerVaultCounter::SEED_PREFIX, owner.as_ref()], &ID)
+}
+
+/// Vault: [b"vault", owner, vault_id (u64 LE)]
+pub fn vault(owner: &Pubkey, vault_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VaultAccount::SEED_PREFIX, owner.as_ref(), &vault_id.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// Per-user intent id allocator on a vault: [b"user_intents", user, vault]
+pub fn intent_counter(user: &Pubkey, vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[UserIntentCounter::SEED_PREFIX, user.as_ref(), vault.as_ref()], &ID)
+}
+
+/// Trade intent: [b"intent", user, vault, intent_id (u64 LE)]
+pub fn intent(user: &Pubkey, vault: &Pubkey, intent_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TradeIntent::SEED_PREFIX, user.as_ref(), vault.as_ref(), &intent_id.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// Execution result: [b"result", intent]
+pub fn result(intent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ExecutionResult::SEED_PREFIX, intent.as_ref()], &ID)
+}
+
+/// TWAP slice fill: [b"fill", intent, slice (u16 LE)]
+pub fn twap_fill(intent: &Pubkey, slice: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TwapFill::SEED_PREFIX, intent.as_ref(), &slice.to_le_bytes()], &ID)
+}
+
+/// Execution history ring: [b"execution_history", vault]
+pub fn execution_history(vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ExecutionHistory::SEED_PREFIX, vault.as_ref()], &ID)
+}
+
+/// Custody ledger: [b"ledger", vault, mint]
+pub fn ledger(vault: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CustodyLedger::SEED_PREFIX, vault.as_ref(), mint.as_ref()], &ID)
+}
+
+/// Share position: [b"position", vault, mint, owner]
+pub fn position(vault: &Pubkey, mint: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[UserPosition::SEED_PREFIX, vault.as_ref(), mint.as_ref(), owner.as_ref()],
+        &ID,
+    )
+}
+
+/// Withdrawal rate limit: [b"withdraw_limit", vault, mint]
+pub fn rate_limit(vault: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[WithdrawalRateLimit::SEED_PREFIX, vault.as_ref(), mint.as_ref()],
+        &ID,
+    )
+}
+
+/// Trade pair: [b"pair", vault, token_in, token_out]
+pub fn trade_pair(vault: &Pubkey, token_in: &Pubkey, token_out: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TradePair::SEED_PREFIX, vault.as_ref(), token_in.as_ref(), token_out.as_ref()],
+        &ID,
+    )
+}
+
+/// Strategy config: [b"strategy", vault, strategy_type]
+pub fn strategy_config(vault: &Pubkey, strategy_type: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[StrategyConfig::SEED_PREFIX, vault.as_ref(), &[strategy_type]], &ID)
+}
+
+/// Price feed: [b"price_feed", token_in, token_out]
+pub fn price_feed(token_in: &Pubkey, token_out: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PriceFeed::SEED_PREFIX, token_in.as_ref(), token_out.as_ref()], &ID)
+}
+
+/// Event CPI signer: [b"__event_authority"]
+pub fn event_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"__event_authority"], &ID)
+}
@@ -0,0 +1,104 @@
+//! Simulate the program's view instructions and decode their return data.
+//! Views write nothing and nothing is signed, but simulation still needs a
+//! fee payer that exists on chain; any funded account will do. `withdraw`
+//! also returns data, read back from the confirmed transaction.
+
+use anchor_lang::AnchorDeserialize;
+use base64::Engine;
+use shadowvault::{DepositPreview, PositionView, VaultSummary, WithdrawOutcome, WithdrawPreview, ID};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcSimulateTransactionConfig, RpcTransactionConfig};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::program::MAX_RETURN_DATA;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::{UiTransactionEncoding, UiTransactionReturnData};
+
+use crate::error::{ClientError, Result};
+use crate::instructions;
+
+/// Decodes view return data. The runtime drops trailing zero bytes, so the
+/// data is padded back out before decoding.
+pub fn decode_return_data<T: AnchorDeserialize>(data: &[u8]) -> Result<T> {
+    let mut padded = data.to_vec();
+    padded.resize(MAX_RETURN_DATA.max(data.len()), 0);
+    T::deserialize(&mut padded.as_slice()).map_err(|e| ClientError::ReturnDataDecode(e.to_string()))
+}
+
+/// Simulates `ix` paid by `payer` and decodes its return data as `T`
+pub fn simulate<T: AnchorDeserialize>(rpc: &RpcClient, payer: &Pubkey, ix: Instruction) -> Result<T> {
+    let tx = Transaction::new_with_payer(&[ix], Some(payer));
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(rpc.commitment()),
+        ..Default::default()
+    };
+    let result = rpc.simulate_transaction_with_config(&tx, config)?.value;
+    if let Some(err) = result.err {
+        let logs = result.logs.unwrap_or_default().join("\n");
+        return Err(ClientError::Simulation(format!("{err}\n{logs}")));
+    }
+
+    decode_ui_return_data(result.return_data.as_ref())
+}
+
+/// What the confirmed `withdraw` transaction `signature` did; a tripped
+/// circuit breaker succeeds without moving funds
+pub fn withdraw_outcome(rpc: &RpcClient, signature: &Signature) -> Result<WithdrawOutcome> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(rpc.commitment()),
+        max_supported_transaction_version: Some(0),
+    };
+    let tx = rpc.get_transaction_with_config(signature, config)?;
+    let meta = tx.transaction.meta.as_ref().ok_or(ClientError::MissingMeta)?;
+    decode_ui_return_data(meta.return_data.as_ref().into())
+}
+
+/// Decodes return data as RPC reports it, if this program set it
+fn decode_ui_return_data<T: AnchorDeserialize>(return_data: Option<&UiTransactionReturnData>) -> Result<T> {
+    let (data, _) = &return_data
+        .filter(|r| r.program_id == ID.to_string())
+        .ok_or(ClientError::MissingReturnData)?
+        .data;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| ClientError::ReturnDataDecode(e.to_string()))?;
+    decode_return_data(&bytes)
+}
+
+pub fn vault_summary(rpc: &RpcClient, payer: &Pubkey, vault: &Pubkey, mints: &[Pubkey]) -> Result<VaultSummary> {
+    simulate(rpc, payer, instructions::get_vault_summary(vault, mints))
+}
+
+pub fn position(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    vault: &Pubkey,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Result<PositionView> {
+    simulate(rpc, payer, instructions::get_position(vault, mint, owner))
+}
+
+pub fn preview_deposit(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    vault: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<DepositPreview> {
+    simulate(rpc, payer, instructions::preview_deposit(vault, mint, amount))
+}
+
+pub fn preview_withdraw(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    vault: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<WithdrawPreview> {
+    simulate(rpc, payer, instructions::preview_withdraw(vault, mint, amount))
+}
@@ -0,0 +1,46 @@
+//! Execution attestations. The executor signs
+//! `intent ++ encrypted_params ++ computation_id` with its registered key and
+//! passes the ed25519 signature as `mpc_proof`, so anyone holding the
+//! executor set can check who vouched for an execution and for what plan.
+
+use solana_sdk::hash::hashv;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Attestation {
+    pub encrypted_params: [u8; 32],
+    pub mpc_proof: [u8; 64],
+    pub computation_id: [u8; 32],
+}
+
+/// Deterministic per intent submission, so retries reuse the same id
+pub fn computation_id(intent: &Pubkey, intent_timestamp: i64) -> [u8; 32] {
+    hashv(&[b"shadowvault-computation", intent.as_ref(), &intent_timestamp.to_le_bytes()]).to_bytes()
+}
+
+fn message(intent: &Pubkey, encrypted_params: &[u8; 32], computation_id: &[u8; 32]) -> Vec<u8> {
+    [intent.as_ref(), encrypted_params, computation_id].concat()
+}
+
+pub fn attest(
+    executor: &Keypair,
+    intent: &Pubkey,
+    intent_timestamp: i64,
+    encrypted_params: [u8; 32],
+) -> Attestation {
+    let computation_id = computation_id(intent, intent_timestamp);
+    let signature = executor.sign_message(&message(intent, &encrypted_params, &computation_id));
+    Attestation {
+        encrypted_params,
+        mpc_proof: signature.into(),
+        computation_id,
+    }
+}
+
+pub fn verify(executor: &Pubkey, intent: &Pubkey, attestation: &Attestation) -> bool {
+    Signature::from(attestation.mpc_proof).verify(
+        executor.as_ref(),
+        &message(intent, &attestation.encrypted_params, &attestation.computation_id),
+    )
+}
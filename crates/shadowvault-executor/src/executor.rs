@@ -0,0 +1,276 @@
+//! Polling loop core. Each `tick` finds unfilled intents that are due, asks
+//! the strategy about them and submits `execute_trade` (or, for TWAP
+//! intents, `execute_twap_slice`) for approved ones. Stop-loss/take-profit
+//! intents count as due once their price feed crosses a trigger, and are
+//! triggered and executed in one transaction; limit intents once it reaches
+//! their limit, as the program prices their fills at it. The feed also goes
+//! along on vaults whose circuit breaker values trade shortfalls. Keeper
+//! tips are collected with the completing fill. Failed submissions retry
+//! with jittered exponential backoff up to a cap.
+
+use anchor_spl::token;
+use anyhow::{Context, Result};
+use shadowvault_client::state::IntentKind;
+use shadowvault_client::{accounts, instructions};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::hash::hashv;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::attestation;
+use crate::strategy::{Decision, PendingIntent, Strategy};
+
+#[derive(Clone, Debug)]
+pub struct ExecutorConfig {
+    /// Only serve these vaults; empty serves every vault
+    pub vaults: Vec<Pubkey>,
+    /// Submissions per intent before giving up
+    pub max_attempts: u32,
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Up to this share of each backoff, in bps, is taken off so intents that
+    /// failed together do not retry in lockstep
+    pub jitter_bps: u16,
+}
+
+impl Default for ExecutorConfig {
+    fn default() -> Self {
+        Self {
+            vaults: Vec::new(),
+            max_attempts: 5,
+            base_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            jitter_bps: 2_000,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Outcome {
+    Executed { intent: Pubkey, signature: Signature },
+    Skipped { intent: Pubkey, reason: String },
+    Failed { intent: Pubkey, attempt: u32, error: String, retry_in: Duration },
+    GaveUp { intent: Pubkey, attempts: u32, error: String },
+}
+
+#[derive(Clone, Debug)]
+struct RetryState {
+    attempts: u32,
+    next_attempt: Instant,
+    gave_up: bool,
+}
+
+pub struct Executor<S> {
+    rpc: RpcClient,
+    signer: Keypair,
+    strategy: S,
+    config: ExecutorConfig,
+    retries: HashMap<Pubkey, RetryState>,
+    // last skip reason per intent, so unchanged skips are reported once
+    skipped: HashMap<Pubkey, String>,
+}
+
+impl<S: Strategy> Executor<S> {
+    pub fn new(rpc: RpcClient, signer: Keypair, strategy: S, config: ExecutorConfig) -> Self {
+        Self {
+            rpc,
+            signer,
+            strategy,
+            config,
+            retries: HashMap::new(),
+            skipped: HashMap::new(),
+        }
+    }
+
+    pub fn executor_key(&self) -> Pubkey {
+        self.signer.pubkey()
+    }
+
+    /// Intents in scope that are not filled and whose next slice is due by
+    /// the local clock; the program re-checks against the chain clock
+    pub fn pending_intents(&self) -> Result<Vec<PendingIntent>> {
+        let intents = if self.config.vaults.is_empty() {
+            accounts::fetch_all_intents(&self.rpc)?
+        } else {
+            let mut all = Vec::new();
+            for vault in &self.config.vaults {
+                all.extend(accounts::fetch_intents_for_vault(&self.rpc, vault)?);
+            }
+            all
+        };
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("system clock before unix epoch")?
+            .as_secs() as i64;
+        let mut feeds = HashMap::new();
+        let mut pending = Vec::new();
+        for (address, intent) in intents {
+            if intent.cancelled || intent.is_filled() || intent.is_expired(now) || intent.next_execution_ts > now {
+                continue;
+            }
+            let untriggered = intent.kind == IntentKind::Conditional && intent.triggered.is_none();
+            if untriggered || intent.kind == IntentKind::Limit {
+                // stop-loss/take-profit intents wait for their feed to cross,
+                // limit intents for it to reach the price they fill at
+                let feed = match feeds.entry((intent.token_in, intent.token_out)) {
+                    Entry::Occupied(e) => e.into_mut(),
+                    Entry::Vacant(e) => {
+                        e.insert(accounts::fetch_price_feed(&self.rpc, &intent.token_in, &intent.token_out)?)
+                    }
+                };
+                let ready = feed.as_ref().is_some_and(|feed| {
+                    feed.is_fresh(now)
+                        && match intent.kind {
+                            IntentKind::Limit => feed.price >= intent.limit_price,
+                            _ => intent.trigger_leg(feed.price).is_some(),
+                        }
+                });
+                if !ready {
+                    continue;
+                }
+            }
+            pending.push(PendingIntent { address, intent });
+        }
+        Ok(pending)
+    }
+
+    /// One poll: evaluate and execute everything that is due
+    pub fn tick(&mut self) -> Result<Vec<Outcome>> {
+        let pending = self.pending_intents()?;
+
+        // forget state for intents that were executed or closed
+        self.retries.retain(|k, _| pending.iter().any(|p| p.address == *k));
+        self.skipped.retain(|k, _| pending.iter().any(|p| p.address == *k));
+
+        let now = Instant::now();
+        let mut outcomes = Vec::new();
+        for p in pending {
+            if let Some(state) = self.retries.get(&p.address) {
+                if state.gave_up || state.next_attempt > now {
+                    continue;
+                }
+            }
+
+            match self.strategy.evaluate(&p) {
+                Decision::Skip(reason) => {
+                    if self.skipped.get(&p.address) != Some(&reason) {
+                        self.skipped.insert(p.address, reason.clone());
+                        outcomes.push(Outcome::Skipped { intent: p.address, reason });
+                    }
+                }
+                Decision::Execute { encrypted_params } => {
+                    self.skipped.remove(&p.address);
+                    outcomes.push(self.execute(&p, encrypted_params));
+                }
+            }
+        }
+        Ok(outcomes)
+    }
+
+    fn execute(&mut self, p: &PendingIntent, encrypted_params: [u8; 32]) -> Outcome {
+        let attestation = attestation::attest(&self.signer, &p.address, p.intent.timestamp, encrypted_params);
+        match self.submit(p, &attestation) {
+            Ok(signature) => {
+                self.retries.remove(&p.address);
+                Outcome::Executed { intent: p.address, signature }
+            }
+            Err(e) => {
+                let error = format!("{e:#}");
+                let state = self.retries.entry(p.address).or_insert(RetryState {
+                    attempts: 0,
+                    next_attempt: Instant::now(),
+                    gave_up: false,
+                });
+                state.attempts += 1;
+                if state.attempts >= self.config.max_attempts {
+                    state.gave_up = true;
+                    return Outcome::GaveUp {
+                        intent: p.address,
+                        attempts: state.attempts,
+                        error,
+                    };
+                }
+                let retry_in = backoff(&self.config, state.attempts, &p.address);
+                state.next_attempt = Instant::now() + retry_in;
+                Outcome::Failed {
+                    intent: p.address,
+                    attempt: state.attempts,
+                    error,
+                    retry_in,
+                }
+            }
+        }
+    }
+
+    fn submit(&self, p: &PendingIntent, attestation: &attestation::Attestation) -> Result<Signature> {
+        let me = self.signer.pubkey();
+        let mut ixs = Vec::new();
+        if p.intent.kind == IntentKind::Conditional && p.intent.triggered.is_none() {
+            ixs.push(instructions::trigger_intent(&me, &p.intent));
+        }
+        // a token tip lands in our ATA for the tip mint
+        if let Some(mint) = p.intent.tip_mint.filter(|_| p.intent.tip_amount > 0) {
+            ixs.push(create_associated_token_account_idempotent(&me, &me, &mint, &token::ID));
+        }
+        ixs.push(match p.intent.kind {
+            IntentKind::Market | IntentKind::Limit | IntentKind::Conditional => {
+                // limit fills are priced at the pair's feed, and a circuit
+                // breaker values shortfalls against it
+                let priced = p.intent.kind == IntentKind::Limit
+                    || accounts::fetch_vault(&self.rpc, &p.intent.vault)?.circuit_breaker.is_enabled();
+                let execute = match priced {
+                    true => instructions::execute_trade_with_feed,
+                    false => instructions::execute_trade,
+                };
+                let mut ix = execute(
+                    &me,
+                    &p.intent.vault,
+                    &p.intent.user,
+                    p.intent.intent_id,
+                    &p.intent.token_in,
+                    &p.intent.token_out,
+                    p.intent.strategy_type,
+                    attestation.encrypted_params,
+                    attestation.mpc_proof,
+                    attestation.computation_id,
+                    p.intent.next_slice_amount(),
+                );
+                ix.accounts.extend(instructions::tip_accounts(&p.intent, &me));
+                ix
+            }
+            IntentKind::Twap => instructions::execute_twap_slice(
+                &me,
+                &p.intent,
+                attestation.encrypted_params,
+                attestation.mpc_proof,
+            ),
+        });
+        let blockhash = self.rpc.get_latest_blockhash().context("fetching blockhash")?;
+        let tx = Transaction::new_signed_with_payer(&ixs, Some(&self.signer.pubkey()), &[&self.signer], blockhash);
+        self.rpc
+            .send_and_confirm_transaction(&tx)
+            .context("submitting execution")
+    }
+}
+
+/// `base * 2^(attempt - 1)`, capped at `max_backoff`, less a jitter of up to
+/// `jitter_bps` of that. The jitter is derived from the intent and attempt,
+/// so it is stable for one retry yet differs between intents.
+pub fn backoff(config: &ExecutorConfig, attempt: u32, intent: &Pubkey) -> Duration {
+    let factor = 1u32.checked_shl(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+    let delay = config
+        .base_backoff
+        .checked_mul(factor)
+        .map_or(config.max_backoff, |d| d.min(config.max_backoff));
+
+    let roll = hashv(&[intent.as_ref(), &attempt.to_le_bytes()]).to_bytes();
+    let roll = u16::from_le_bytes([roll[0], roll[1]]) as u128 % 10_001;
+    let jitter = delay.as_nanos() * config.jitter_bps.min(10_000) as u128 * roll / 100_000_000;
+    delay.saturating_sub(Duration::from_nanos(u64::try_from(jitter).unwrap_or(u64::MAX)))
+}
@@ -0,0 +1,18 @@
+// ============================================================================
+// ShadowVault Executor - keeper for trade intents
+// ============================================================================
+//
+// Polls the program for unfilled trade intents that are due, runs each
+// through a pluggable `Strategy`, and submits attested `execute_trade`
+// transactions (one `execute_twap_slice` per due slice for TWAP intents),
+// retrying failures with jittered exponential backoff.
+//
+// The signing key must be registered in ProtocolConfig via `set_executor`.
+// ============================================================================
+
+pub mod attestation;
+pub mod executor;
+pub mod strategy;
+
+pub use executor::{backoff, Executor, ExecutorConfig, Outcome};
+pub use strategy::{Decision, MockAmm, PendingIntent, Quoter, SlippageGuard, Strategy};
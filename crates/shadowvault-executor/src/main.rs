@@ -0,0 +1,86 @@
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use serde_json::json;
+use shadowvault_executor::{Executor, ExecutorConfig, MockAmm, Outcome, SlippageGuard};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::read_keypair_file;
+use std::time::Duration;
+
+/// Executes pending ShadowVault trade intents; prints one JSON line per outcome
+#[derive(Parser)]
+#[command(name = "shadowvault-executor", version)]
+struct Args {
+    /// RPC endpoint
+    #[arg(long, short = 'u', env = "SHADOWVAULT_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Executor keypair; must be registered with `set_executor`
+    #[arg(long, short = 'k', env = "SHADOWVAULT_KEYPAIR", default_value = "~/.config/solana/id.json")]
+    keypair: String,
+
+    /// Serve only this vault (repeatable); default serves all vaults
+    #[arg(long = "vault")]
+    vaults: Vec<Pubkey>,
+
+    #[arg(long, default_value_t = 2_000)]
+    poll_ms: u64,
+
+    #[arg(long, default_value_t = 5)]
+    max_attempts: u32,
+
+    /// Mock AMM reserve per side, used to quote every pair
+    #[arg(long, default_value_t = 1_000_000_000_000)]
+    amm_reserve: u64,
+
+    #[arg(long, default_value_t = 30)]
+    amm_fee_bps: u16,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let keypair_path = match args.keypair.strip_prefix("~/") {
+        Some(rest) => format!("{}/{rest}", std::env::var("HOME").unwrap_or_default()),
+        None => args.keypair.clone(),
+    };
+    let signer = read_keypair_file(&keypair_path).map_err(|e| anyhow!("reading keypair {keypair_path}: {e}"))?;
+
+    let rpc = RpcClient::new_with_commitment(args.url, CommitmentConfig::confirmed());
+    let strategy = SlippageGuard {
+        quoter: MockAmm::uniform(args.amm_reserve, args.amm_fee_bps),
+    };
+    let config = ExecutorConfig {
+        vaults: args.vaults,
+        max_attempts: args.max_attempts,
+        ..ExecutorConfig::default()
+    };
+    let mut executor = Executor::new(rpc, signer, strategy, config);
+    println!("{}", json!({ "event": "started", "executor": executor.executor_key().to_string() }));
+
+    loop {
+        match executor.tick() {
+            Ok(outcomes) => outcomes.iter().for_each(|o| println!("{}", render(o))),
+            Err(e) => eprintln!("{}", json!({ "event": "poll_failed", "error": format!("{e:#}") })),
+        }
+        std::thread::sleep(Duration::from_millis(args.poll_ms));
+    }
+}
+
+fn render(outcome: &Outcome) -> serde_json::Value {
+    match outcome {
+        Outcome::Executed { intent, signature } => json!({
+            "event": "executed", "intent": intent.to_string(), "signature": signature.to_string(),
+        }),
+        Outcome::Skipped { intent, reason } => json!({
+            "event": "skipped", "intent": intent.to_string(), "reason": reason,
+        }),
+        Outcome::Failed { intent, attempt, error, retry_in } => json!({
+            "event": "failed", "intent": intent.to_string(), "attempt": attempt,
+            "error": error, "retry_in_ms": retry_in.as_millis() as u64,
+        }),
+        Outcome::GaveUp { intent, attempts, error } => json!({
+            "event": "gave_up", "intent": intent.to_string(), "attempts": attempts, "error": error,
+        }),
+    }
+}
@@ -0,0 +1,130 @@
+//! Pluggable execution policy. The executor asks a `Strategy` about every
+//! pending intent; the bundled `SlippageGuard` prices intents against a
+//! `Quoter` and only executes those that fit the user's slippage bound and,
+//! for limit orders, limit price.
+
+use shadowvault_client::state::{TradeIntent, PRICE_SCALE};
+use solana_sdk::hash::hashv;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+
+/// An unfilled intent whose next slice is due
+#[derive(Clone)]
+pub struct PendingIntent {
+    pub address: Pubkey,
+    pub intent: TradeIntent,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// Execute with this commitment to the routing plan as `encrypted_params`
+    Execute { encrypted_params: [u8; 32] },
+    /// Leave the intent pending; re-evaluated on later polls
+    Skip(String),
+}
+
+pub trait Strategy {
+    fn evaluate(&self, pending: &PendingIntent) -> Decision;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quote {
+    pub amount_out: u64,
+    // shortfall versus the pre-trade spot price, fees included
+    pub slippage_bps: u64,
+}
+
+/// Price source for a directional pair
+pub trait Quoter {
+    fn quote(&self, token_in: &Pubkey, token_out: &Pubkey, amount_in: u64) -> Option<Quote>;
+}
+
+/// In-memory constant-product pools for local testing. Pairs without an
+/// explicit pool fall back to `default_reserves` when set.
+#[derive(Clone, Debug, Default)]
+pub struct MockAmm {
+    pools: HashMap<(Pubkey, Pubkey), (u64, u64)>,
+    default_reserves: Option<(u64, u64)>,
+    fee_bps: u16,
+}
+
+impl MockAmm {
+    pub fn new(fee_bps: u16) -> Self {
+        Self {
+            fee_bps,
+            ..Self::default()
+        }
+    }
+
+    /// Quote every pair from one pool of `reserve` on each side
+    pub fn uniform(reserve: u64, fee_bps: u16) -> Self {
+        Self {
+            default_reserves: Some((reserve, reserve)),
+            ..Self::new(fee_bps)
+        }
+    }
+
+    pub fn with_pool(mut self, token_in: Pubkey, token_out: Pubkey, reserve_in: u64, reserve_out: u64) -> Self {
+        self.pools.insert((token_in, token_out), (reserve_in, reserve_out));
+        self
+    }
+}
+
+impl Quoter for MockAmm {
+    fn quote(&self, token_in: &Pubkey, token_out: &Pubkey, amount_in: u64) -> Option<Quote> {
+        let (reserve_in, reserve_out) = self
+            .pools
+            .get(&(*token_in, *token_out))
+            .copied()
+            .or(self.default_reserves)?;
+        if reserve_in == 0 || reserve_out == 0 || amount_in == 0 {
+            return None;
+        }
+
+        // x * y = k with the fee taken from the input
+        let in_after_fee = amount_in as u128 * (10_000 - self.fee_bps as u128) / 10_000;
+        let amount_out = reserve_out as u128 * in_after_fee / (reserve_in as u128 + in_after_fee);
+
+        // spot output at the pre-trade price, for the slippage figure
+        let spot_out = reserve_out as u128 * amount_in as u128 / reserve_in as u128;
+        let slippage_bps = ((spot_out - amount_out.min(spot_out)) * 10_000)
+            .checked_div(spot_out)
+            .unwrap_or(10_000);
+
+        Some(Quote {
+            amount_out: u64::try_from(amount_out).ok()?,
+            slippage_bps: slippage_bps as u64,
+        })
+    }
+}
+
+/// Executes only intents whose quoted slippage is within `max_slippage_bps`
+/// and, for limit orders, whose quote meets the limit price
+pub struct SlippageGuard<Q> {
+    pub quoter: Q,
+}
+
+impl<Q: Quoter> Strategy for SlippageGuard<Q> {
+    fn evaluate(&self, pending: &PendingIntent) -> Decision {
+        let i = &pending.intent;
+        let amount_in = i.next_slice_amount();
+        let Some(quote) = self.quoter.quote(&i.token_in, &i.token_out, amount_in) else {
+            return Decision::Skip("no quote for pair".into());
+        };
+        if quote.slippage_bps > i.max_slippage_bps as u64 {
+            return Decision::Skip(format!(
+                "quoted slippage {} bps exceeds max {} bps",
+                quote.slippage_bps, i.max_slippage_bps
+            ));
+        }
+        if i.limit_price > 0
+            && (quote.amount_out as u128) * (PRICE_SCALE as u128) < (i.limit_price as u128) * (amount_in as u128)
+        {
+            return Decision::Skip("quote below limit price".into());
+        }
+        Decision::Execute {
+            encrypted_params: hashv(&[pending.address.as_ref(), &quote.amount_out.to_le_bytes()])
+                .to_bytes(),
+        }
+    }
+}
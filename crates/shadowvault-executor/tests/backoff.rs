@@ -0,0 +1,46 @@
+use shadowvault_executor::{backoff, ExecutorConfig};
+use solana_sdk::pubkey::Pubkey;
+use std::time::Duration;
+
+fn config(jitter_bps: u16) -> ExecutorConfig {
+    ExecutorConfig {
+        base_backoff: Duration::from_secs(1),
+        max_backoff: Duration::from_secs(60),
+        jitter_bps,
+        ..ExecutorConfig::default()
+    }
+}
+
+#[test]
+fn backoff_doubles_per_attempt_up_to_the_cap() {
+    let (config, intent) = (config(0), Pubkey::new_unique());
+    let delays: Vec<u64> = (1..=8).map(|attempt| backoff(&config, attempt, &intent).as_secs()).collect();
+    assert_eq!(delays, [1, 2, 4, 8, 16, 32, 60, 60]);
+
+    // the shift and the multiplication overflow long after the cap
+    assert_eq!(backoff(&config, 40, &intent), config.max_backoff);
+    assert_eq!(backoff(&config, u32::MAX, &intent), config.max_backoff);
+    // attempt 0 is treated as the first
+    assert_eq!(backoff(&config, 0, &intent), config.base_backoff);
+}
+
+#[test]
+fn jitter_stays_within_its_share_of_the_delay() {
+    let config = config(2_000);
+    for attempt in [1, 4, 10] {
+        let full = backoff(&ExecutorConfig { jitter_bps: 0, ..config.clone() }, attempt, &Pubkey::default());
+        let delays: Vec<Duration> = (0..200).map(|_| backoff(&config, attempt, &Pubkey::new_unique())).collect();
+        assert!(delays.iter().all(|d| *d <= full && *d >= full.mul_f64(0.8)), "{full:?} {delays:?}");
+        // intents that failed together spread out
+        assert!(delays.iter().any(|d| *d < full.mul_f64(0.9)));
+        assert!(delays.iter().any(|d| *d > full.mul_f64(0.9)));
+    }
+
+    // stable for one intent and attempt
+    let intent = Pubkey::new_unique();
+    assert_eq!(backoff(&config, 3, &intent), backoff(&config, 3, &intent));
+
+    // a share above 100% still never goes below zero
+    let wild = ExecutorConfig { jitter_bps: u16::MAX, ..config };
+    assert!((0..50).all(|_| backoff(&wild, 2, &Pubkey::new_unique()) <= Duration::from_secs(2)));
+}
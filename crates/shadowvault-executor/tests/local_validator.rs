@@ -0,0 +1,136 @@
+//! End-to-end run against a local validator with the program deployed
+//! (`anchor localnet`). The keypair at SHADOWVAULT_KEYPAIR (default
+//! ~/.config/solana/id.json) must be the protocol admin, or the protocol must
+//! be uninitialized and the keypair the program's upgrade authority.
+//!
+//!   cargo test -p shadowvault-executor --test local_validator -- --ignored
+
+use anchor_spl::token::spl_token;
+use shadowvault_client::{accounts, instructions, pda};
+use shadowvault_executor::{Executor, ExecutorConfig, MockAmm, Outcome, SlippageGuard};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account;
+
+fn rpc() -> RpcClient {
+    let url = std::env::var("SHADOWVAULT_RPC_URL").unwrap_or_else(|_| "http://127.0.0.1:8899".into());
+    RpcClient::new_with_commitment(url, CommitmentConfig::confirmed())
+}
+
+fn admin() -> Keypair {
+    let path = std::env::var("SHADOWVAULT_KEYPAIR")
+        .unwrap_or_else(|_| format!("{}/.config/solana/id.json", std::env::var("HOME").unwrap()));
+    read_keypair_file(&path).expect("admin keypair")
+}
+
+fn send(rpc: &RpcClient, payer: &Keypair, ixs: &[Instruction], signers: &[&Keypair]) {
+    let blockhash = rpc.get_latest_blockhash().unwrap();
+    let mut all = vec![payer];
+    all.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(ixs, Some(&payer.pubkey()), &all, blockhash);
+    rpc.send_and_confirm_transaction(&tx).unwrap();
+}
+
+fn funded(rpc: &RpcClient) -> Keypair {
+    let kp = Keypair::new();
+    let sig = rpc.request_airdrop(&kp.pubkey(), 2_000_000_000).unwrap();
+    while !rpc.confirm_transaction(&sig).unwrap() {
+        std::thread::sleep(std::time::Duration::from_millis(200));
+    }
+    kp
+}
+
+fn create_mint(rpc: &RpcClient, payer: &Keypair) -> Pubkey {
+    let mint = Keypair::new();
+    let rent = rpc
+        .get_minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN)
+        .unwrap();
+    send(
+        rpc,
+        payer,
+        &[
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &mint.pubkey(),
+                rent,
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(&spl_token::ID, &mint.pubkey(), &payer.pubkey(), None, 6)
+                .unwrap(),
+        ],
+        &[&mint],
+    );
+    mint.pubkey()
+}
+
+#[test]
+#[ignore = "needs a local validator with shadowvault deployed"]
+fn executes_pending_intent() {
+    let rpc = rpc();
+    let admin = admin();
+    let executor = funded(&rpc);
+
+    if accounts::fetch_optional::<shadowvault_client::state::ProtocolConfig>(&rpc, &pda::protocol_config().0)
+        .unwrap()
+        .is_none()
+    {
+        send(&rpc, &admin, &[instructions::initialize_protocol(&admin.pubkey(), 0, &admin.pubkey())], &[]);
+    }
+    send(&rpc, &admin, &[instructions::set_executor(&admin.pubkey(), &executor.pubkey(), true)], &[]);
+
+    let owner = funded(&rpc);
+    let vault_id = accounts::next_vault_id(&rpc, &owner.pubkey()).unwrap();
+    let vault = pda::vault(&owner.pubkey(), vault_id).0;
+    send(&rpc, &owner, &[instructions::initialize_vault(&owner.pubkey(), &owner.pubkey(), vault_id, [7; 32])], &[]);
+
+    let (token_in, token_out) = (create_mint(&rpc, &owner), create_mint(&rpc, &owner));
+    send(
+        &rpc,
+        &owner,
+        &[
+            create_associated_token_account(&owner.pubkey(), &vault, &token_in, &spl_token::ID),
+            create_associated_token_account(&owner.pubkey(), &vault, &token_out, &spl_token::ID),
+            instructions::set_trade_pair(&owner.pubkey(), &vault, &token_in, &token_out, true, 0, 500),
+            instructions::set_strategy_config(&owner.pubkey(), &vault, 0, true, vec![], 0, 0, 0),
+        ],
+        &[],
+    );
+    assert!(rpc.get_account(&get_associated_token_address(&vault, &token_in)).is_ok());
+
+    let user = funded(&rpc);
+    send(
+        &rpc,
+        &user,
+        &[instructions::submit_trade_intent(&user.pubkey(), &vault, 0, &token_in, &token_out, 1_000_000, 100, 0)],
+        &[],
+    );
+    let intent = pda::intent(&user.pubkey(), &vault, 0).0;
+
+    let strategy = SlippageGuard {
+        quoter: MockAmm::new(30).with_pool(token_in, token_out, 1_000_000_000_000, 1_000_000_000_000),
+    };
+    let config = ExecutorConfig {
+        vaults: vec![vault],
+        ..ExecutorConfig::default()
+    };
+    let mut keeper = Executor::new(self::rpc(), executor, strategy, config);
+
+    let outcomes = keeper.tick().unwrap();
+    assert!(
+        matches!(outcomes.as_slice(), [Outcome::Executed { intent: i, .. }] if *i == intent),
+        "{outcomes:?}"
+    );
+    assert!(keeper.pending_intents().unwrap().is_empty());
+
+    let result = accounts::fetch_result(&rpc, &pda::result(&intent).0).unwrap();
+    assert!(result.success);
+    assert_eq!(result.executed_amount, 1_000_000);
+}
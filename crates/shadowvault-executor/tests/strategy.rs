@@ -0,0 +1,100 @@
+use shadowvault_client::state::{IntentKind, TradeIntent, PRICE_SCALE};
+use shadowvault_executor::{Decision, MockAmm, PendingIntent, Quoter, SlippageGuard, Strategy};
+use solana_sdk::hash::hashv;
+use solana_sdk::pubkey::Pubkey;
+
+const RESERVE: u64 = 1_000_000_000_000;
+
+fn pending(token_in: Pubkey, token_out: Pubkey, amount: u64, max_slippage_bps: u16) -> PendingIntent {
+    PendingIntent {
+        address: Pubkey::new_unique(),
+        intent: TradeIntent {
+            user: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            intent_id: 0,
+            token_in,
+            token_out,
+            amount,
+            max_slippage_bps,
+            strategy_type: 0,
+            timestamp: 0,
+            bump: 255,
+            kind: IntentKind::Market,
+            slices: 1,
+            slice_interval_secs: 0,
+            remaining_amount: amount,
+            slices_done: 0,
+            next_execution_ts: 0,
+            limit_price: 0,
+            expires_at: 0,
+            cancelled: false,
+            stop_loss_price: 0,
+            take_profit_price: 0,
+            triggered: None,
+            tip_mint: None,
+            tip_amount: 0,
+        },
+    }
+}
+
+fn skip_reason(decision: Decision) -> String {
+    match decision {
+        Decision::Skip(reason) => reason,
+        other => panic!("expected a skip, got {other:?}"),
+    }
+}
+
+#[test]
+fn slippage_guard_executes_within_the_bound_and_skips_above_it() {
+    let (token_in, token_out) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let amm = MockAmm::new(30).with_pool(token_in, token_out, RESERVE, RESERVE);
+    let guard = SlippageGuard { quoter: amm.clone() };
+
+    // a small trade only pays the 30 bps fee
+    let p = pending(token_in, token_out, 1_000_000, 100);
+    let quote = amm.quote(&token_in, &token_out, 1_000_000).unwrap();
+    assert_eq!(quote.slippage_bps, 30);
+    let expected = hashv(&[p.address.as_ref(), &quote.amount_out.to_le_bytes()]).to_bytes();
+    assert_eq!(guard.evaluate(&p), Decision::Execute { encrypted_params: expected });
+
+    // the bound is inclusive
+    assert!(matches!(guard.evaluate(&pending(token_in, token_out, 1_000_000, 30)), Decision::Execute { .. }));
+    let reason = skip_reason(guard.evaluate(&pending(token_in, token_out, 1_000_000, 29)));
+    assert_eq!(reason, "quoted slippage 30 bps exceeds max 29 bps");
+
+    // price impact counts too
+    let reason = skip_reason(guard.evaluate(&pending(token_in, token_out, RESERVE / 10, 100)));
+    assert!(reason.starts_with("quoted slippage"), "{reason}");
+
+    // only the pool's direction is quoted
+    let reason = skip_reason(guard.evaluate(&pending(token_out, token_in, 1_000_000, 100)));
+    assert_eq!(reason, "no quote for pair");
+}
+
+#[test]
+fn slippage_guard_prices_twap_slices_and_limit_orders() {
+    let (token_in, token_out) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let guard = SlippageGuard {
+        quoter: MockAmm::uniform(RESERVE, 30),
+    };
+
+    // the whole amount moves the pool ~9%, one slice of ten ~1%
+    let mut twap = pending(token_in, token_out, RESERVE / 10, 200);
+    assert!(matches!(guard.evaluate(&twap), Decision::Skip(_)));
+    twap.intent.kind = IntentKind::Twap;
+    twap.intent.slices = 10;
+    assert!(matches!(guard.evaluate(&twap), Decision::Execute { .. }));
+
+    // 1_000_000 in quotes 996_999 out after the fee
+    let mut limit = pending(token_in, token_out, 1_000_000, 100);
+    limit.intent.kind = IntentKind::Limit;
+    limit.intent.limit_price = PRICE_SCALE * 996_999 / 1_000_000;
+    assert!(matches!(guard.evaluate(&limit), Decision::Execute { .. }));
+    limit.intent.limit_price = PRICE_SCALE;
+    assert_eq!(skip_reason(guard.evaluate(&limit)), "quote below limit price");
+
+    // a partially filled limit order is priced on what remains
+    limit.intent.remaining_amount = 1_000;
+    limit.intent.limit_price = PRICE_SCALE * 996 / 1_000;
+    assert!(matches!(guard.evaluate(&limit), Decision::Execute { .. }));
+}
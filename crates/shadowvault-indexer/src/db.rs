@@ -0,0 +1,685 @@
+//! SQLite storage. Every event lands in `events`; the ones history queries
+//! need are also projected into typed tables. Rows carry a `finalized` flag:
+//! finalized rows are permanent, the rest are replaced on every sync so a
+//! dropped fork never leaves data behind.
+//!
+//! u64 amounts are stored bit-cast to INTEGER and cast back on read, so they
+//! round-trip exactly; totals are summed in Rust rather than SQL.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::{json, Value};
+use shadowvault_client::ShadowVaultEvent;
+use solana_sdk::pubkey::Pubkey;
+use std::path::Path;
+
+pub type Result<T> = rusqlite::Result<T>;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS cursor (
+    id        INTEGER PRIMARY KEY CHECK (id = 0),
+    signature TEXT NOT NULL,
+    slot      INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS transactions (
+    signature  TEXT PRIMARY KEY,
+    slot       INTEGER NOT NULL,
+    block_time INTEGER,
+    finalized  INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS events (
+    signature TEXT NOT NULL,
+    ordinal   INTEGER NOT NULL,
+    slot      INTEGER NOT NULL,
+    kind      TEXT NOT NULL,
+    vault     TEXT,
+    seq       INTEGER NOT NULL,
+    version   INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    payload   TEXT NOT NULL,
+    finalized INTEGER NOT NULL,
+    PRIMARY KEY (signature, ordinal)
+);
+CREATE INDEX IF NOT EXISTS events_vault ON events (vault, seq);
+CREATE TABLE IF NOT EXISTS vaults (
+    address       TEXT PRIMARY KEY,
+    owner         TEXT NOT NULL,
+    vault_id      INTEGER NOT NULL,
+    strategy_hash TEXT NOT NULL,
+    signature     TEXT NOT NULL,
+    slot          INTEGER NOT NULL,
+    timestamp     INTEGER NOT NULL,
+    finalized     INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS vaults_owner ON vaults (owner);
+CREATE TABLE IF NOT EXISTS deposits (
+    signature   TEXT NOT NULL,
+    ordinal     INTEGER NOT NULL,
+    slot        INTEGER NOT NULL,
+    vault       TEXT NOT NULL,
+    seq         INTEGER NOT NULL,
+    payer       TEXT NOT NULL,
+    beneficiary TEXT NOT NULL,
+    amount      INTEGER NOT NULL,
+    shares      INTEGER NOT NULL,
+    new_tvl     INTEGER NOT NULL,
+    timestamp   INTEGER NOT NULL,
+    finalized   INTEGER NOT NULL,
+    PRIMARY KEY (signature, ordinal)
+);
+CREATE INDEX IF NOT EXISTS deposits_beneficiary ON deposits (beneficiary);
+CREATE TABLE IF NOT EXISTS withdrawals (
+    signature TEXT NOT NULL,
+    ordinal   INTEGER NOT NULL,
+    slot      INTEGER NOT NULL,
+    vault     TEXT NOT NULL,
+    seq       INTEGER NOT NULL,
+    authority TEXT NOT NULL,
+    amount    INTEGER NOT NULL,
+    new_tvl   INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    finalized INTEGER NOT NULL,
+    PRIMARY KEY (signature, ordinal)
+);
+CREATE INDEX IF NOT EXISTS withdrawals_authority ON withdrawals (authority);
+CREATE TABLE IF NOT EXISTS intents (
+    signature        TEXT NOT NULL,
+    ordinal          INTEGER NOT NULL,
+    slot             INTEGER NOT NULL,
+    vault            TEXT NOT NULL,
+    seq              INTEGER NOT NULL,
+    intent           TEXT NOT NULL,
+    user             TEXT NOT NULL,
+    token_in         TEXT NOT NULL,
+    token_out        TEXT NOT NULL,
+    amount           INTEGER NOT NULL,
+    max_slippage_bps INTEGER NOT NULL,
+    strategy_type    INTEGER NOT NULL,
+    timestamp        INTEGER NOT NULL,
+    finalized        INTEGER NOT NULL,
+    PRIMARY KEY (signature, ordinal)
+);
+CREATE INDEX IF NOT EXISTS intents_user ON intents (user);
+CREATE INDEX IF NOT EXISTS intents_intent ON intents (intent);
+CREATE TABLE IF NOT EXISTS executions (
+    signature       TEXT NOT NULL,
+    ordinal         INTEGER NOT NULL,
+    slot            INTEGER NOT NULL,
+    vault           TEXT NOT NULL,
+    seq             INTEGER NOT NULL,
+    intent          TEXT NOT NULL,
+    executor        TEXT NOT NULL,
+    executed_amount INTEGER NOT NULL,
+    received_amount INTEGER NOT NULL,
+    success         INTEGER NOT NULL,
+    timestamp       INTEGER NOT NULL,
+    finalized       INTEGER NOT NULL,
+    PRIMARY KEY (signature, ordinal)
+);
+CREATE INDEX IF NOT EXISTS executions_intent ON executions (intent);
+CREATE TABLE IF NOT EXISTS intent_updates (
+    signature TEXT NOT NULL,
+    ordinal   INTEGER NOT NULL,
+    slot      INTEGER NOT NULL,
+    vault     TEXT NOT NULL,
+    seq       INTEGER NOT NULL,
+    intent    TEXT NOT NULL,
+    kind      TEXT NOT NULL,
+    actor     TEXT NOT NULL,
+    amount    INTEGER NOT NULL,
+    detail    TEXT,
+    timestamp INTEGER NOT NULL,
+    finalized INTEGER NOT NULL,
+    PRIMARY KEY (signature, ordinal)
+);
+CREATE INDEX IF NOT EXISTS intent_updates_intent ON intent_updates (intent);
+CREATE TABLE IF NOT EXISTS pauses (
+    signature   TEXT NOT NULL,
+    ordinal     INTEGER NOT NULL,
+    slot        INTEGER NOT NULL,
+    vault       TEXT NOT NULL,
+    seq         INTEGER NOT NULL,
+    authority   TEXT,
+    pause_flags INTEGER NOT NULL,
+    timestamp   INTEGER NOT NULL,
+    finalized   INTEGER NOT NULL,
+    PRIMARY KEY (signature, ordinal)
+);
+CREATE INDEX IF NOT EXISTS pauses_vault ON pauses (vault, seq);
+";
+
+// Tables holding per-transaction rows, cleared of unfinalized data on sync
+const TABLES: [&str; 9] = [
+    "transactions",
+    "events",
+    "vaults",
+    "deposits",
+    "withdrawals",
+    "intents",
+    "executions",
+    "intent_updates",
+    "pauses",
+];
+
+/// Last finalized program transaction that has been fully ingested
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cursor {
+    pub signature: String,
+    pub slot: u64,
+}
+
+/// A program transaction and the events decoded from it
+pub struct IndexedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub events: Vec<ShadowVaultEvent>,
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    pub fn cursor(&self) -> Result<Option<Cursor>> {
+        self.conn
+            .query_row("SELECT signature, slot FROM cursor WHERE id = 0", [], |r| {
+                Ok(Cursor {
+                    signature: r.get(0)?,
+                    slot: r.get::<_, i64>(1)? as u64,
+                })
+            })
+            .optional()
+    }
+
+    /// Stores finalized transactions (oldest first) and moves the cursor to
+    /// the last one, atomically, so a crash never skips or half-applies a
+    /// batch. Unfinalized rows are dropped; the next confirmed pass refills them.
+    pub fn commit_finalized(&mut self, txs: &[IndexedTransaction]) -> Result<()> {
+        let Some(last) = txs.last() else {
+            return Ok(());
+        };
+        let db = self.conn.transaction()?;
+        clear_unfinalized(&db)?;
+        for tx in txs {
+            insert_transaction(&db, tx, true)?;
+        }
+        db.execute(
+            "INSERT INTO cursor (id, signature, slot) VALUES (0, ?1, ?2)
+             ON CONFLICT (id) DO UPDATE SET signature = excluded.signature, slot = excluded.slot",
+            params![last.signature, last.slot as i64],
+        )?;
+        db.commit()
+    }
+
+    /// Replaces every unfinalized row with `txs`
+    pub fn replace_unfinalized(&mut self, txs: &[IndexedTransaction]) -> Result<()> {
+        let db = self.conn.transaction()?;
+        clear_unfinalized(&db)?;
+        for tx in txs {
+            insert_transaction(&db, tx, false)?;
+        }
+        db.commit()
+    }
+
+    /// Every event recorded for `vault`, in sequence order
+    pub fn vault_history(&self, vault: &Pubkey, limit: u32) -> Result<Vec<Value>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT seq, kind, signature, slot, timestamp, finalized, payload
+             FROM events WHERE vault = ?1 ORDER BY seq LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![vault.to_string(), limit], |r| {
+            Ok(json!({
+                "seq": r.get::<_, i64>(0)? as u64,
+                "kind": r.get::<_, String>(1)?,
+                "signature": r.get::<_, String>(2)?,
+                "slot": r.get::<_, i64>(3)? as u64,
+                "timestamp": r.get::<_, i64>(4)?,
+                "finalized": r.get::<_, bool>(5)?,
+                "payload": r.get::<_, String>(6)?,
+            }))
+        })?;
+        rows.collect()
+    }
+
+    /// Deposits credited to, withdrawals signed by, and intents (with their
+    /// executions and lifecycle updates) submitted by `user`, plus per-vault totals
+    pub fn user_statement(&self, user: &Pubkey) -> Result<Value> {
+        let user = user.to_string();
+
+        let mut stmt = self.conn.prepare(
+            "SELECT vault, payer, amount, shares, signature, slot, timestamp, finalized
+             FROM deposits WHERE beneficiary = ?1 ORDER BY slot, ordinal",
+        )?;
+        let deposits: Vec<Value> = stmt
+            .query_map([&user], |r| {
+                Ok(json!({
+                    "vault": r.get::<_, String>(0)?,
+                    "payer": r.get::<_, String>(1)?,
+                    "amount": r.get::<_, i64>(2)? as u64,
+                    "shares": r.get::<_, i64>(3)? as u64,
+                    "signature": r.get::<_, String>(4)?,
+                    "slot": r.get::<_, i64>(5)? as u64,
+                    "timestamp": r.get::<_, i64>(6)?,
+                    "finalized": r.get::<_, bool>(7)?,
+                }))
+            })?
+            .collect::<Result<_>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT vault, amount, signature, slot, timestamp, finalized
+             FROM withdrawals WHERE authority = ?1 ORDER BY slot, ordinal",
+        )?;
+        let withdrawals: Vec<Value> = stmt
+            .query_map([&user], |r| {
+                Ok(json!({
+                    "vault": r.get::<_, String>(0)?,
+                    "amount": r.get::<_, i64>(1)? as u64,
+                    "signature": r.get::<_, String>(2)?,
+                    "slot": r.get::<_, i64>(3)? as u64,
+                    "timestamp": r.get::<_, i64>(4)?,
+                    "finalized": r.get::<_, bool>(5)?,
+                }))
+            })?
+            .collect::<Result<_>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT i.vault, i.intent, i.token_in, i.token_out, i.amount, i.max_slippage_bps,
+                    i.signature, i.timestamp, i.finalized,
+                    e.executed_amount, e.received_amount, e.success, e.signature
+             FROM intents i LEFT JOIN executions e ON e.intent = i.intent AND e.slot >= i.slot
+             WHERE i.user = ?1 ORDER BY i.slot, i.ordinal",
+        )?;
+        let mut updates = self.intent_updates(&user)?;
+        let intents: Vec<Value> = stmt
+            .query_map([&user], |r| {
+                let execution = match r.get::<_, Option<i64>>(9)? {
+                    Some(executed) => json!({
+                        "executed_amount": executed as u64,
+                        "received_amount": r.get::<_, i64>(10)? as u64,
+                        "success": r.get::<_, bool>(11)?,
+                        "signature": r.get::<_, String>(12)?,
+                    }),
+                    None => Value::Null,
+                };
+                let intent = r.get::<_, String>(1)?;
+                Ok(json!({
+                    "vault": r.get::<_, String>(0)?,
+                    "updates": updates.remove(&intent).unwrap_or_default(),
+                    "intent": intent,
+                    "token_in": r.get::<_, String>(2)?,
+                    "token_out": r.get::<_, String>(3)?,
+                    "amount": r.get::<_, i64>(4)? as u64,
+                    "max_slippage_bps": r.get::<_, u16>(5)?,
+                    "signature": r.get::<_, String>(6)?,
+                    "timestamp": r.get::<_, i64>(7)?,
+                    "finalized": r.get::<_, bool>(8)?,
+                    "execution": execution,
+                }))
+            })?
+            .collect::<Result<_>>()?;
+
+        let mut totals = std::collections::BTreeMap::<String, (u128, u128)>::new();
+        for d in &deposits {
+            totals.entry(d["vault"].as_str().unwrap_or_default().to_string()).or_default().0 +=
+                d["amount"].as_u64().unwrap_or_default() as u128;
+        }
+        for w in &withdrawals {
+            totals.entry(w["vault"].as_str().unwrap_or_default().to_string()).or_default().1 +=
+                w["amount"].as_u64().unwrap_or_default() as u128;
+        }
+        let totals: Vec<Value> = totals
+            .into_iter()
+            .map(|(vault, (deposited, withdrawn))| {
+                json!({
+                    "vault": vault,
+                    "deposited": deposited.to_string(),
+                    "withdrawn": withdrawn.to_string(),
+                })
+            })
+            .collect();
+
+        Ok(json!({
+            "user": user,
+            "totals": totals,
+            "deposits": deposits,
+            "withdrawals": withdrawals,
+            "intents": intents,
+        }))
+    }
+
+    /// Cancel, trigger and tip updates of `user`'s intents, keyed by intent
+    fn intent_updates(&self, user: &str) -> Result<std::collections::HashMap<String, Vec<Value>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT u.intent, u.kind, u.actor, u.amount, u.detail, u.signature, u.slot, u.timestamp, u.finalized
+             FROM intent_updates u
+             WHERE u.intent IN (SELECT intent FROM intents WHERE user = ?1)
+             ORDER BY u.slot, u.ordinal",
+        )?;
+        let rows = stmt.query_map([user], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                json!({
+                    "kind": r.get::<_, String>(1)?,
+                    "actor": r.get::<_, String>(2)?,
+                    "amount": r.get::<_, i64>(3)? as u64,
+                    "detail": r.get::<_, Option<String>>(4)?,
+                    "signature": r.get::<_, String>(5)?,
+                    "slot": r.get::<_, i64>(6)? as u64,
+                    "timestamp": r.get::<_, i64>(7)?,
+                    "finalized": r.get::<_, bool>(8)?,
+                }),
+            ))
+        })?;
+        let mut updates = std::collections::HashMap::<String, Vec<Value>>::new();
+        for row in rows {
+            let (intent, update) = row?;
+            updates.entry(intent).or_default().push(update);
+        }
+        Ok(updates)
+    }
+
+    /// Pause flag changes of `vault` in sequence order; breaker trips have
+    /// no authority
+    pub fn pause_history(&self, vault: &Pubkey) -> Result<Vec<Value>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT seq, authority, pause_flags, signature, slot, timestamp, finalized
+             FROM pauses WHERE vault = ?1 ORDER BY seq",
+        )?;
+        let rows = stmt.query_map([vault.to_string()], |r| {
+            Ok(json!({
+                "seq": r.get::<_, i64>(0)? as u64,
+                "authority": r.get::<_, Option<String>>(1)?,
+                "pause_flags": r.get::<_, u8>(2)?,
+                "signature": r.get::<_, String>(3)?,
+                "slot": r.get::<_, i64>(4)? as u64,
+                "timestamp": r.get::<_, i64>(5)?,
+                "finalized": r.get::<_, bool>(6)?,
+            }))
+        })?;
+        rows.collect()
+    }
+
+    /// Indexed vaults, optionally only those of `owner`
+    pub fn vaults(&self, owner: Option<&Pubkey>) -> Result<Vec<Value>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT address, owner, vault_id, strategy_hash, signature, slot, finalized
+             FROM vaults WHERE ?1 IS NULL OR owner = ?1 ORDER BY slot, address",
+        )?;
+        let rows = stmt.query_map([owner.map(|o| o.to_string())], |r| {
+            Ok(json!({
+                "address": r.get::<_, String>(0)?,
+                "owner": r.get::<_, String>(1)?,
+                "vault_id": r.get::<_, i64>(2)? as u64,
+                "strategy_hash": r.get::<_, String>(3)?,
+                "signature": r.get::<_, String>(4)?,
+                "slot": r.get::<_, i64>(5)? as u64,
+                "finalized": r.get::<_, bool>(6)?,
+            }))
+        })?;
+        rows.collect()
+    }
+}
+
+fn clear_unfinalized(db: &Connection) -> Result<()> {
+    for table in TABLES {
+        db.execute(&format!("DELETE FROM {table} WHERE finalized = 0"), [])?;
+    }
+    Ok(())
+}
+
+fn insert_transaction(db: &Connection, tx: &IndexedTransaction, finalized: bool) -> Result<()> {
+    // replaying a batch after a crash must not duplicate its events
+    let inserted = db.execute(
+        "INSERT OR IGNORE INTO transactions (signature, slot, block_time, finalized) VALUES (?1, ?2, ?3, ?4)",
+        params![tx.signature, tx.slot as i64, tx.block_time, finalized],
+    )?;
+    if inserted == 0 {
+        return Ok(());
+    }
+    for (ordinal, event) in tx.events.iter().enumerate() {
+        insert_event(db, tx, ordinal as i64, event, finalized)?;
+    }
+    Ok(())
+}
+
+fn insert_event(
+    db: &Connection,
+    tx: &IndexedTransaction,
+    ordinal: i64,
+    event: &ShadowVaultEvent,
+    finalized: bool,
+) -> Result<()> {
+    let (sig, slot) = (&tx.signature, tx.slot as i64);
+    let seq = event.seq() as i64;
+    db.execute(
+        "INSERT INTO events (signature, ordinal, slot, kind, vault, seq, version, timestamp, payload, finalized)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            sig,
+            ordinal,
+            slot,
+            event.name(),
+            event.vault().map(|v| v.to_string()),
+            seq,
+            event.version(),
+            event.timestamp(),
+            format!("{event:?}"),
+            finalized,
+        ],
+    )?;
+
+    match event {
+        ShadowVaultEvent::VaultInitialized(e) => {
+            db.execute(
+                "INSERT OR IGNORE INTO vaults (address, owner, vault_id, strategy_hash, signature, slot, timestamp, finalized)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    e.vault.to_string(),
+                    e.owner.to_string(),
+                    e.vault_id as i64,
+                    hex(&e.encrypted_strategy_hash),
+                    sig,
+                    slot,
+                    e.timestamp,
+                    finalized,
+                ],
+            )?;
+        }
+        ShadowVaultEvent::Deposited(e) => {
+            db.execute(
+                "INSERT INTO deposits (signature, ordinal, slot, vault, seq, payer, beneficiary, amount, shares, new_tvl, timestamp, finalized)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    sig,
+                    ordinal,
+                    slot,
+                    e.vault.to_string(),
+                    seq,
+                    e.user.to_string(),
+                    e.beneficiary.to_string(),
+                    e.amount as i64,
+                    e.shares as i64,
+                    e.new_tvl as i64,
+                    e.timestamp,
+                    finalized,
+                ],
+            )?;
+        }
+        ShadowVaultEvent::Withdrawn(e) => {
+            db.execute(
+                "INSERT INTO withdrawals (signature, ordinal, slot, vault, seq, authority, amount, new_tvl, timestamp, finalized)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    sig,
+                    ordinal,
+                    slot,
+                    e.vault.to_string(),
+                    seq,
+                    e.authority.to_string(),
+                    e.amount as i64,
+                    e.new_tvl as i64,
+                    e.timestamp,
+                    finalized,
+                ],
+            )?;
+        }
+        ShadowVaultEvent::TradeIntentSubmitted(e) => {
+            db.execute(
+                "INSERT INTO intents (signature, ordinal, slot, vault, seq, intent, user, token_in, token_out, amount, max_slippage_bps, strategy_type, timestamp, finalized)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                params![
+                    sig,
+                    ordinal,
+                    slot,
+                    e.vault.to_string(),
+                    seq,
+                    e.intent.to_string(),
+                    e.user.to_string(),
+                    e.token_in.to_string(),
+                    e.token_out.to_string(),
+                    e.amount as i64,
+                    e.max_slippage_bps,
+                    e.strategy_type,
+                    e.timestamp,
+                    finalized,
+                ],
+            )?;
+        }
+        ShadowVaultEvent::TradeExecuted(e) => {
+            db.execute(
+                "INSERT INTO executions (signature, ordinal, slot, vault, seq, intent, executor, executed_amount, received_amount, success, timestamp, finalized)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    sig,
+                    ordinal,
+                    slot,
+                    e.vault.to_string(),
+                    seq,
+                    e.intent.to_string(),
+                    e.executor.to_string(),
+                    e.executed_amount as i64,
+                    e.received_amount as i64,
+                    e.success,
+                    e.timestamp,
+                    finalized,
+                ],
+            )?;
+        }
+        ShadowVaultEvent::IntentCancelled(e) => {
+            db.execute(
+                "INSERT INTO intent_updates (signature, ordinal, slot, vault, seq, intent, kind, actor, amount, detail, timestamp, finalized)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    sig,
+                    ordinal,
+                    slot,
+                    e.vault.to_string(),
+                    seq,
+                    e.intent.to_string(),
+                    "cancelled",
+                    e.user.to_string(),
+                    e.remaining_amount as i64,
+                    None::<String>,
+                    e.timestamp,
+                    finalized,
+                ],
+            )?;
+        }
+        ShadowVaultEvent::IntentTriggered(e) => {
+            db.execute(
+                "INSERT INTO intent_updates (signature, ordinal, slot, vault, seq, intent, kind, actor, amount, detail, timestamp, finalized)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    sig,
+                    ordinal,
+                    slot,
+                    e.vault.to_string(),
+                    seq,
+                    e.intent.to_string(),
+                    "triggered",
+                    e.keeper.to_string(),
+                    e.price as i64,
+                    format!("{:?}", e.leg),
+                    e.timestamp,
+                    finalized,
+                ],
+            )?;
+        }
+        ShadowVaultEvent::IntentTipped(e) => {
+            // `tip_amount` is the running total; no mint means lamports
+            db.execute(
+                "INSERT INTO intent_updates (signature, ordinal, slot, vault, seq, intent, kind, actor, amount, detail, timestamp, finalized)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    sig,
+                    ordinal,
+                    slot,
+                    e.vault.to_string(),
+                    seq,
+                    e.intent.to_string(),
+                    "tipped",
+                    e.user.to_string(),
+                    e.tip_amount as i64,
+                    e.tip_mint.map(|m| m.to_string()),
+                    e.timestamp,
+                    finalized,
+                ],
+            )?;
+        }
+        ShadowVaultEvent::VaultPaused(e) => {
+            db.execute(
+                "INSERT INTO pauses (signature, ordinal, slot, vault, seq, authority, pause_flags, timestamp, finalized)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    sig,
+                    ordinal,
+                    slot,
+                    e.vault.to_string(),
+                    seq,
+                    e.authority.to_string(),
+                    e.pause_flags,
+                    e.timestamp,
+                    finalized,
+                ],
+            )?;
+        }
+        // the breaker pauses the vault itself, so there is no authority
+        ShadowVaultEvent::CircuitBreakerTripped(e) => {
+            db.execute(
+                "INSERT INTO pauses (signature, ordinal, slot, vault, seq, authority, pause_flags, timestamp, finalized)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    sig,
+                    ordinal,
+                    slot,
+                    e.vault.to_string(),
+                    seq,
+                    None::<String>,
+                    e.pause_flags,
+                    e.timestamp,
+                    finalized,
+                ],
+            )?;
+        }
+        // configuration and protocol events are only kept in `events`
+        _ => {}
+    }
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
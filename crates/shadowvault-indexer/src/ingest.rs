@@ -0,0 +1,139 @@
+//! RPC ingestion. Finalized transactions are stored permanently and advance
+//! the cursor; with `Commitment::Confirmed` the confirmed-but-not-finalized
+//! tail is indexed too, flagged unfinalized and rebuilt on every sync, so a
+//! fork that never finalizes disappears from the index.
+
+use anyhow::{Context, Result};
+use shadowvault_client::{events, PROGRAM_ID};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+use std::str::FromStr;
+
+use crate::db::{Cursor, IndexedTransaction, Store};
+
+// getSignaturesForAddress page size, also the commit batch size
+const PAGE_SIZE: usize = 1_000;
+const BATCH_SIZE: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Commitment {
+    /// Index finalized transactions only
+    Finalized,
+    /// Also index the confirmed tail, replaced on every sync
+    Confirmed,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SyncReport {
+    pub finalized: usize,
+    pub unfinalized: usize,
+    pub cursor: Option<Cursor>,
+}
+
+pub fn sync(rpc: &RpcClient, store: &mut Store, commitment: Commitment) -> Result<SyncReport> {
+    let mut report = SyncReport::default();
+
+    let cursor = store.cursor()?;
+    let signatures = signatures_since(rpc, cursor.as_ref(), CommitmentConfig::finalized())?;
+    for batch in signatures.chunks(BATCH_SIZE) {
+        let txs = fetch_all(rpc, batch, CommitmentConfig::finalized())?;
+        store.commit_finalized(&txs)?;
+        report.finalized += txs.len();
+    }
+
+    report.cursor = store.cursor()?;
+    let tail = match commitment {
+        Commitment::Finalized => Vec::new(),
+        Commitment::Confirmed => {
+            let signatures = signatures_since(rpc, report.cursor.as_ref(), CommitmentConfig::confirmed())?;
+            fetch_all(rpc, &signatures, CommitmentConfig::confirmed())?
+        }
+    };
+    store.replace_unfinalized(&tail)?;
+    report.unfinalized = tail.len();
+    Ok(report)
+}
+
+/// Program signatures after `cursor`, oldest first
+fn signatures_since(
+    rpc: &RpcClient,
+    cursor: Option<&Cursor>,
+    commitment: CommitmentConfig,
+) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+    let until = cursor
+        .map(|c| Signature::from_str(&c.signature))
+        .transpose()
+        .context("invalid cursor signature")?;
+
+    // newest first; page backwards until `until` or the start of history
+    let mut all = Vec::new();
+    let mut before = None;
+    loop {
+        let page = rpc
+            .get_signatures_for_address_with_config(
+                &PROGRAM_ID,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: Some(PAGE_SIZE),
+                    commitment: Some(commitment),
+                },
+            )
+            .context("fetching program signatures")?;
+        let done = page.len() < PAGE_SIZE;
+        before = page
+            .last()
+            .map(|s| Signature::from_str(&s.signature))
+            .transpose()
+            .context("invalid signature from RPC")?;
+        all.extend(page);
+        if done || before.is_none() {
+            break;
+        }
+    }
+    all.reverse();
+    Ok(all)
+}
+
+fn fetch_all(
+    rpc: &RpcClient,
+    signatures: &[RpcConfirmedTransactionStatusWithSignature],
+    commitment: CommitmentConfig,
+) -> Result<Vec<IndexedTransaction>> {
+    signatures.iter().map(|s| fetch(rpc, s, commitment)).collect()
+}
+
+fn fetch(
+    rpc: &RpcClient,
+    status: &RpcConfirmedTransactionStatusWithSignature,
+    commitment: CommitmentConfig,
+) -> Result<IndexedTransaction> {
+    let mut indexed = IndexedTransaction {
+        signature: status.signature.clone(),
+        slot: status.slot,
+        block_time: status.block_time,
+        events: Vec::new(),
+    };
+    // failed transactions emit nothing but still move the cursor
+    if status.err.is_some() {
+        return Ok(indexed);
+    }
+
+    let signature = Signature::from_str(&status.signature).context("invalid signature from RPC")?;
+    let tx = rpc
+        .get_transaction_with_config(
+            &signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(commitment),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .with_context(|| format!("fetching transaction {signature}"))?;
+    indexed.events = events::parse_transaction(&tx).with_context(|| format!("decoding transaction {signature}"))?;
+    Ok(indexed)
+}
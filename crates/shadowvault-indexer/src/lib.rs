@@ -0,0 +1,16 @@
+// ============================================================================
+// ShadowVault Indexer - event history in SQLite
+// ============================================================================
+//
+// Walks the program's transaction history over RPC, decodes every shadowvault
+// event and stores it in SQLite alongside typed vault, deposit, withdrawal,
+// intent, intent update, execution and pause tables. A cursor on the last
+// finalized transaction makes syncs resumable; see `ingest` for how
+// unfinalized data is handled.
+// ============================================================================
+
+pub mod db;
+pub mod ingest;
+
+pub use db::{Cursor, IndexedTransaction, Store};
+pub use ingest::{sync, Commitment, SyncReport};
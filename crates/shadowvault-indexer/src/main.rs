@@ -0,0 +1,112 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use serde_json::{json, Value};
+use shadowvault_indexer::{sync, Commitment, Store, SyncReport};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::time::Duration;
+
+/// Indexes ShadowVault events into SQLite and queries the history
+#[derive(Parser)]
+#[command(name = "shadowvault-indexer", version)]
+struct Cli {
+    /// RPC endpoint
+    #[arg(long, short = 'u', global = true, env = "SHADOWVAULT_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// SQLite database file
+    #[arg(long, global = true, env = "SHADOWVAULT_DB", default_value = "shadowvault-index.db")]
+    db: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Ingest new program transactions, resuming from the stored cursor
+    Sync {
+        #[arg(long, value_enum, default_value_t = Commitment::Finalized)]
+        commitment: Commitment,
+        /// Keep syncing every `--interval-ms`, printing one JSON line per pass
+        #[arg(long)]
+        follow: bool,
+        #[arg(long, default_value_t = 5_000)]
+        interval_ms: u64,
+    },
+    /// Every event of a vault in sequence order
+    VaultHistory {
+        #[arg(long)]
+        vault: Pubkey,
+        #[arg(long, default_value_t = 1_000)]
+        limit: u32,
+    },
+    /// Pause flag changes of a vault, including circuit breaker trips
+    PauseHistory {
+        #[arg(long)]
+        vault: Pubkey,
+    },
+    /// Deposits, withdrawals and intents of a user with per-vault totals
+    UserStatement {
+        #[arg(long)]
+        user: Pubkey,
+    },
+    /// Indexed vaults
+    Vaults {
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+}
+
+fn main() {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(value) => println!("{}", serde_json::to_string_pretty(&value).unwrap_or_default()),
+        Err(e) => {
+            eprintln!("{}", json!({ "error": format!("{e:#}") }));
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<Value> {
+    let mut store = Store::open(&cli.db)?;
+    let value = match cli.command {
+        Command::Sync {
+            commitment,
+            follow,
+            interval_ms,
+        } => {
+            let rpc = RpcClient::new(cli.url);
+            if !follow {
+                return Ok(render(&sync(&rpc, &mut store, commitment)?));
+            }
+            loop {
+                match sync(&rpc, &mut store, commitment) {
+                    Ok(report) => println!("{}", render(&report)),
+                    Err(e) => eprintln!("{}", json!({ "error": format!("{e:#}") })),
+                }
+                std::thread::sleep(Duration::from_millis(interval_ms));
+            }
+        }
+        Command::VaultHistory { vault, limit } => json!({
+            "vault": vault.to_string(),
+            "events": store.vault_history(&vault, limit)?,
+        }),
+        Command::PauseHistory { vault } => json!({
+            "vault": vault.to_string(),
+            "pauses": store.pause_history(&vault)?,
+        }),
+        Command::UserStatement { user } => store.user_statement(&user)?,
+        Command::Vaults { owner } => Value::Array(store.vaults(owner.as_ref())?),
+    };
+    Ok(value)
+}
+
+fn render(report: &SyncReport) -> Value {
+    json!({
+        "finalized": report.finalized,
+        "unfinalized": report.unfinalized,
+        "cursor": report.cursor.as_ref().map(|c| json!({ "signature": c.signature, "slot": c.slot })),
+    })
+}
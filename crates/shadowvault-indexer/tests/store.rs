@@ -0,0 +1,237 @@
+use shadowvault::state::{PauseFlags, TriggerLeg};
+use shadowvault::{
+    CircuitBreakerTripped, Deposited, IntentCancelled, IntentTipped, IntentTriggered, TradeExecuted,
+    TradeIntentSubmitted, VaultInitialized, VaultPaused, Withdrawn, EVENT_SCHEMA_VERSION,
+};
+use shadowvault_client::ShadowVaultEvent;
+use shadowvault_indexer::{Cursor, IndexedTransaction, Store};
+use solana_sdk::pubkey::Pubkey;
+
+fn tx(signature: &str, slot: u64, events: Vec<ShadowVaultEvent>) -> IndexedTransaction {
+    IndexedTransaction {
+        signature: signature.to_string(),
+        slot,
+        block_time: Some(slot as i64),
+        events,
+    }
+}
+
+fn deposited(vault: Pubkey, user: Pubkey, seq: u64, amount: u64) -> ShadowVaultEvent {
+    ShadowVaultEvent::Deposited(Deposited {
+        version: EVENT_SCHEMA_VERSION,
+        seq,
+        vault,
+        user,
+        beneficiary: user,
+        amount,
+        shares: amount,
+        new_tvl: amount,
+        timestamp: 0,
+    })
+}
+
+#[test]
+fn finalized_rows_persist_and_unfinalized_rows_are_replaced() {
+    let (vault, owner, user) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut store = Store::open_in_memory().unwrap();
+    assert_eq!(store.cursor().unwrap(), None);
+
+    let init = ShadowVaultEvent::VaultInitialized(VaultInitialized {
+        version: EVENT_SCHEMA_VERSION,
+        seq: 1,
+        vault,
+        owner,
+        vault_id: 0,
+        encrypted_strategy_hash: [1; 32],
+        timestamp: 0,
+    });
+    store
+        .commit_finalized(&[tx("a", 10, vec![init]), tx("b", 11, vec![deposited(vault, user, 2, 500)])])
+        .unwrap();
+    assert_eq!(
+        store.cursor().unwrap(),
+        Some(Cursor {
+            signature: "b".into(),
+            slot: 11
+        })
+    );
+
+    // a confirmed deposit on a fork that is later dropped
+    store
+        .replace_unfinalized(&[tx("fork", 12, vec![deposited(vault, user, 3, 7)])])
+        .unwrap();
+    assert_eq!(store.vault_history(&vault, 100).unwrap().len(), 3);
+    store.replace_unfinalized(&[]).unwrap();
+
+    let history = store.vault_history(&vault, 100).unwrap();
+    let kinds: Vec<_> = history.iter().map(|e| e["kind"].as_str().unwrap()).collect();
+    assert_eq!(kinds, ["VaultInitialized", "Deposited"]);
+    assert!(history.iter().all(|e| e["finalized"] == true));
+
+    // replaying an already committed batch does not duplicate rows
+    store
+        .commit_finalized(&[tx("b", 11, vec![deposited(vault, user, 2, 500)])])
+        .unwrap();
+    assert_eq!(store.vault_history(&vault, 100).unwrap().len(), 2);
+    assert_eq!(store.vaults(Some(&owner)).unwrap().len(), 1);
+}
+
+#[test]
+fn user_statement_totals_and_links_executions() {
+    let (vault, user, executor) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let (intent, token_in, token_out) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut store = Store::open_in_memory().unwrap();
+
+    let withdrawn = ShadowVaultEvent::Withdrawn(Withdrawn {
+        version: EVENT_SCHEMA_VERSION,
+        seq: 3,
+        vault,
+        authority: user,
+        amount: 200,
+        new_tvl: 1_300,
+        timestamp: 0,
+    });
+    let submitted = ShadowVaultEvent::TradeIntentSubmitted(TradeIntentSubmitted {
+        version: EVENT_SCHEMA_VERSION,
+        seq: 4,
+        vault,
+        intent,
+        user,
+        token_in,
+        token_out,
+        amount: 100,
+        max_slippage_bps: 50,
+        strategy_type: 0,
+        timestamp: 0,
+    });
+    let executed = ShadowVaultEvent::TradeExecuted(TradeExecuted {
+        version: EVENT_SCHEMA_VERSION,
+        seq: 5,
+        vault,
+        intent,
+        executor,
+        executed_amount: 100,
+        received_amount: 98,
+        success: true,
+        timestamp: 0,
+    });
+    // u64 amounts above i64::MAX must round-trip
+    let big = u64::MAX - 1;
+    store
+        .commit_finalized(&[
+            tx("d1", 1, vec![deposited(vault, user, 1, 1_000)]),
+            tx("d2", 2, vec![deposited(vault, user, 2, 500)]),
+            tx("w", 3, vec![withdrawn]),
+            tx("i", 4, vec![submitted]),
+            tx("x", 5, vec![executed]),
+            tx("d3", 6, vec![deposited(Pubkey::new_unique(), user, 1, big)]),
+        ])
+        .unwrap();
+
+    let statement = store.user_statement(&user).unwrap();
+    assert_eq!(statement["deposits"].as_array().unwrap().len(), 3);
+    assert_eq!(statement["deposits"][2]["amount"], big);
+
+    let totals = statement["totals"].as_array().unwrap();
+    let this_vault = totals.iter().find(|t| t["vault"] == vault.to_string()).unwrap();
+    assert_eq!(this_vault["deposited"], "1500");
+    assert_eq!(this_vault["withdrawn"], "200");
+
+    let execution = &statement["intents"][0]["execution"];
+    assert_eq!(execution["received_amount"], 98);
+    assert_eq!(execution["signature"], "x");
+}
+
+#[test]
+fn intent_lifecycle_and_pause_history_are_projected() {
+    let (vault, user, keeper, guardian) =
+        (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let intent = Pubkey::new_unique();
+    let mut store = Store::open_in_memory().unwrap();
+
+    let submitted = ShadowVaultEvent::TradeIntentSubmitted(TradeIntentSubmitted {
+        version: EVENT_SCHEMA_VERSION,
+        seq: 1,
+        vault,
+        intent,
+        user,
+        token_in: Pubkey::new_unique(),
+        token_out: Pubkey::new_unique(),
+        amount: 100,
+        max_slippage_bps: 50,
+        strategy_type: 3,
+        timestamp: 0,
+    });
+    let tipped = ShadowVaultEvent::IntentTipped(IntentTipped {
+        version: EVENT_SCHEMA_VERSION,
+        seq: 2,
+        vault,
+        intent,
+        user,
+        tip_mint: None,
+        amount: 5_000,
+        tip_amount: 5_000,
+        timestamp: 0,
+    });
+    let triggered = ShadowVaultEvent::IntentTriggered(IntentTriggered {
+        version: EVENT_SCHEMA_VERSION,
+        seq: 3,
+        vault,
+        intent,
+        keeper,
+        leg: TriggerLeg::StopLoss,
+        price: 90,
+        timestamp: 0,
+    });
+    let cancelled = ShadowVaultEvent::IntentCancelled(IntentCancelled {
+        version: EVENT_SCHEMA_VERSION,
+        seq: 4,
+        vault,
+        intent,
+        user,
+        remaining_amount: 100,
+        timestamp: 0,
+    });
+    let paused = ShadowVaultEvent::VaultPaused(VaultPaused {
+        version: EVENT_SCHEMA_VERSION,
+        seq: 5,
+        vault,
+        authority: guardian,
+        pause_flags: PauseFlags::DEPOSITS,
+        timestamp: 0,
+    });
+    let tripped = ShadowVaultEvent::CircuitBreakerTripped(CircuitBreakerTripped {
+        version: EVENT_SCHEMA_VERSION,
+        seq: 6,
+        vault,
+        window_outflow: 600,
+        tvl: 1_000,
+        threshold_bps: 5_000,
+        pause_flags: PauseFlags::ALL,
+        timestamp: 0,
+    });
+    store
+        .commit_finalized(&[tx("i", 1, vec![submitted, tipped]), tx("t", 2, vec![triggered, cancelled])])
+        .unwrap();
+    store.replace_unfinalized(&[tx("p", 3, vec![paused, tripped])]).unwrap();
+
+    let statement = store.user_statement(&user).unwrap();
+    let updates = statement["intents"][0]["updates"].as_array().unwrap();
+    let kinds: Vec<&str> = updates.iter().map(|u| u["kind"].as_str().unwrap()).collect();
+    assert_eq!(kinds, ["tipped", "triggered", "cancelled"]);
+    assert_eq!(updates[0]["detail"], serde_json::Value::Null);
+    assert_eq!(updates[1]["actor"], keeper.to_string());
+    assert_eq!(updates[1]["detail"], "StopLoss");
+    assert_eq!(updates[2]["amount"], 100);
+
+    let pauses = store.pause_history(&vault).unwrap();
+    assert_eq!(pauses.len(), 2);
+    assert_eq!(pauses[0]["authority"], guardian.to_string());
+    assert_eq!(pauses[1]["authority"], serde_json::Value::Null);
+    assert_eq!(pauses[1]["pause_flags"], PauseFlags::ALL);
+    assert_eq!(pauses[1]["finalized"], false);
+
+    // unfinalized pauses go away with their fork
+    store.replace_unfinalized(&[]).unwrap();
+    assert!(store.pause_history(&vault).unwrap().is_empty());
+}
@@ -0,0 +1,401 @@
+// ============================================================================
+// ShadowVault program tests - in-process SVM harness
+// ============================================================================
+//
+// Runs the program natively inside solana-program-test, so `cargo test`
+// exercises every instruction without a validator or an SBF build. The SPL
+// token and associated token programs come bundled with program-test.
+//
+// `TestEnv::new` starts a bank with the protocol initialized; its payer is
+// the protocol admin and the mint authority for `create_mint`.
+// ============================================================================
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorDeserialize};
+use anchor_spl::token::spl_token;
+use shadowvault::errors::ShadowError;
+use shadowvault::event_decoder::{decode_inner_instructions, ShadowVaultEvent};
+use shadowvault::state::{TradeIntent, PRICE_SCALE};
+use shadowvault_client::{instructions, pda, views};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::account_info::AccountInfo;
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::clock::Clock;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use std::cell::RefCell;
+
+pub type SendResult = Result<(), BanksClientError>;
+
+thread_local! {
+    // event CPI payloads the program received, in order; banks-client does
+    // not record inner instructions, so `send_with_events` reads them here
+    static EVENT_CPIS: RefCell<Vec<(Pubkey, Vec<u8>)>> = const { RefCell::new(Vec::new()) };
+}
+
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if data.starts_with(&EVENT_IX_TAG_LE) {
+        EVENT_CPIS.with(|cpis| cpis.borrow_mut().push((*program_id, data.to_vec())));
+    }
+    // Anchor's entry ties the slice and account lifetimes together; the
+    // accounts outlive this call, so widening the slice lifetime is sound
+    let accounts: &[AccountInfo] = unsafe { std::mem::transmute(accounts) };
+    shadowvault::entry(program_id, accounts, data)
+}
+
+pub struct TestEnv {
+    pub ctx: ProgramTestContext,
+    // makes otherwise identical transactions distinct within one blockhash
+    nonce: u64,
+}
+
+impl TestEnv {
+    /// Fresh bank with the protocol initialized (no fee, no executors)
+    pub async fn new() -> Self {
+        let mut env = Self::without_protocol().await;
+        let admin = env.admin();
+        env.send(&[instructions::initialize_protocol(&admin, 0, &admin)], &[])
+            .await
+            .expect("initialize_protocol");
+        env
+    }
+
+    /// Fresh bank before `initialize_protocol`; the admin is recorded as the
+    /// program's upgrade authority
+    pub async fn without_protocol() -> Self {
+        let mut program_test = ProgramTest::new("shadowvault", shadowvault::ID, processor!(process_instruction));
+        program_test.prefer_bpf(false);
+        let mut env = Self {
+            ctx: program_test.start_with_context().await,
+            nonce: 0,
+        };
+
+        // the program runs natively, so stand in the ProgramData the
+        // upgradeable loader would hold: bincode of
+        // `UpgradeableLoaderState::ProgramData { slot, upgrade_authority_address }`
+        let mut data = 3u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(env.admin().as_ref());
+        let rent = env.ctx.banks_client.get_rent().await.unwrap();
+        let mut program_data = AccountSharedData::new(rent.minimum_balance(data.len()), data.len(), &bpf_loader_upgradeable::ID);
+        program_data.set_data_from_slice(&data);
+        env.ctx.set_account(&pda::program_data().0, &program_data);
+        env
+    }
+
+    pub fn admin(&self) -> Pubkey {
+        self.ctx.payer.pubkey()
+    }
+
+    /// Sends `ixs` paid by the admin, additionally signed by `signers`
+    pub async fn send(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> SendResult {
+        let tx = self.transaction(ixs, signers).await?;
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    /// Like `send`, returning the event CPI events the transaction emitted, as
+    /// the program received them. `emit!` events are not seen: natively run
+    /// programs print `sol_log_data` to stdout instead of the log collector.
+    pub async fn send_with_events(
+        &mut self,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Vec<ShadowVaultEvent>, BanksClientError> {
+        let tx = self.transaction(ixs, signers).await?;
+        EVENT_CPIS.with(|cpis| cpis.borrow_mut().clear());
+        // unlike `process_transaction`, this runs the program on this thread
+        let processed = self.ctx.banks_client.process_transaction_with_metadata(tx).await?;
+        processed.result.map_err(BanksClientError::TransactionError)?;
+        let inner = EVENT_CPIS.with(|cpis| cpis.take());
+        Ok(decode_inner_instructions(inner.iter().map(|(p, d)| (p, d.as_slice()))))
+    }
+
+    /// Like `send`, decoding the return data the program set
+    pub async fn send_with_return<T: AnchorDeserialize>(
+        &mut self,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<T, BanksClientError> {
+        let tx = self.transaction(ixs, signers).await?;
+        let processed = self.ctx.banks_client.process_transaction_with_metadata(tx).await?;
+        processed.result.map_err(BanksClientError::TransactionError)?;
+        let data = processed
+            .metadata
+            .and_then(|m| m.return_data)
+            .map(|r| r.data)
+            .unwrap_or_default();
+        Ok(views::decode_return_data(&data).expect("return data"))
+    }
+
+    async fn transaction(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> Result<Transaction, BanksClientError> {
+        self.nonce += 1;
+        let mut ixs = ixs.to_vec();
+        ixs.insert(0, ComputeBudgetInstruction::set_compute_unit_price(self.nonce));
+
+        let blockhash = self.ctx.banks_client.get_latest_blockhash().await?;
+        let mut all: Vec<&Keypair> = vec![&self.ctx.payer];
+        all.extend_from_slice(signers);
+        Ok(Transaction::new_signed_with_payer(&ixs, Some(&self.ctx.payer.pubkey()), &all, blockhash))
+    }
+
+    /// Simulates a view instruction paid by the admin and decodes its return data
+    pub async fn view<T: AnchorDeserialize>(&mut self, ix: Instruction) -> Result<T, BanksClientError> {
+        let blockhash = self.ctx.banks_client.get_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(&[ix], Some(&self.ctx.payer.pubkey()), &[&self.ctx.payer], blockhash);
+        let simulation = self.ctx.banks_client.simulate_transaction(tx).await?;
+        if let Some(Err(err)) = simulation.result {
+            return Err(BanksClientError::TransactionError(err));
+        }
+        let data = simulation
+            .simulation_details
+            .and_then(|d| d.return_data)
+            .map(|r| r.data)
+            .unwrap_or_default();
+        Ok(views::decode_return_data(&data).expect("view return data"))
+    }
+
+    /// The bank's current unix timestamp
+    pub async fn now(&mut self) -> i64 {
+        let clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    /// Moves the bank's unix timestamp forward by `secs`
+    pub async fn advance_clock(&mut self, secs: i64) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += secs;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    /// Moves the bank's clock into the next epoch
+    pub async fn advance_epoch(&mut self) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.epoch += 1;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    /// New keypair holding 10 SOL
+    pub async fn funded(&mut self) -> Keypair {
+        let kp = Keypair::new();
+        let ix = system_instruction::transfer(&self.admin(), &kp.pubkey(), 10_000_000_000);
+        self.send(&[ix], &[]).await.expect("fund");
+        kp
+    }
+
+    pub async fn create_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        let admin = self.admin();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let ixs = [
+            system_instruction::create_account(
+                &admin,
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(&spl_token::ID, &mint.pubkey(), &admin, None, 6).unwrap(),
+        ];
+        self.send(&ixs, &[&mint]).await.expect("create mint");
+        mint.pubkey()
+    }
+
+    /// Mints `amount` into `owner`'s ATA, creating it if needed; `owner` may
+    /// be a PDA (e.g. a vault, to simulate a donation to custody)
+    pub async fn mint_to(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let admin = self.admin();
+        let ata = get_associated_token_address(owner, mint);
+        let ixs = [
+            create_associated_token_account_idempotent(&admin, owner, mint, &spl_token::ID),
+            spl_token::instruction::mint_to(&spl_token::ID, mint, &ata, &admin, &[], amount).unwrap(),
+        ];
+        self.send(&ixs, &[]).await.expect("mint_to");
+        ata
+    }
+
+    pub async fn token_balance(&mut self, ata: &Pubkey) -> u64 {
+        let account = self.ctx.banks_client.get_account(*ata).await.unwrap().expect("token account");
+        spl_token::state::Account::unpack(&account.data).unwrap().amount
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.ctx.banks_client.get_balance(*address).await.unwrap()
+    }
+
+    pub async fn exists(&mut self, address: &Pubkey) -> bool {
+        self.ctx.banks_client.get_account(*address).await.unwrap().is_some()
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("account {address} not found"));
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// Rewrites a program account in place, bypassing the program
+    pub async fn overwrite<T: AccountSerialize + AccountDeserialize>(&mut self, address: &Pubkey, edit: impl FnOnce(&mut T)) {
+        let mut account = self.ctx.banks_client.get_account(*address).await.unwrap().unwrap();
+        let mut value = T::try_deserialize(&mut account.data.as_slice()).unwrap();
+        edit(&mut value);
+        let mut data = Vec::with_capacity(account.data.len());
+        value.try_serialize(&mut data).unwrap();
+        data.resize(account.data.len(), 0);
+        account.data = data;
+        self.ctx.set_account(address, &AccountSharedData::from(account));
+    }
+
+    /// Creates the owner's next vault and returns its address
+    pub async fn create_vault(&mut self, owner: &Keypair) -> Pubkey {
+        let vault_id = match self
+            .ctx
+            .banks_client
+            .get_account(pda::owner_counter(&owner.pubkey()).0)
+            .await
+            .unwrap()
+        {
+            Some(account) => {
+                shadowvault::state::OwnerVaultCounter::try_deserialize(&mut account.data.as_slice())
+                    .unwrap()
+                    .next_vault_id
+            }
+            None => 0,
+        };
+        let ix = instructions::initialize_vault(&owner.pubkey(), &owner.pubkey(), vault_id, [7; 32]);
+        self.send(&[ix], &[owner]).await.expect("initialize_vault");
+        pda::vault(&owner.pubkey(), vault_id).0
+    }
+
+    /// Funds `user` with `amount` of `mint` and deposits all of it
+    pub async fn fund_and_deposit(&mut self, user: &Keypair, vault: &Pubkey, mint: &Pubkey, amount: u64) -> SendResult {
+        self.mint_to(mint, &user.pubkey(), amount).await;
+        let ix = instructions::deposit(&user.pubkey(), vault, mint, amount, vec![]);
+        self.send(&[ix], &[user]).await
+    }
+}
+
+/// Vault with one funded mint: (owner, user, vault, mint)
+pub async fn funded_vault(env: &mut TestEnv, amount: u64) -> (Keypair, Keypair, Pubkey, Pubkey) {
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+    env.fund_and_deposit(&user, &vault, &mint, amount).await.unwrap();
+    (owner, user, vault, mint)
+}
+
+/// `funded_vault` plus a second mint, an enabled pair capped at 100 and an
+/// unrestricted strategy 0
+pub async fn trading_vault(env: &mut TestEnv) -> (Keypair, Keypair, Pubkey, Pubkey, Pubkey) {
+    let (owner, user, vault, token_in) = funded_vault(env, 1_000).await;
+    let token_out = env.create_mint().await;
+    env.fund_and_deposit(&user, &vault, &token_out, 1_000).await.unwrap();
+    let ix = instructions::set_trade_pair(&owner.pubkey(), &vault, &token_in, &token_out, true, 100, 100);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ix = instructions::set_strategy_config(&owner.pubkey(), &vault, 0, true, vec![], 0, 0, 0);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    (owner, user, vault, token_in, token_out)
+}
+
+/// Kind-specific terms of the intent `vault_with_intent` submits
+#[derive(Clone, Copy)]
+pub enum IntentTerms {
+    Twap { slices: u16, interval_secs: i64 },
+    Limit { limit_price: u64, expires_at: i64 },
+    Conditional { stop_loss_price: u64, take_profit_price: u64, expires_at: i64 },
+}
+
+/// `trading_vault` plus a registered executor, a price feed at 1.0 and the
+/// user's intent 0 of 50 at 10 bps under `terms`: (user, vault, executor, intent)
+pub async fn vault_with_intent(env: &mut TestEnv, terms: IntentTerms) -> (Keypair, Pubkey, Keypair, TradeIntent) {
+    let (_owner, user, vault, token_in, token_out) = trading_vault(env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+    let ix = instructions::set_price_feed(&admin, &token_in, &token_out, PRICE_SCALE);
+    env.send(&[ix], &[]).await.unwrap();
+
+    let (user_key, amount, max_slippage_bps) = (user.pubkey(), 50, 10);
+    let ix = match terms {
+        IntentTerms::Twap { slices, interval_secs } => instructions::submit_twap_intent(
+            &user_key,
+            &vault,
+            0,
+            &token_in,
+            &token_out,
+            amount,
+            max_slippage_bps,
+            0,
+            slices,
+            interval_secs,
+        ),
+        IntentTerms::Limit { limit_price, expires_at } => instructions::submit_limit_intent(
+            &user_key,
+            &vault,
+            0,
+            &token_in,
+            &token_out,
+            amount,
+            max_slippage_bps,
+            0,
+            limit_price,
+            expires_at,
+        ),
+        IntentTerms::Conditional { stop_loss_price, take_profit_price, expires_at } => {
+            instructions::submit_conditional_intent(
+                &user_key,
+                &vault,
+                0,
+                &token_in,
+                &token_out,
+                amount,
+                max_slippage_bps,
+                0,
+                stop_loss_price,
+                take_profit_price,
+                expires_at,
+            )
+        }
+    };
+    env.send(&[ix], &[&user]).await.unwrap();
+    let intent = env.account(&pda::intent(&user_key, &vault, 0).0).await;
+    (user, vault, executor, intent)
+}
+
+/// Custom program error code carried by a failed transaction
+pub fn error_code(result: SendResult) -> Option<u32> {
+    match result.err()?.unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
+
+/// Asserts that `result` failed with `expected`
+#[track_caller]
+pub fn assert_shadow_error(result: SendResult, expected: ShadowError) {
+    let code = ERROR_CODE_OFFSET + expected as u32;
+    assert_eq!(error_code(result), Some(code), "expected {expected:?} ({code})");
+}
+
+/// Asserts that `result` failed with an Anchor framework error
+#[track_caller]
+pub fn assert_anchor_error(result: SendResult, expected: anchor_lang::error::ErrorCode) {
+    let code: u32 = expected.into();
+    assert_eq!(error_code(result), Some(code), "expected {expected:?} ({code})");
+}
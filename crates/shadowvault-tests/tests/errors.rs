@@ -0,0 +1,764 @@
+//! One failing path per `ShadowError` variant the program can return.
+//! `TradeFailed` is declared but no instruction returns it yet.
+
+use anchor_lang::error::ErrorCode;
+use shadowvault::errors::ShadowError;
+use shadowvault::state::{
+    CustodyLedger, IntentKind, PauseFlags, PriceFeed, ProtocolConfig, StrategyConfig, TradeIntent, VaultAccount,
+    PRICE_SCALE,
+};
+use shadowvault::VaultSummary;
+use shadowvault_client::{instructions, pda};
+use shadowvault_tests::{
+    assert_anchor_error, assert_shadow_error, funded_vault, trading_vault, vault_with_intent, IntentTerms, TestEnv,
+};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+
+/// 2 slices of 25 every 60s
+const TWAP: IntentTerms = IntentTerms::Twap { slices: 2, interval_secs: 60 };
+/// Stop-loss at 0.5
+const STOP_LOSS: IntentTerms = IntentTerms::Conditional {
+    stop_loss_price: PRICE_SCALE / 2,
+    take_profit_price: 0,
+    expires_at: 0,
+};
+
+#[tokio::test]
+async fn unauthorized_withdraw() {
+    let mut env = TestEnv::new().await;
+    let (_owner, user, vault, mint) = funded_vault(&mut env, 1_000).await;
+
+    let ix = instructions::withdraw(&user.pubkey(), &vault, &mint, 1);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::Unauthorized);
+}
+
+#[tokio::test]
+async fn unauthorized_pause_and_resync() {
+    let mut env = TestEnv::new().await;
+    let (_owner, user, vault, mint) = funded_vault(&mut env, 1_000).await;
+
+    let ix = instructions::pause_vault(&user.pubkey(), &vault, PauseFlags::ALL, true);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::Unauthorized);
+    let ix = instructions::reconcile(&user.pubkey(), &vault, &[mint], true);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::Unauthorized);
+}
+
+#[tokio::test]
+async fn zero_amount() {
+    let mut env = TestEnv::new().await;
+    let (owner, user, vault, mint) = funded_vault(&mut env, 1_000).await;
+
+    let ix = instructions::deposit(&user.pubkey(), &vault, &mint, 0, vec![]);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::InvalidAmount);
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 0);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::InvalidAmount);
+}
+
+#[tokio::test]
+async fn paused_deposit() {
+    let mut env = TestEnv::new().await;
+    let (owner, user, vault, mint) = funded_vault(&mut env, 1_000).await;
+
+    let ix = instructions::pause_vault(&owner.pubkey(), &vault, PauseFlags::DEPOSITS, true);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    assert_shadow_error(
+        env.fund_and_deposit(&user, &vault, &mint, 10).await,
+        ShadowError::VaultPaused,
+    );
+}
+
+#[tokio::test]
+async fn overflow() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+
+    // u64::MAX tokens price to u64::MAX * VIRTUAL_SHARES shares
+    assert_shadow_error(
+        env.fund_and_deposit(&user, &vault, &mint, u64::MAX).await,
+        ShadowError::MathOverflow,
+    );
+}
+
+#[tokio::test]
+async fn wrong_bump() {
+    let mut env = TestEnv::new().await;
+    let (_owner, user, vault, mint) = funded_vault(&mut env, 1_000).await;
+
+    env.overwrite::<VaultAccount>(&vault, |v| v.bump = v.bump.wrapping_sub(1)).await;
+    assert_anchor_error(
+        env.fund_and_deposit(&user, &vault, &mint, 10).await,
+        ErrorCode::ConstraintSeeds,
+    );
+}
+
+#[tokio::test]
+async fn invalid_account_in_reconcile() {
+    let mut env = TestEnv::new().await;
+    let (_owner, user, vault, mint) = funded_vault(&mut env, 1_000).await;
+
+    // the user's token account stands in for the custody ATA
+    let mut ix = instructions::reconcile(&user.pubkey(), &vault, &[mint], false);
+    ix.accounts.last_mut().unwrap().pubkey = get_associated_token_address(&user.pubkey(), &mint);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::InvalidAccount);
+}
+
+#[tokio::test]
+async fn invalid_proof() {
+    let mut env = TestEnv::new().await;
+    let (_owner, user, vault, token_in, token_out) = trading_vault(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+    let ix = instructions::submit_trade_intent(&user.pubkey(), &vault, 0, &token_in, &token_out, 50, 10, 0);
+    env.send(&[ix], &[&user]).await.unwrap();
+
+    let ix = instructions::execute_trade(
+        &executor.pubkey(),
+        &vault,
+        &user.pubkey(),
+        0,
+        &token_in,
+        &token_out,
+        0,
+        [1; 32],
+        [0; 64],
+        [3; 32],
+        50,
+    );
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::InvalidProof);
+}
+
+#[tokio::test]
+async fn invalid_pause_flags() {
+    let mut env = TestEnv::new().await;
+    let owner = env.funded().await;
+    let vault = env.create_vault(&owner).await;
+
+    let ix = instructions::pause_vault(&owner.pubkey(), &vault, 0, true);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::InvalidPauseFlags);
+    let ix = instructions::pause_vault(&owner.pubkey(), &vault, 1 << 7, true);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::InvalidPauseFlags);
+}
+
+#[tokio::test]
+async fn invalid_circuit_breaker_config() {
+    let mut env = TestEnv::new().await;
+    let owner = env.funded().await;
+    let vault = env.create_vault(&owner).await;
+
+    let ix = instructions::configure_circuit_breaker(&owner.pubkey(), &vault, -1, 100);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::InvalidCircuitBreakerConfig);
+    let ix = instructions::configure_circuit_breaker(&owner.pubkey(), &vault, 60, 10_001);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::InvalidCircuitBreakerConfig);
+}
+
+#[tokio::test]
+async fn withdrawal_rate_limited() {
+    let mut env = TestEnv::new().await;
+    let (owner, _user, vault, mint) = funded_vault(&mut env, 1_000).await;
+
+    let ix = instructions::set_withdrawal_limit(&owner.pubkey(), &vault, &mint, 100, 0);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 60);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 41);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::WithdrawalRateLimited);
+}
+
+#[tokio::test]
+async fn invalid_withdrawal_limit() {
+    let mut env = TestEnv::new().await;
+    let owner = env.funded().await;
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+
+    let ix = instructions::set_withdrawal_limit(&owner.pubkey(), &vault, &mint, 0, 10_001);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::InvalidWithdrawalLimit);
+}
+
+#[tokio::test]
+async fn incomplete_reconciliation() {
+    let mut env = TestEnv::new().await;
+    let (_owner, user, vault, _mint) = funded_vault(&mut env, 1_000).await;
+
+    let ix = instructions::reconcile(&user.pubkey(), &vault, &[], false);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::IncompleteReconciliation);
+}
+
+#[tokio::test]
+async fn zero_shares() {
+    let mut env = TestEnv::new().await;
+    let (owner, user, vault, mint) = funded_vault(&mut env, 1).await;
+
+    // donations never reach the book, so inflate it directly: 1 token is
+    // then worth less than 1 share
+    env.mint_to(&mint, &vault, 1_000_000_000).await;
+    let ix = instructions::reconcile(&owner.pubkey(), &vault, &[mint], true);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    env.overwrite::<CustodyLedger>(&pda::ledger(&vault, &mint).0, |l| l.balance = 1_000_000_001).await;
+    assert_shadow_error(env.fund_and_deposit(&user, &vault, &mint, 1).await, ShadowError::ZeroShares);
+}
+
+#[tokio::test]
+async fn not_allowlisted() {
+    let mut env = TestEnv::new().await;
+    let (owner, user, vault, mint) = funded_vault(&mut env, 1_000).await;
+
+    let ix = instructions::set_allowlist(&owner.pubkey(), &vault, [9; 32], true);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    assert_shadow_error(
+        env.fund_and_deposit(&user, &vault, &mint, 10).await,
+        ShadowError::NotAllowlisted,
+    );
+}
+
+#[tokio::test]
+async fn pair_not_allowed() {
+    let mut env = TestEnv::new().await;
+    let (owner, user, vault, token_in, token_out) = trading_vault(&mut env).await;
+
+    let ix = instructions::set_trade_pair(&owner.pubkey(), &vault, &token_in, &token_out, false, 0, 0);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ix = instructions::submit_trade_intent(&user.pubkey(), &vault, 0, &token_in, &token_out, 50, 10, 0);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::PairNotAllowed);
+}
+
+#[tokio::test]
+async fn pair_limit_exceeded() {
+    let mut env = TestEnv::new().await;
+    let (_owner, user, vault, token_in, token_out) = trading_vault(&mut env).await;
+
+    let ix = instructions::submit_trade_intent(&user.pubkey(), &vault, 0, &token_in, &token_out, 101, 10, 0);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::PairLimitExceeded);
+    let ix = instructions::submit_trade_intent(&user.pubkey(), &vault, 0, &token_in, &token_out, 50, 101, 0);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::PairLimitExceeded);
+}
+
+#[tokio::test]
+async fn invalid_pair_config() {
+    let mut env = TestEnv::new().await;
+    let owner = env.funded().await;
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+
+    let ix = instructions::set_trade_pair(&owner.pubkey(), &vault, &mint, &mint, true, 0, 0);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::InvalidPairConfig);
+}
+
+#[tokio::test]
+async fn strategy_disabled() {
+    let mut env = TestEnv::new().await;
+    let (owner, user, vault, token_in, token_out) = trading_vault(&mut env).await;
+
+    let ix = instructions::set_strategy_config(&owner.pubkey(), &vault, 0, false, vec![], 0, 0, 0);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ix = instructions::submit_trade_intent(&user.pubkey(), &vault, 0, &token_in, &token_out, 50, 10, 0);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::StrategyDisabled);
+}
+
+#[tokio::test]
+async fn strategy_pair_not_allowed() {
+    let mut env = TestEnv::new().await;
+    let (owner, user, vault, token_in, token_out) = trading_vault(&mut env).await;
+
+    let other = pda::trade_pair(&vault, &token_out, &token_in).0;
+    let ix = instructions::set_strategy_config(&owner.pubkey(), &vault, 0, true, vec![other], 0, 0, 0);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ix = instructions::submit_trade_intent(&user.pubkey(), &vault, 0, &token_in, &token_out, 50, 10, 0);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::StrategyPairNotAllowed);
+}
+
+#[tokio::test]
+async fn strategy_limit_exceeded() {
+    let mut env = TestEnv::new().await;
+    let (owner, user, vault, token_in, token_out) = trading_vault(&mut env).await;
+
+    let ix = instructions::set_strategy_config(&owner.pubkey(), &vault, 0, true, vec![], 40, 5, 0);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ix = instructions::submit_trade_intent(&user.pubkey(), &vault, 0, &token_in, &token_out, 50, 5, 0);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::StrategyLimitExceeded);
+    let ix = instructions::submit_trade_intent(&user.pubkey(), &vault, 0, &token_in, &token_out, 40, 10, 0);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::StrategyLimitExceeded);
+}
+
+#[tokio::test]
+async fn strategy_cooling_down() {
+    let mut env = TestEnv::new().await;
+    let (owner, user, vault, token_in, token_out) = trading_vault(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+    let ix = instructions::set_strategy_config(&owner.pubkey(), &vault, 0, true, vec![], 0, 0, 3_600);
+    env.send(&[ix], &[&owner]).await.unwrap();
+
+    let second = env.funded().await;
+    for u in [&user, &second] {
+        let ix = instructions::submit_trade_intent(&u.pubkey(), &vault, 0, &token_in, &token_out, 50, 10, 0);
+        env.send(&[ix], &[u]).await.unwrap();
+    }
+    let execute = |u: &Keypair| {
+        instructions::execute_trade(
+            &executor.pubkey(),
+            &vault,
+            &u.pubkey(),
+            0,
+            &token_in,
+            &token_out,
+            0,
+            [1; 32],
+            [2; 64],
+            [3; 32],
+            50,
+        )
+    };
+    env.send(&[execute(&user)], &[&executor]).await.unwrap();
+    assert_shadow_error(
+        env.send(&[execute(&second)], &[&executor]).await,
+        ShadowError::StrategyCoolingDown,
+    );
+}
+
+#[tokio::test]
+async fn invalid_strategy_config() {
+    let mut env = TestEnv::new().await;
+    let owner = env.funded().await;
+    let vault = env.create_vault(&owner).await;
+
+    let pairs = vec![Pubkey::new_unique(); StrategyConfig::MAX_ALLOWED_PAIRS + 1];
+    let ix = instructions::set_strategy_config(&owner.pubkey(), &vault, 0, true, pairs, 0, 0, 0);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::InvalidStrategyConfig);
+    let ix = instructions::set_strategy_config(&owner.pubkey(), &vault, 0, true, vec![], 0, 10_001, 0);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::InvalidStrategyConfig);
+    let ix = instructions::set_strategy_config(&owner.pubkey(), &vault, 0, true, vec![], 0, 0, -1);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::InvalidStrategyConfig);
+}
+
+#[tokio::test]
+async fn invalid_twap_schedule() {
+    let mut env = TestEnv::new().await;
+    let (_owner, user, vault, token_in, token_out) = trading_vault(&mut env).await;
+
+    for (amount, slices, interval) in [(50, 1, 60), (50, 101, 60), (2, 3, 60), (50, 2, 0)] {
+        let ix = instructions::submit_twap_intent(
+            &user.pubkey(),
+            &vault,
+            0,
+            &token_in,
+            &token_out,
+            amount,
+            10,
+            0,
+            slices,
+            interval,
+        );
+        assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::InvalidTwapSchedule);
+    }
+}
+
+#[tokio::test]
+async fn wrong_intent_kind() {
+    let mut env = TestEnv::new().await;
+    let (user, vault, executor, intent) = vault_with_intent(&mut env, TWAP).await;
+
+    let ix = instructions::execute_trade(
+        &executor.pubkey(),
+        &vault,
+        &user.pubkey(),
+        0,
+        &intent.token_in,
+        &intent.token_out,
+        0,
+        [1; 32],
+        [2; 64],
+        [3; 32],
+        50,
+    );
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::WrongIntentKind);
+}
+
+#[tokio::test]
+async fn slice_not_due() {
+    let mut env = TestEnv::new().await;
+    let (user, vault, executor, _) = vault_with_intent(&mut env, TWAP).await;
+    let intent_key = pda::intent(&user.pubkey(), &vault, 0).0;
+
+    let intent: TradeIntent = env.account(&intent_key).await;
+    let ix = instructions::execute_twap_slice(&executor.pubkey(), &intent, [1; 32], [2; 64]);
+    env.send(&[ix], &[&executor]).await.unwrap();
+    let intent: TradeIntent = env.account(&intent_key).await;
+    let ix = instructions::execute_twap_slice(&executor.pubkey(), &intent, [1; 32], [2; 64]);
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::SliceNotDue);
+}
+
+#[tokio::test]
+async fn intent_filled() {
+    let mut env = TestEnv::new().await;
+    let (user, vault, executor, _) = vault_with_intent(&mut env, TWAP).await;
+    let intent_key = pda::intent(&user.pubkey(), &vault, 0).0;
+
+    for _ in 0..2 {
+        let intent: TradeIntent = env.account(&intent_key).await;
+        let ix = instructions::execute_twap_slice(&executor.pubkey(), &intent, [1; 32], [2; 64]);
+        env.send(&[ix], &[&executor]).await.unwrap();
+        env.advance_clock(60).await;
+    }
+    let intent: TradeIntent = env.account(&intent_key).await;
+    let ix = instructions::execute_twap_slice(&executor.pubkey(), &intent, [1; 32], [2; 64]);
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::IntentFilled);
+}
+
+/// Fills `fill_amount` of `user`'s intent on `vault`, with the price feed
+/// for a limit intent
+async fn execute_fill(
+    env: &mut TestEnv,
+    executor: &Pubkey,
+    user: &Pubkey,
+    vault: &Pubkey,
+    fill_amount: u64,
+) -> Instruction {
+    let intent: TradeIntent = env.account(&pda::intent(user, vault, 0).0).await;
+    let execute = match intent.kind {
+        IntentKind::Limit => instructions::execute_trade_with_feed,
+        _ => instructions::execute_trade,
+    };
+    execute(
+        executor,
+        vault,
+        user,
+        0,
+        &intent.token_in,
+        &intent.token_out,
+        intent.strategy_type,
+        [1; 32],
+        [2; 64],
+        [3; 32],
+        fill_amount,
+    )
+}
+
+#[tokio::test]
+async fn partial_fill_not_allowed() {
+    let mut env = TestEnv::new().await;
+    let (_owner, user, vault, token_in, token_out) = trading_vault(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+    let ix = instructions::submit_trade_intent(&user.pubkey(), &vault, 0, &token_in, &token_out, 50, 10, 0);
+    env.send(&[ix], &[&user]).await.unwrap();
+
+    let ix = execute_fill(&mut env, &executor.pubkey(), &user.pubkey(), &vault, 20).await;
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::PartialFillNotAllowed);
+}
+
+#[tokio::test]
+async fn limit_price_not_met() {
+    let mut env = TestEnv::new().await;
+    // the feed prices the fill at 1:1, below a limit of 2 out per in
+    let terms = IntentTerms::Limit { limit_price: 2 * PRICE_SCALE, expires_at: 0 };
+    let (user, vault, executor, _) = vault_with_intent(&mut env, terms).await;
+
+    let ix = execute_fill(&mut env, &executor.pubkey(), &user.pubkey(), &vault, 50).await;
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::LimitPriceNotMet);
+}
+
+#[tokio::test]
+async fn price_feed_required() {
+    let mut env = TestEnv::new().await;
+    let terms = IntentTerms::Limit { limit_price: PRICE_SCALE, expires_at: 0 };
+    let (user, vault, executor, _) = vault_with_intent(&mut env, terms).await;
+    let intent: TradeIntent = env.account(&pda::intent(&user.pubkey(), &vault, 0).0).await;
+
+    // a limit fill without the feed cannot be priced
+    let ix = instructions::execute_trade(
+        &executor.pubkey(),
+        &vault,
+        &user.pubkey(),
+        0,
+        &intent.token_in,
+        &intent.token_out,
+        intent.strategy_type,
+        [1; 32],
+        [2; 64],
+        [3; 32],
+        50,
+    );
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::PriceFeedRequired);
+
+    // nor with one too old to act on
+    env.advance_clock(PriceFeed::MAX_AGE_SECS + 1).await;
+    let ix = execute_fill(&mut env, &executor.pubkey(), &user.pubkey(), &vault, 50).await;
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::StalePrice);
+}
+
+#[tokio::test]
+async fn intent_expired() {
+    let mut env = TestEnv::new().await;
+    let now = env.now().await;
+    let terms = IntentTerms::Limit { limit_price: PRICE_SCALE, expires_at: now + 60 };
+    let (user, vault, executor, _) = vault_with_intent(&mut env, terms).await;
+
+    env.advance_clock(61).await;
+    let ix = execute_fill(&mut env, &executor.pubkey(), &user.pubkey(), &vault, 50).await;
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::IntentExpired);
+}
+
+#[tokio::test]
+async fn intent_cancelled() {
+    let mut env = TestEnv::new().await;
+    let terms = IntentTerms::Limit { limit_price: PRICE_SCALE, expires_at: 0 };
+    let (user, vault, executor, _) = vault_with_intent(&mut env, terms).await;
+
+    env.send(&[instructions::cancel_intent(&user.pubkey(), &vault, 0)], &[&user])
+        .await
+        .unwrap();
+    let ix = execute_fill(&mut env, &executor.pubkey(), &user.pubkey(), &vault, 50).await;
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::IntentCancelled);
+    let ix = instructions::cancel_intent(&user.pubkey(), &vault, 0);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::IntentCancelled);
+}
+
+#[tokio::test]
+async fn invalid_limit_order() {
+    let mut env = TestEnv::new().await;
+    let (_owner, user, vault, token_in, token_out) = trading_vault(&mut env).await;
+    let now = env.now().await;
+
+    for (limit_price, expires_at) in [(0, 0), (PRICE_SCALE, now)] {
+        let ix = instructions::submit_limit_intent(
+            &user.pubkey(),
+            &vault,
+            0,
+            &token_in,
+            &token_out,
+            50,
+            10,
+            0,
+            limit_price,
+            expires_at,
+        );
+        assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::InvalidLimitOrder);
+    }
+}
+
+#[tokio::test]
+async fn invalid_trigger() {
+    let mut env = TestEnv::new().await;
+    let (_owner, user, vault, token_in, token_out) = trading_vault(&mut env).await;
+
+    for (stop_loss, take_profit) in [(0, 0), (PRICE_SCALE, PRICE_SCALE), (2 * PRICE_SCALE, PRICE_SCALE)] {
+        let ix = instructions::submit_conditional_intent(
+            &user.pubkey(),
+            &vault,
+            0,
+            &token_in,
+            &token_out,
+            50,
+            10,
+            0,
+            stop_loss,
+            take_profit,
+            0,
+        );
+        assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::InvalidTrigger);
+    }
+}
+
+#[tokio::test]
+async fn invalid_price() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin();
+    let (token_in, token_out) = (env.create_mint().await, env.create_mint().await);
+
+    let ix = instructions::set_price_feed(&admin, &token_in, &token_out, 0);
+    assert_shadow_error(env.send(&[ix], &[]).await, ShadowError::InvalidPrice);
+    let ix = instructions::set_price_feed(&admin, &token_in, &token_in, PRICE_SCALE);
+    assert_shadow_error(env.send(&[ix], &[]).await, ShadowError::InvalidPrice);
+}
+
+#[tokio::test]
+async fn stale_price() {
+    let mut env = TestEnv::new().await;
+    let (_user, _vault, executor, intent) = vault_with_intent(&mut env, STOP_LOSS).await;
+
+    env.advance_clock(PriceFeed::MAX_AGE_SECS + 1).await;
+    let ix = instructions::trigger_intent(&executor.pubkey(), &intent);
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::StalePrice);
+}
+
+#[tokio::test]
+async fn trigger_not_met() {
+    let mut env = TestEnv::new().await;
+    let (_user, _vault, executor, intent) = vault_with_intent(&mut env, STOP_LOSS).await;
+
+    let ix = instructions::trigger_intent(&executor.pubkey(), &intent);
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::TriggerNotMet);
+}
+
+#[tokio::test]
+async fn intent_not_triggered() {
+    let mut env = TestEnv::new().await;
+    let (user, vault, executor, _intent) = vault_with_intent(&mut env, STOP_LOSS).await;
+
+    let ix = execute_fill(&mut env, &executor.pubkey(), &user.pubkey(), &vault, 50).await;
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::IntentNotTriggered);
+}
+
+#[tokio::test]
+async fn already_triggered() {
+    let mut env = TestEnv::new().await;
+    let (_user, _vault, executor, intent) = vault_with_intent(&mut env, STOP_LOSS).await;
+    let admin = env.admin();
+
+    let ix = instructions::set_price_feed(&admin, &intent.token_in, &intent.token_out, PRICE_SCALE / 4);
+    env.send(&[ix], &[]).await.unwrap();
+    let ix = instructions::trigger_intent(&executor.pubkey(), &intent);
+    env.send(std::slice::from_ref(&ix), &[&executor]).await.unwrap();
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::AlreadyTriggered);
+}
+
+#[tokio::test]
+async fn tip_mismatch() {
+    let mut env = TestEnv::new().await;
+    let terms = IntentTerms::Limit { limit_price: PRICE_SCALE, expires_at: 0 };
+    let (user, vault, _executor, _) = vault_with_intent(&mut env, terms).await;
+    let mint = env.create_mint().await;
+    env.mint_to(&mint, &user.pubkey(), 10).await;
+
+    let ix = instructions::tip_intent_lamports(&user.pubkey(), &vault, 0, 10);
+    env.send(&[ix], &[&user]).await.unwrap();
+    let ix = instructions::tip_intent_tokens(&user.pubkey(), &vault, 0, &mint, 10);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::TipMismatch);
+    let ix = instructions::tip_intent_lamports(&user.pubkey(), &vault, 0, 0);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::InvalidAmount);
+}
+
+#[tokio::test]
+async fn protocol_paused() {
+    let mut env = TestEnv::new().await;
+    let (owner, user, vault, mint) = funded_vault(&mut env, 1_000).await;
+    let admin = env.admin();
+    env.send(&[instructions::set_protocol_pause(&admin, true)], &[]).await.unwrap();
+
+    let ix = instructions::initialize_vault(&owner.pubkey(), &owner.pubkey(), 1, [0; 32]);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::ProtocolPaused);
+    assert_shadow_error(
+        env.fund_and_deposit(&user, &vault, &mint, 10).await,
+        ShadowError::ProtocolPaused,
+    );
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 10);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::ProtocolPaused);
+}
+
+#[tokio::test]
+async fn protocol_paused_blocks_triggers_and_tips() {
+    let mut env = TestEnv::new().await;
+    let (user, vault, executor, intent) = vault_with_intent(&mut env, STOP_LOSS).await;
+    let admin = env.admin();
+    let ix = instructions::set_price_feed(&admin, &intent.token_in, &intent.token_out, PRICE_SCALE / 4);
+    env.send(&[ix], &[]).await.unwrap();
+    let mint = env.create_mint().await;
+    env.mint_to(&mint, &user.pubkey(), 10).await;
+    env.send(&[instructions::set_protocol_pause(&admin, true)], &[]).await.unwrap();
+
+    let ix = instructions::trigger_intent(&executor.pubkey(), &intent);
+    assert_shadow_error(env.send(&[ix], &[&executor]).await, ShadowError::ProtocolPaused);
+    let ix = instructions::tip_intent_lamports(&user.pubkey(), &vault, 0, 10);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::ProtocolPaused);
+    let ix = instructions::tip_intent_tokens(&user.pubkey(), &vault, 0, &mint, 10);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::ProtocolPaused);
+
+    env.send(&[instructions::set_protocol_pause(&admin, false)], &[]).await.unwrap();
+    let ix = instructions::trigger_intent(&executor.pubkey(), &intent);
+    env.send(&[ix], &[&executor]).await.unwrap();
+}
+
+#[tokio::test]
+async fn unauthorized_executor() {
+    let mut env = TestEnv::new().await;
+    let (_owner, user, vault, token_in, token_out) = trading_vault(&mut env).await;
+    let ix = instructions::submit_trade_intent(&user.pubkey(), &vault, 0, &token_in, &token_out, 50, 10, 0);
+    env.send(&[ix], &[&user]).await.unwrap();
+
+    let stranger = env.funded().await;
+    let ix = instructions::execute_trade(
+        &stranger.pubkey(),
+        &vault,
+        &user.pubkey(),
+        0,
+        &token_in,
+        &token_out,
+        0,
+        [1; 32],
+        [2; 64],
+        [3; 32],
+        50,
+    );
+    assert_shadow_error(env.send(&[ix], &[&stranger]).await, ShadowError::UnauthorizedExecutor);
+}
+
+#[tokio::test]
+async fn protocol_init_requires_upgrade_authority() {
+    let mut env = TestEnv::without_protocol().await;
+    let stranger = env.funded().await;
+
+    let ix = instructions::initialize_protocol(&stranger.pubkey(), 0, &stranger.pubkey());
+    assert_shadow_error(env.send(&[ix], &[&stranger]).await, ShadowError::Unauthorized);
+    let admin = env.admin();
+    env.send(&[instructions::initialize_protocol(&admin, 0, &admin)], &[])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn invalid_protocol_config() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin();
+
+    let ix = instructions::set_protocol_fee(&admin, ProtocolConfig::MAX_PROTOCOL_FEE_BPS + 1, &admin);
+    assert_shadow_error(env.send(&[ix], &[]).await, ShadowError::InvalidProtocolConfig);
+}
+
+#[tokio::test]
+async fn view_too_large() {
+    let mut env = TestEnv::new().await;
+    let (_, _, vault, mint) = funded_vault(&mut env, 100).await;
+
+    let mints = vec![mint; shadowvault::instructions::MAX_SUMMARY_MINTS + 1];
+    let result = env.view::<VaultSummary>(instructions::get_vault_summary(&vault, &mints)).await;
+    assert_shadow_error(result.map(drop), ShadowError::ViewTooLarge);
+}
+
+#[tokio::test]
+async fn too_many_executors() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin();
+
+    for _ in 0..ProtocolConfig::MAX_EXECUTORS {
+        let ix = instructions::set_executor(&admin, &Pubkey::new_unique(), true);
+        env.send(&[ix], &[]).await.unwrap();
+    }
+    let ix = instructions::set_executor(&admin, &Pubkey::new_unique(), true);
+    assert_shadow_error(env.send(&[ix], &[]).await, ShadowError::TooManyExecutors);
+}
+
+#[tokio::test]
+async fn non_admin_cannot_update_protocol() {
+    let mut env = TestEnv::new().await;
+    let stranger = env.funded().await;
+
+    let ix = instructions::set_protocol_pause(&stranger.pubkey(), true);
+    assert_shadow_error(env.send(&[ix], &[&stranger]).await, ShadowError::Unauthorized);
+    let ix = instructions::accept_protocol_admin(&stranger.pubkey());
+    assert_shadow_error(env.send(&[ix], &[&stranger]).await, ShadowError::Unauthorized);
+}
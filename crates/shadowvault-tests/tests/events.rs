@@ -0,0 +1,230 @@
+//! Event decoding: every event survives the event CPI encoding, payloads are
+//! only accepted from this program, and transactions decode end to end.
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::{AnchorDeserialize, AnchorSerialize, Event};
+use base64::Engine;
+use shadowvault::event_decoder::{decode_event_cpi, decode_inner_instructions, ShadowVaultEvent, EVENT_NAMES};
+use shadowvault::{CircuitBreakerConfigured, Deposited, EVENT_SCHEMA_VERSION};
+use shadowvault_client::{events, instructions};
+use shadowvault_tests::TestEnv;
+use solana_sdk::instruction::{CompiledInstruction, Instruction, InstructionError};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::{Transaction, TransactionError, VersionedTransaction};
+use solana_transaction_status::{
+    ConfirmedTransactionWithStatusMeta, EncodedConfirmedTransactionWithStatusMeta, InnerInstruction,
+    InnerInstructions, TransactionStatusMeta, TransactionWithStatusMeta, UiTransactionEncoding,
+    VersionedTransactionWithStatusMeta,
+};
+
+/// Round-trips each named event through `EVENT_IX_TAG_LE ++ disc ++ borsh`
+/// and returns the names covered
+macro_rules! round_trip {
+    ($($name:ident),* $(,)?) => {{
+        let mut covered = Vec::new();
+        $(
+            // all-zero bytes are a valid borsh encoding of every event field
+            let event = shadowvault::$name::deserialize(&mut &[0u8; 1024][..]).unwrap();
+            let decoded = decode_event_cpi(&event_cpi_data(&event)).expect(stringify!($name));
+            let ShadowVaultEvent::$name(decoded) = decoded else {
+                panic!("{} decoded as {}", stringify!($name), decoded.name());
+            };
+            assert_eq!(decoded.try_to_vec().unwrap(), event.try_to_vec().unwrap());
+            covered.push(stringify!($name));
+        )*
+        covered
+    }};
+}
+
+fn event_cpi_data(event: &impl Event) -> Vec<u8> {
+    let mut data = EVENT_IX_TAG_LE.to_vec();
+    data.extend(event.data());
+    data
+}
+
+fn deposited() -> Deposited {
+    Deposited {
+        version: EVENT_SCHEMA_VERSION,
+        seq: 7,
+        vault: Pubkey::new_unique(),
+        user: Pubkey::new_unique(),
+        beneficiary: Pubkey::new_unique(),
+        amount: 500,
+        shares: 500_000,
+        new_tvl: 1_500,
+        timestamp: 1_700_000_000,
+    }
+}
+
+fn breaker_configured() -> CircuitBreakerConfigured {
+    CircuitBreakerConfigured {
+        version: EVENT_SCHEMA_VERSION,
+        seq: 8,
+        vault: Pubkey::new_unique(),
+        window_secs: 3_600,
+        threshold_bps: 5_000,
+        timestamp: 1_700_000_000,
+    }
+}
+
+#[test]
+fn every_event_round_trips_through_event_cpi_data() {
+    let covered = round_trip!(
+        VaultInitialized,
+        Deposited,
+        TradeIntentSubmitted,
+        TradeExecuted,
+        IntentTriggered,
+        IntentTipped,
+        IntentCancelled,
+        Withdrawn,
+        VaultPaused,
+        GuardianUpdated,
+        CircuitBreakerConfigured,
+        CircuitBreakerTripped,
+        WithdrawalLimitUpdated,
+        ReconciliationReport,
+        SurplusAllocated,
+        AllowlistUpdated,
+        TradePairUpdated,
+        StrategyConfigUpdated,
+        ProtocolInitialized,
+        ProtocolFeeUpdated,
+        ProtocolPauseSet,
+        ExecutorUpdated,
+        ProtocolAdminTransferStarted,
+        ProtocolAdminTransferred,
+        PriceFeedUpdated,
+    );
+    assert_eq!(covered, EVENT_NAMES);
+
+    let event = deposited();
+    let Some(ShadowVaultEvent::Deposited(decoded)) = decode_event_cpi(&event_cpi_data(&event)) else {
+        panic!("Deposited did not decode");
+    };
+    assert_eq!((decoded.user, decoded.beneficiary), (event.user, event.beneficiary));
+    assert_eq!((decoded.seq, decoded.shares), (7, 500_000));
+
+    // missing tag, unknown discriminator, truncated body
+    assert!(decode_event_cpi(&event.data()).is_none());
+    assert!(decode_event_cpi(&[&EVENT_IX_TAG_LE[..], &[0; 40]].concat()).is_none());
+    let data = event_cpi_data(&event);
+    assert!(decode_event_cpi(&data[..data.len() - 1]).is_none());
+}
+
+#[test]
+fn payloads_from_foreign_programs_are_ignored() {
+    let data = event_cpi_data(&deposited());
+    let foreign = Pubkey::new_unique();
+    let inner = [(&foreign, data.as_slice()), (&shadowvault::ID, data.as_slice())];
+    let decoded = decode_inner_instructions(inner);
+    assert_eq!(decoded.len(), 1);
+
+    // a `Program data:` line only counts while this program is on top of the stack
+    let line = format!("Program data: {}", base64::engine::general_purpose::STANDARD.encode(breaker_configured().data()));
+    let logs: Vec<String> = [
+        format!("Program {} invoke [1]", shadowvault::ID),
+        format!("Program {foreign} invoke [2]"),
+        line.clone(),
+        format!("Program {foreign} success"),
+        line,
+        format!("Program {} success", shadowvault::ID),
+    ]
+    .into();
+    let decoded = events::parse_logs(&logs);
+    assert_eq!(decoded.len(), 1);
+    assert_eq!(decoded[0].name(), "CircuitBreakerConfigured");
+}
+
+/// A confirmed transaction invoking this program and a look-alike, as RPC
+/// returns it with base64 encoding
+fn confirmed_transaction(status: Result<(), TransactionError>) -> EncodedConfirmedTransactionWithStatusMeta {
+    let (payer, foreign) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let message = Message::new(
+        &[
+            Instruction::new_with_bytes(shadowvault::ID, &[], vec![]),
+            Instruction::new_with_bytes(foreign, &[], vec![]),
+        ],
+        Some(&payer),
+    );
+    let index_of = |key: &Pubkey| message.account_keys.iter().position(|k| k == key).unwrap() as u8;
+    let inner = |program: &Pubkey, data: Vec<u8>| InnerInstruction {
+        instruction: CompiledInstruction::new_from_raw_parts(index_of(program), data, vec![]),
+        stack_height: Some(2),
+    };
+    let data = event_cpi_data(&deposited());
+    let inner_instructions = vec![
+        InnerInstructions {
+            index: 0,
+            instructions: vec![inner(&shadowvault::ID, data.clone())],
+        },
+        InnerInstructions {
+            index: 1,
+            instructions: vec![inner(&foreign, data)],
+        },
+    ];
+
+    let line = format!("Program data: {}", base64::engine::general_purpose::STANDARD.encode(breaker_configured().data()));
+    let log_messages = vec![
+        format!("Program {} invoke [1]", shadowvault::ID),
+        line.clone(),
+        format!("Program {} success", shadowvault::ID),
+        format!("Program {foreign} invoke [1]"),
+        line,
+        format!("Program {foreign} success"),
+    ];
+
+    ConfirmedTransactionWithStatusMeta {
+        slot: 1,
+        tx_with_meta: TransactionWithStatusMeta::Complete(VersionedTransactionWithStatusMeta {
+            transaction: VersionedTransaction::from(Transaction::new_unsigned(message)),
+            meta: TransactionStatusMeta {
+                status,
+                inner_instructions: Some(inner_instructions),
+                log_messages: Some(log_messages),
+                ..TransactionStatusMeta::default()
+            },
+        }),
+        block_time: None,
+    }
+    .encode(UiTransactionEncoding::Base64, Some(0))
+    .unwrap()
+}
+
+#[test]
+fn parse_transaction_reads_inner_instructions_then_logs() {
+    let decoded = events::parse_transaction(&confirmed_transaction(Ok(()))).unwrap();
+    let names: Vec<&str> = decoded.iter().map(|e| e.name()).collect();
+    assert_eq!(names, ["Deposited", "CircuitBreakerConfigured"]);
+
+    let failed = Err(TransactionError::InstructionError(0, InstructionError::Custom(1)));
+    assert!(events::parse_transaction(&confirmed_transaction(failed)).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn program_test_transactions_decode_their_events() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+
+    env.mint_to(&mint, &user.pubkey(), 300).await;
+    let ix = instructions::deposit(&user.pubkey(), &vault, &mint, 300, vec![]);
+    let decoded = env.send_with_events(&[ix], &[&user]).await.unwrap();
+    let [ShadowVaultEvent::Deposited(deposited)] = decoded.as_slice() else {
+        panic!("expected one Deposited, got {decoded:?}");
+    };
+    assert_eq!((deposited.vault, deposited.user, deposited.beneficiary), (vault, user.pubkey(), user.pubkey()));
+    assert_eq!((deposited.amount, deposited.new_tvl), (300, 300));
+
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 100);
+    let decoded = env.send_with_events(&[ix], &[&owner]).await.unwrap();
+    let [ShadowVaultEvent::Withdrawn(withdrawn)] = decoded.as_slice() else {
+        panic!("expected one Withdrawn, got {decoded:?}");
+    };
+    assert_eq!((withdrawn.vault, withdrawn.authority), (vault, owner.pubkey()));
+    assert_eq!((withdrawn.amount, withdrawn.new_tvl), (100, 200));
+    assert_eq!(withdrawn.seq, deposited.seq + 1);
+}
@@ -0,0 +1,599 @@
+//! Seeded property test: accounting invariants under random operation
+//! sequences, run in-process on `solana-program-test` (not a coverage-guided
+//! fuzz harness).
+//!
+//! A reference model predicts the outcome of every step, down to the error a
+//! paused or over-drawn vault must return, and after each step the on-chain
+//! books are compared against it:
+//! - vault TVL is the sum of its ledgers, and each ledger equals its custody
+//!   balance less direct donations
+//! - position shares sum to the ledger's total shares, and nothing leaves
+//!   custody beyond the booked balance
+//! - vault ids, execution counts and event sequences only move forward
+//! - paused vaults, and a paused protocol, reject what their flags cover
+//!
+//! Runs are seeded and a failure names its seed; replay it with
+//! `SHADOWVAULT_PROPTEST_SEED=<seed> cargo test -p shadowvault-tests --test invariants`.
+//! `SHADOWVAULT_PROPTEST_RUNS` and `SHADOWVAULT_PROPTEST_STEPS` widen the search.
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use shadowvault::errors::ShadowError;
+use shadowvault::state::{
+    CustodyLedger, ExecutionHistory, OwnerVaultCounter, PauseFlags, ProtocolConfig, UserPosition,
+    VaultAccount,
+};
+use shadowvault_client::{instructions, pda};
+use shadowvault_tests::{error_code, SendResult, TestEnv};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+
+const USERS: usize = 3;
+const OWNERS: usize = 2;
+const MINTS: usize = 3;
+const MAX_VAULTS: usize = 5;
+// every vault allows these (token_in, token_out) mint indices
+const PAIRS: [(usize, usize); 2] = [(0, 1), (1, 2)];
+const PAIR_MAX_AMOUNT: u64 = 10_000;
+const PAIR_MAX_SLIPPAGE_BPS: u16 = 100;
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    InitVault { owner: usize },
+    Deposit { user: usize, vault: usize, mint: usize, amount: u64 },
+    // direct transfer into custody, bypassing the program
+    Donate { vault: usize, mint: usize, amount: u64 },
+    Submit { user: usize, vault: usize, pair: usize, amount: u64, slippage_bps: u16 },
+    Execute { user: usize, vault: usize },
+    // `as_user` signs in place of the owner
+    Withdraw { vault: usize, mint: usize, amount: u64, as_user: Option<usize> },
+    Pause { vault: usize, flags: u8, pause: bool },
+    ProtocolPause { paused: bool },
+}
+
+/// `Err(None)` is any failure raised before the handler runs (missing or
+/// already-initialized accounts) or by the token program
+type Expected = Result<(), Option<ShadowError>>;
+
+/// One mint's books within a vault
+#[derive(Default, Clone, Copy)]
+struct Book {
+    ledger_open: bool,
+    custody_open: bool,
+    balance: u64,
+    total_shares: u64,
+    custody: u64,
+    positions: [u64; USERS],
+}
+
+impl Book {
+    fn shares_for_deposit(&self, amount: u64) -> u64 {
+        let ledger = CustodyLedger {
+            vault: Pubkey::default(),
+            mint: Pubkey::default(),
+            balance: self.balance,
+            total_shares: self.total_shares,
+            unallocated: 0,
+            bump: 0,
+        };
+        ledger.shares_for_deposit(amount).unwrap()
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Intent {
+    pair: usize,
+    amount: u64,
+    executed: bool,
+}
+
+struct VaultModel {
+    address: Pubkey,
+    owner: usize,
+    tvl: u64,
+    pause_flags: u8,
+    execution_count: u64,
+    event_seq: u64,
+    books: [Book; MINTS],
+    intents: [Option<Intent>; USERS],
+}
+
+impl VaultModel {
+    fn is_paused(&self, flag: u8) -> bool {
+        self.pause_flags & flag != 0
+    }
+}
+
+#[derive(Default)]
+struct Model {
+    vaults: Vec<VaultModel>,
+    next_vault_id: [u64; OWNERS],
+    protocol_paused: bool,
+    protocol_event_seq: u64,
+    wallets: [[u64; MINTS]; USERS],
+    // what each owner has withdrawn, per mint, across their vaults
+    received: [[u64; MINTS]; OWNERS],
+}
+
+impl Model {
+    fn predict(&self, op: &Op) -> Expected {
+        use ShadowError::*;
+        let live = |flag: u8, vault: &VaultModel| {
+            if self.protocol_paused {
+                Err(Some(ProtocolPaused))
+            } else if vault.is_paused(flag) {
+                Err(Some(VaultPaused))
+            } else {
+                Ok(())
+            }
+        };
+
+        match *op {
+            Op::InitVault { .. } if self.protocol_paused => Err(Some(ProtocolPaused)),
+            Op::InitVault { .. } | Op::Donate { .. } | Op::Pause { .. } | Op::ProtocolPause { .. } => Ok(()),
+            Op::Deposit { vault, mint, amount, .. } => {
+                let v = &self.vaults[vault];
+                live(PauseFlags::DEPOSITS, v)?;
+                if v.books[mint].shares_for_deposit(amount) == 0 {
+                    return Err(Some(ZeroShares));
+                }
+                Ok(())
+            }
+            Op::Submit { user, vault, amount, slippage_bps, .. } => {
+                let v = &self.vaults[vault];
+                if v.intents[user].is_some() {
+                    return Err(None);
+                }
+                live(PauseFlags::INTENTS, v)?;
+                if amount > PAIR_MAX_AMOUNT || slippage_bps > PAIR_MAX_SLIPPAGE_BPS {
+                    return Err(Some(PairLimitExceeded));
+                }
+                Ok(())
+            }
+            Op::Execute { user, vault } => {
+                let v = &self.vaults[vault];
+                let Some(intent) = v.intents[user] else {
+                    return Err(None);
+                };
+                let (token_in, token_out) = PAIRS[intent.pair];
+                if !v.books[token_in].custody_open || !v.books[token_out].custody_open || intent.executed {
+                    return Err(None);
+                }
+                live(PauseFlags::EXECUTIONS, v)
+            }
+            Op::Withdraw { vault, mint, amount, as_user } => {
+                let v = &self.vaults[vault];
+                let book = &v.books[mint];
+                if !book.custody_open || !book.ledger_open {
+                    return Err(None);
+                }
+                if as_user.is_some() {
+                    return Err(Some(Unauthorized));
+                }
+                live(PauseFlags::WITHDRAWALS, v)?;
+                if amount > book.custody {
+                    return Err(None);
+                }
+                if amount > book.balance {
+                    return Err(Some(MathOverflow));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Books a step the program accepted
+    fn apply(&mut self, op: &Op, new_vault: Option<Pubkey>) {
+        match *op {
+            Op::InitVault { owner } => {
+                self.next_vault_id[owner] += 1;
+                self.vaults.push(VaultModel {
+                    address: new_vault.unwrap(),
+                    owner,
+                    tvl: 0,
+                    pause_flags: 0,
+                    execution_count: 0,
+                    // VaultInitialized, one TradePairUpdated per pair, then
+                    // StrategyConfigUpdated
+                    event_seq: 2 + PAIRS.len() as u64,
+                    books: Default::default(),
+                    intents: [None; USERS],
+                });
+            }
+            Op::Deposit { user, vault, mint, amount } => {
+                let v = &mut self.vaults[vault];
+                let book = &mut v.books[mint];
+                let shares = book.shares_for_deposit(amount);
+                book.ledger_open = true;
+                book.custody_open = true;
+                book.balance += amount;
+                book.custody += amount;
+                book.total_shares += shares;
+                book.positions[user] += shares;
+                v.tvl += amount;
+                v.event_seq += 1;
+                self.wallets[user][mint] -= amount;
+            }
+            Op::Donate { vault, mint, amount } => {
+                let book = &mut self.vaults[vault].books[mint];
+                book.custody_open = true;
+                book.custody += amount;
+            }
+            Op::Submit { user, vault, pair, amount, .. } => {
+                let v = &mut self.vaults[vault];
+                v.intents[user] = Some(Intent { pair, amount, executed: false });
+                v.event_seq += 1;
+            }
+            Op::Execute { user, vault } => {
+                let v = &mut self.vaults[vault];
+                v.intents[user].as_mut().unwrap().executed = true;
+                v.execution_count += 1;
+                v.event_seq += 1;
+            }
+            Op::Withdraw { vault, mint, amount, .. } => {
+                let v = &mut self.vaults[vault];
+                let book = &mut v.books[mint];
+                book.balance -= amount;
+                book.custody -= amount;
+                v.tvl -= amount;
+                v.event_seq += 1;
+                self.received[v.owner][mint] += amount;
+            }
+            Op::Pause { vault, flags, pause } => {
+                let v = &mut self.vaults[vault];
+                if pause {
+                    v.pause_flags |= flags;
+                } else {
+                    v.pause_flags &= !flags;
+                }
+                v.event_seq += 1;
+            }
+            Op::ProtocolPause { paused } => {
+                self.protocol_paused = paused;
+                self.protocol_event_seq += 1;
+            }
+        }
+    }
+
+    fn generate(&self, rng: &mut StdRng) -> Op {
+        let vault = rng.gen_range(0..self.vaults.len());
+        let mint = rng.gen_range(0..MINTS);
+        let user = rng.gen_range(0..USERS);
+        match rng.gen_range(0..100) {
+            0..=29 => Op::Deposit {
+                user,
+                vault,
+                mint,
+                amount: rng.gen_range(1..=100_000),
+            },
+            30..=34 => Op::Donate {
+                vault,
+                mint,
+                amount: rng.gen_range(1..=1_000),
+            },
+            35..=49 => Op::Submit {
+                user,
+                vault,
+                pair: rng.gen_range(0..PAIRS.len()),
+                amount: rng.gen_range(1..=PAIR_MAX_AMOUNT * 5 / 4),
+                slippage_bps: rng.gen_range(0..=PAIR_MAX_SLIPPAGE_BPS * 3 / 2),
+            },
+            50..=62 => Op::Execute { user, vault },
+            63..=84 => {
+                // reach past the booked balance now and then
+                let balance = self.vaults[vault].books[mint].balance;
+                Op::Withdraw {
+                    vault,
+                    mint,
+                    amount: rng.gen_range(1..=balance + balance / 4 + 10),
+                    as_user: rng.gen_bool(0.1).then_some(user),
+                }
+            }
+            85..=93 => Op::Pause {
+                vault,
+                flags: rng.gen_range(1..=PauseFlags::ALL),
+                pause: rng.gen_bool(0.5),
+            },
+            94..=96 if self.vaults.len() < MAX_VAULTS => Op::InitVault {
+                owner: rng.gen_range(0..OWNERS),
+            },
+            // a paused protocol should not stall the rest of the run
+            _ => Op::ProtocolPause {
+                paused: !self.protocol_paused && rng.gen_bool(0.5),
+            },
+        }
+    }
+}
+
+struct Harness {
+    env: TestEnv,
+    rng: StdRng,
+    seed: u64,
+    step: usize,
+    owners: Vec<Keypair>,
+    users: Vec<Keypair>,
+    executor: Keypair,
+    mints: Vec<Pubkey>,
+    model: Model,
+}
+
+impl Harness {
+    async fn new(seed: u64) -> Self {
+        let mut env = TestEnv::new().await;
+        let mut owners = Vec::new();
+        for _ in 0..OWNERS {
+            owners.push(env.funded().await);
+        }
+        let mut users = Vec::new();
+        for _ in 0..USERS {
+            users.push(env.funded().await);
+        }
+        let mut mints = Vec::new();
+        for _ in 0..MINTS {
+            mints.push(env.create_mint().await);
+        }
+        let executor = env.funded().await;
+        let admin = env.admin();
+        env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+            .await
+            .unwrap();
+        let config: ProtocolConfig = env.account(&pda::protocol_config().0).await;
+
+        let mut harness = Self {
+            env,
+            rng: StdRng::seed_from_u64(seed),
+            seed,
+            step: 0,
+            owners,
+            users,
+            executor,
+            mints,
+            model: Model {
+                protocol_event_seq: config.event_seq,
+                ..Default::default()
+            },
+        };
+        for owner in 0..OWNERS {
+            harness.step(Op::InitVault { owner }).await;
+        }
+        harness
+    }
+
+    async fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            let op = self.model.generate(&mut self.rng);
+            self.step(op).await;
+        }
+    }
+
+    async fn step(&mut self, op: Op) {
+        self.step += 1;
+        let expected = self.model.predict(&op);
+        let (result, new_vault) = self.execute(&op).await;
+        let outcome = outcome(result);
+        let accepted = match (expected, outcome) {
+            (Ok(()), Ok(())) => true,
+            (Err(None), Err(_)) => false,
+            (Err(Some(error)), Err(code)) if code == Some(ERROR_CODE_OFFSET + error as u32) => false,
+            (expected, outcome) => panic!(
+                "{}: {op:?} expected {expected:?}, got {outcome:?}",
+                self.context()
+            ),
+        };
+        if accepted {
+            self.model.apply(&op, new_vault);
+        }
+        self.check().await;
+    }
+
+    /// Sends `op`; returns the address of a vault it created
+    async fn execute(&mut self, op: &Op) -> (SendResult, Option<Pubkey>) {
+        let env = &mut self.env;
+        let vault_address = |vault: usize| self.model.vaults[vault].address;
+        let result = match *op {
+            Op::InitVault { owner } => {
+                let vault_id = self.model.next_vault_id[owner];
+                let owner = &self.owners[owner];
+                let ix = instructions::initialize_vault(&owner.pubkey(), &owner.pubkey(), vault_id, [7; 32]);
+                let result = env.send(&[ix], &[owner]).await;
+                if result.is_err() {
+                    return (result, None);
+                }
+                let vault = pda::vault(&owner.pubkey(), vault_id).0;
+                for (token_in, token_out) in PAIRS {
+                    let ix = instructions::set_trade_pair(
+                        &owner.pubkey(),
+                        &vault,
+                        &self.mints[token_in],
+                        &self.mints[token_out],
+                        true,
+                        PAIR_MAX_AMOUNT,
+                        PAIR_MAX_SLIPPAGE_BPS,
+                    );
+                    env.send(&[ix], &[owner]).await.expect("set_trade_pair");
+                }
+                // an unrestricted strategy leaves the pair limits in charge
+                let ix = instructions::set_strategy_config(&owner.pubkey(), &vault, 0, true, vec![], 0, 0, 0);
+                env.send(&[ix], &[owner]).await.expect("set_strategy_config");
+                return (Ok(()), Some(vault));
+            }
+            Op::Deposit { user, vault, mint, amount } => {
+                let user_kp = &self.users[user];
+                env.mint_to(&self.mints[mint], &user_kp.pubkey(), amount).await;
+                self.model.wallets[user][mint] += amount;
+                let ix = instructions::deposit(&user_kp.pubkey(), &vault_address(vault), &self.mints[mint], amount, vec![]);
+                env.send(&[ix], &[user_kp]).await
+            }
+            Op::Donate { vault, mint, amount } => {
+                env.mint_to(&self.mints[mint], &vault_address(vault), amount).await;
+                Ok(())
+            }
+            Op::Submit { user, vault, pair, amount, slippage_bps } => {
+                let (token_in, token_out) = PAIRS[pair];
+                let user = &self.users[user];
+                let ix = instructions::submit_trade_intent(
+                    &user.pubkey(),
+                    &vault_address(vault),
+                    0,
+                    &self.mints[token_in],
+                    &self.mints[token_out],
+                    amount,
+                    slippage_bps,
+                    0,
+                );
+                env.send(&[ix], &[user]).await
+            }
+            Op::Execute { user, vault } => {
+                // an intent that was never submitted still needs mints for the accounts
+                let (pair, amount) = self.model.vaults[vault].intents[user].map_or((0, 0), |i| (i.pair, i.amount));
+                let (token_in, token_out) = PAIRS[pair];
+                let ix = instructions::execute_trade(
+                    &self.executor.pubkey(),
+                    &vault_address(vault),
+                    &self.users[user].pubkey(),
+                    0,
+                    &self.mints[token_in],
+                    &self.mints[token_out],
+                    0,
+                    [1; 32],
+                    [2; 64],
+                    [3; 32],
+                    amount,
+                );
+                env.send(&[ix], &[&self.executor]).await
+            }
+            Op::Withdraw { vault, mint, amount, as_user } => {
+                let signer = match as_user {
+                    Some(user) => &self.users[user],
+                    None => &self.owners[self.model.vaults[vault].owner],
+                };
+                let ix = instructions::withdraw(&signer.pubkey(), &vault_address(vault), &self.mints[mint], amount);
+                env.send(&[ix], &[signer]).await
+            }
+            Op::Pause { vault, flags, pause } => {
+                let owner = &self.owners[self.model.vaults[vault].owner];
+                let ix = instructions::pause_vault(&owner.pubkey(), &vault_address(vault), flags, pause);
+                env.send(&[ix], &[owner]).await
+            }
+            Op::ProtocolPause { paused } => {
+                let admin = env.admin();
+                env.send(&[instructions::set_protocol_pause(&admin, paused)], &[]).await
+            }
+        };
+        (result, None)
+    }
+
+    /// Compares every account the model tracks against the chain
+    async fn check(&mut self) {
+        let at = self.context();
+        let env = &mut self.env;
+        let model = &self.model;
+
+        let config: ProtocolConfig = env.account(&pda::protocol_config().0).await;
+        assert_eq!(config.paused, model.protocol_paused, "{at}: protocol pause");
+        assert_eq!(config.event_seq, model.protocol_event_seq, "{at}: protocol event_seq");
+
+        for (owner, kp) in self.owners.iter().enumerate() {
+            let counter = pda::owner_counter(&kp.pubkey()).0;
+            let next_vault_id = match env.exists(&counter).await {
+                true => env.account::<OwnerVaultCounter>(&counter).await.next_vault_id,
+                false => 0,
+            };
+            assert_eq!(next_vault_id, model.next_vault_id[owner], "{at}: owner {owner} next_vault_id");
+            for (mint, key) in self.mints.iter().enumerate() {
+                let received = balance(env, &get_associated_token_address(&kp.pubkey(), key)).await;
+                assert_eq!(received, model.received[owner][mint], "{at}: owner {owner} withdrew more than booked");
+            }
+        }
+        for (user, kp) in self.users.iter().enumerate() {
+            for (mint, key) in self.mints.iter().enumerate() {
+                let wallet = balance(env, &get_associated_token_address(&kp.pubkey(), key)).await;
+                assert_eq!(wallet, model.wallets[user][mint], "{at}: user {user} wallet, mint {mint}");
+            }
+        }
+
+        for (index, v) in model.vaults.iter().enumerate() {
+            let at = format!("{at}, vault {index}");
+            let account: VaultAccount = env.account(&v.address).await;
+            assert_eq!(account.pause_flags, v.pause_flags, "{at}: pause flags");
+            assert_eq!(account.execution_count, v.execution_count, "{at}: execution_count");
+            assert_eq!(account.event_seq, v.event_seq, "{at}: event_seq");
+            assert_eq!(account.tvl, v.tvl, "{at}: tvl");
+            if v.execution_count > 0 {
+                let history: ExecutionHistory = env.account(&pda::execution_history(&v.address).0).await;
+                assert_eq!(history.total, v.execution_count, "{at}: execution history total");
+            }
+
+            let mut ledger_total = 0u64;
+            let mut open_ledgers = 0u16;
+            for (mint, key) in self.mints.iter().enumerate() {
+                let book = &v.books[mint];
+                let custody = balance(env, &get_associated_token_address(&v.address, key)).await;
+                assert_eq!(custody, book.custody, "{at}, mint {mint}: custody");
+                if !book.ledger_open {
+                    assert!(!env.exists(&pda::ledger(&v.address, key).0).await, "{at}, mint {mint}: stray ledger");
+                    continue;
+                }
+                open_ledgers += 1;
+
+                let ledger: CustodyLedger = env.account(&pda::ledger(&v.address, key).0).await;
+                assert_eq!(ledger.balance, book.balance, "{at}, mint {mint}: ledger balance");
+                assert!(ledger.balance <= custody, "{at}, mint {mint}: ledger exceeds custody");
+                assert_eq!(ledger.total_shares, book.total_shares, "{at}, mint {mint}: total shares");
+                ledger_total += ledger.balance;
+
+                let mut position_total = 0u64;
+                for (user, kp) in self.users.iter().enumerate() {
+                    let address = pda::position(&v.address, key, &kp.pubkey()).0;
+                    let shares = match env.exists(&address).await {
+                        true => env.account::<UserPosition>(&address).await.shares,
+                        false => 0,
+                    };
+                    assert_eq!(shares, book.positions[user], "{at}, mint {mint}: user {user} shares");
+                    position_total += shares;
+                }
+                assert_eq!(position_total, ledger.total_shares, "{at}, mint {mint}: shares outstanding");
+            }
+            assert_eq!(account.tvl, ledger_total, "{at}: tvl is not the sum of its ledgers");
+            assert_eq!(account.custody_mint_count, open_ledgers, "{at}: custody_mint_count");
+        }
+    }
+
+    fn context(&self) -> String {
+        format!("seed {} step {}", self.seed, self.step)
+    }
+}
+
+fn outcome(result: SendResult) -> Result<(), Option<u32>> {
+    match result {
+        Ok(()) => Ok(()),
+        failed => Err(error_code(failed)),
+    }
+}
+
+/// Token balance, 0 for an account that does not exist yet
+async fn balance(env: &mut TestEnv, ata: &Pubkey) -> u64 {
+    match env.exists(ata).await {
+        true => env.token_balance(ata).await,
+        false => 0,
+    }
+}
+
+fn env_or(name: &str, default: u64) -> u64 {
+    std::env::var(name).map_or(default, |value| value.parse().unwrap_or_else(|_| panic!("{name}: not a number")))
+}
+
+#[tokio::test]
+async fn accounting_invariants_hold_under_random_operations() {
+    let steps = env_or("SHADOWVAULT_PROPTEST_STEPS", 150) as usize;
+    let seeds: Vec<u64> = match std::env::var("SHADOWVAULT_PROPTEST_SEED") {
+        Ok(seed) => vec![seed.parse().expect("SHADOWVAULT_PROPTEST_SEED: not a number")],
+        Err(_) => (0..env_or("SHADOWVAULT_PROPTEST_RUNS", 4)).map(|_| rand::random()).collect(),
+    };
+    for seed in seeds {
+        let mut harness = Harness::new(seed).await;
+        harness.run(steps).await;
+    }
+}
@@ -0,0 +1,89 @@
+use shadowvault::state::{PriceFeed, ProtocolConfig, PRICE_SCALE};
+use shadowvault_client::{instructions, pda};
+use shadowvault_tests::TestEnv;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn initialize_protocol_sets_admin() {
+    let mut env = TestEnv::new().await;
+    let config: ProtocolConfig = env.account(&pda::protocol_config().0).await;
+    assert_eq!(config.admin, env.admin());
+    assert_eq!(config.protocol_fee_bps, 0);
+    assert!(!config.paused);
+    assert!(config.executors.is_empty());
+    assert_eq!(config.event_seq, 1);
+}
+
+#[tokio::test]
+async fn admin_updates_fee_pause_and_executors() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin();
+    let (recipient, executor) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    env.send(&[instructions::set_protocol_fee(&admin, 250, &recipient)], &[])
+        .await
+        .unwrap();
+    env.send(&[instructions::set_protocol_pause(&admin, true)], &[]).await.unwrap();
+    env.send(&[instructions::set_executor(&admin, &executor, true)], &[])
+        .await
+        .unwrap();
+
+    let config: ProtocolConfig = env.account(&pda::protocol_config().0).await;
+    assert_eq!(config.protocol_fee_bps, 250);
+    assert_eq!(config.fee_recipient, recipient);
+    assert!(config.paused);
+    assert_eq!(config.executors, vec![executor]);
+
+    env.send(&[instructions::set_executor(&admin, &executor, false)], &[])
+        .await
+        .unwrap();
+    env.send(&[instructions::set_protocol_pause(&admin, false)], &[]).await.unwrap();
+    let config: ProtocolConfig = env.account(&pda::protocol_config().0).await;
+    assert!(config.executors.is_empty());
+    assert!(!config.paused);
+    assert_eq!(config.event_seq, 6);
+}
+
+#[tokio::test]
+async fn admin_transfer_is_two_step() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin();
+    let new_admin = env.funded().await;
+
+    env.send(&[instructions::transfer_protocol_admin(&admin, &new_admin.pubkey())], &[])
+        .await
+        .unwrap();
+    let config: ProtocolConfig = env.account(&pda::protocol_config().0).await;
+    assert_eq!(config.admin, admin);
+    assert_eq!(config.pending_admin, new_admin.pubkey());
+
+    env.send(&[instructions::accept_protocol_admin(&new_admin.pubkey())], &[&new_admin])
+        .await
+        .unwrap();
+    let config: ProtocolConfig = env.account(&pda::protocol_config().0).await;
+    assert_eq!(config.admin, new_admin.pubkey());
+    assert_eq!(config.pending_admin, Pubkey::default());
+}
+
+#[tokio::test]
+async fn admin_posts_price_feed() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin();
+    let (token_in, token_out) = (env.create_mint().await, env.create_mint().await);
+
+    for price in [PRICE_SCALE, 2 * PRICE_SCALE] {
+        let ix = instructions::set_price_feed(&admin, &token_in, &token_out, price);
+        env.send(&[ix], &[]).await.unwrap();
+    }
+    let feed: PriceFeed = env.account(&pda::price_feed(&token_in, &token_out).0).await;
+    assert_eq!((feed.token_in, feed.token_out), (token_in, token_out));
+    assert_eq!(feed.price, 2 * PRICE_SCALE);
+    assert_eq!(feed.updated_at, env.now().await);
+    let config: ProtocolConfig = env.account(&pda::protocol_config().0).await;
+    assert_eq!(config.event_seq, 3);
+
+    let stranger = env.funded().await;
+    let ix = instructions::set_price_feed(&stranger.pubkey(), &token_in, &token_out, PRICE_SCALE);
+    assert!(env.send(&[ix], &[&stranger]).await.is_err());
+}
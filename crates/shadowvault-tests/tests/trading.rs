@@ -0,0 +1,732 @@
+use shadowvault::errors::ShadowError;
+use shadowvault::state::{
+    ExecutionHistory, ExecutionResult, IntentKind, PauseFlags, StrategyConfig, TradeIntent, TradePair, TriggerLeg,
+    TwapFill, UserIntentCounter, VaultAccount, PRICE_SCALE,
+};
+use shadowvault_client::{instructions, pda};
+use shadowvault_tests::{assert_shadow_error, TestEnv};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+struct Market {
+    owner: Keypair,
+    user: Keypair,
+    vault: Pubkey,
+    token_in: Pubkey,
+    token_out: Pubkey,
+}
+
+/// A vault holding both mints with token_in -> token_out enabled
+async fn market(env: &mut TestEnv) -> Market {
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let (token_in, token_out) = (env.create_mint().await, env.create_mint().await);
+    env.fund_and_deposit(&user, &vault, &token_in, 1_000).await.unwrap();
+    env.fund_and_deposit(&user, &vault, &token_out, 1_000).await.unwrap();
+
+    let ix = instructions::set_trade_pair(&owner.pubkey(), &vault, &token_in, &token_out, true, 500, 100);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    for strategy_type in [0, 1] {
+        let ix = instructions::set_strategy_config(&owner.pubkey(), &vault, strategy_type, true, vec![], 0, 0, 0);
+        env.send(&[ix], &[&owner]).await.unwrap();
+    }
+    Market {
+        owner,
+        user,
+        vault,
+        token_in,
+        token_out,
+    }
+}
+
+#[tokio::test]
+async fn set_trade_pair_records_limits() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+
+    let pair: TradePair = env.account(&pda::trade_pair(&m.vault, &m.token_in, &m.token_out).0).await;
+    assert!(pair.enabled);
+    assert_eq!(pair.max_amount, 500);
+    assert_eq!(pair.max_slippage_bps, 100);
+
+    let ix = instructions::set_trade_pair(&m.owner.pubkey(), &m.vault, &m.token_in, &m.token_out, false, 0, 0);
+    env.send(&[ix], &[&m.owner]).await.unwrap();
+    let pair: TradePair = env.account(&pda::trade_pair(&m.vault, &m.token_in, &m.token_out).0).await;
+    assert!(!pair.enabled);
+}
+
+#[tokio::test]
+async fn set_strategy_config_records_parameters() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+
+    let pair = pda::trade_pair(&m.vault, &m.token_in, &m.token_out).0;
+    let ix = instructions::set_strategy_config(&m.owner.pubkey(), &m.vault, 7, true, vec![pair], 250, 30, 60);
+    env.send(&[ix], &[&m.owner]).await.unwrap();
+
+    let strategy: StrategyConfig = env.account(&pda::strategy_config(&m.vault, 7).0).await;
+    assert_eq!((strategy.vault, strategy.strategy_type), (m.vault, 7));
+    assert!(strategy.enabled);
+    assert_eq!(strategy.allowed_pairs, vec![pair]);
+    assert_eq!((strategy.max_amount, strategy.max_slippage_bps), (250, 30));
+    assert_eq!(strategy.cooldown_secs, 60);
+    assert_eq!(strategy.last_executed_at, None);
+
+    // an intent naming an unconfigured strategy has nothing to run under
+    let ix = instructions::submit_trade_intent(&m.user.pubkey(), &m.vault, 0, &m.token_in, &m.token_out, 100, 10, 9);
+    assert!(env.send(&[ix], &[&m.user]).await.is_err());
+}
+
+#[tokio::test]
+async fn strategy_changes_apply_to_pending_intents() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+
+    let ix = instructions::submit_trade_intent(&m.user.pubkey(), &m.vault, 0, &m.token_in, &m.token_out, 300, 50, 0);
+    env.send(&[ix], &[&m.user]).await.unwrap();
+    let ix = instructions::set_strategy_config(&m.owner.pubkey(), &m.vault, 0, true, vec![], 200, 0, 0);
+    env.send(&[ix], &[&m.owner]).await.unwrap();
+
+    let ix = instructions::execute_trade(
+        &executor.pubkey(),
+        &m.vault,
+        &m.user.pubkey(),
+        0,
+        &m.token_in,
+        &m.token_out,
+        0,
+        [1; 32],
+        [2; 64],
+        [3; 32],
+        300,
+    );
+    assert_shadow_error(
+        env.send(std::slice::from_ref(&ix), &[&executor]).await,
+        ShadowError::StrategyLimitExceeded,
+    );
+
+    let loosen = instructions::set_strategy_config(&m.owner.pubkey(), &m.vault, 0, true, vec![], 0, 0, 0);
+    env.send(&[loosen], &[&m.owner]).await.unwrap();
+    env.send(&[ix], &[&executor]).await.unwrap();
+    let strategy: StrategyConfig = env.account(&pda::strategy_config(&m.vault, 0).0).await;
+    assert!(strategy.last_executed_at.is_some());
+}
+
+#[tokio::test]
+async fn submit_and_execute_trade() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+
+    let ix = instructions::submit_trade_intent(&m.user.pubkey(), &m.vault, 0, &m.token_in, &m.token_out, 300, 50, 1);
+    env.send(&[ix], &[&m.user]).await.unwrap();
+    let intent_key = pda::intent(&m.user.pubkey(), &m.vault, 0).0;
+    let intent: TradeIntent = env.account(&intent_key).await;
+    assert_eq!(intent.amount, 300);
+    assert_eq!(intent.max_slippage_bps, 50);
+    assert_eq!(intent.strategy_type, 1);
+
+    let ix = instructions::execute_trade(
+        &executor.pubkey(),
+        &m.vault,
+        &m.user.pubkey(),
+        0,
+        &m.token_in,
+        &m.token_out,
+        1,
+        [1; 32],
+        [2; 64],
+        [3; 32],
+        300,
+    );
+    env.send(&[ix], &[&executor]).await.unwrap();
+
+    let result: ExecutionResult = env.account(&pda::result(&intent_key).0).await;
+    assert_eq!(result.intent, intent_key);
+    assert_eq!(result.executed_amount, 300);
+    assert!(result.success);
+    let v: VaultAccount = env.account(&m.vault).await;
+    assert_eq!(v.execution_count, 1);
+
+    let history: ExecutionHistory = env.account(&pda::execution_history(&m.vault).0).await;
+    assert_eq!(history.vault, m.vault);
+    assert_eq!(history.total, 1);
+    let record = history.recent().next().unwrap();
+    assert_eq!(record.intent, intent_key);
+    assert_eq!((record.token_in, record.token_out), (m.token_in, m.token_out));
+    assert_eq!(record.executed_amount, 300);
+    assert_eq!(record.received_amount, 300);
+    assert_eq!(record.price, PRICE_SCALE);
+    assert!(record.slot > 0);
+}
+
+#[tokio::test]
+async fn execution_history_keeps_the_latest_records() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+
+    // each intent is a new account, so one user can fill the ring
+    let runs = ExecutionHistory::CAPACITY + 2;
+    let user = m.user.pubkey();
+    let mut executed = Vec::new();
+    for (intent_id, amount) in (1..=runs as u64).enumerate() {
+        let intent_id = intent_id as u64;
+        let ix = instructions::submit_trade_intent(&user, &m.vault, intent_id, &m.token_in, &m.token_out, amount, 0, 0);
+        env.send(&[ix], &[&m.user]).await.unwrap();
+        let ix = instructions::execute_trade(
+            &executor.pubkey(),
+            &m.vault,
+            &user,
+            intent_id,
+            &m.token_in,
+            &m.token_out,
+            0,
+            [1; 32],
+            [2; 64],
+            [3; 32],
+            amount,
+        );
+        env.send(&[ix], &[&executor]).await.unwrap();
+        executed.push((pda::intent(&user, &m.vault, intent_id).0, amount));
+    }
+
+    let history: ExecutionHistory = env.account(&pda::execution_history(&m.vault).0).await;
+    assert_eq!(history.total, runs as u64);
+    let recent: Vec<_> = history.recent().map(|r| (r.intent, r.executed_amount)).collect();
+    let expected: Vec<_> = executed.into_iter().rev().take(ExecutionHistory::CAPACITY).collect();
+    assert_eq!(recent, expected);
+}
+
+#[tokio::test]
+async fn twap_intent_executes_in_slices() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+
+    // 1_000 exceeds the pair's 500 cap as one swap, but each slice fits
+    let ix = instructions::submit_trade_intent(&m.user.pubkey(), &m.vault, 0, &m.token_in, &m.token_out, 1_000, 50, 0);
+    assert_shadow_error(env.send(&[ix], &[&m.user]).await, ShadowError::PairLimitExceeded);
+    let ix =
+        instructions::submit_twap_intent(&m.user.pubkey(), &m.vault, 0, &m.token_in, &m.token_out, 1_000, 50, 0, 3, 60);
+    env.send(&[ix], &[&m.user]).await.unwrap();
+
+    let intent_key = pda::intent(&m.user.pubkey(), &m.vault, 0).0;
+    let mut fills = Vec::new();
+    for slice in 0..3u16 {
+        if slice > 0 {
+            env.advance_clock(60).await;
+        }
+        let intent: TradeIntent = env.account(&intent_key).await;
+        let ix = instructions::execute_twap_slice(&executor.pubkey(), &intent, [1; 32], [2; 64]);
+        env.send(&[ix], &[&executor]).await.unwrap();
+        let fill: TwapFill = env.account(&pda::twap_fill(&intent_key, slice).0).await;
+        assert_eq!((fill.intent, fill.slice), (intent_key, slice));
+        fills.push(fill.executed_amount);
+    }
+    // the last slice takes the rounding remainder
+    assert_eq!(fills, vec![333, 333, 334]);
+
+    let intent: TradeIntent = env.account(&intent_key).await;
+    assert_eq!(intent.kind, IntentKind::Twap);
+    assert_eq!((intent.slices_done, intent.remaining_amount), (3, 0));
+    assert!(intent.is_filled());
+    let v: VaultAccount = env.account(&m.vault).await;
+    assert_eq!(v.execution_count, 3);
+    let history: ExecutionHistory = env.account(&pda::execution_history(&m.vault).0).await;
+    let recent: Vec<_> = history.recent().map(|r| r.executed_amount).collect();
+    assert_eq!(recent, vec![334, 333, 333]);
+}
+
+#[tokio::test]
+async fn limit_order_fills_in_parts() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+    // fills are priced at the feed, here above the limit
+    let ix = instructions::set_price_feed(&admin, &m.token_in, &m.token_out, PRICE_SCALE * 3 / 2);
+    env.send(&[ix], &[]).await.unwrap();
+
+    let ix = instructions::submit_limit_intent(
+        &m.user.pubkey(),
+        &m.vault,
+        0,
+        &m.token_in,
+        &m.token_out,
+        300,
+        50,
+        0,
+        PRICE_SCALE,
+        0,
+    );
+    env.send(&[ix], &[&m.user]).await.unwrap();
+
+    let intent_key = pda::intent(&m.user.pubkey(), &m.vault, 0).0;
+    for (fill, remaining) in [(100, 200), (200, 0)] {
+        let ix = instructions::execute_trade_with_feed(
+            &executor.pubkey(),
+            &m.vault,
+            &m.user.pubkey(),
+            0,
+            &m.token_in,
+            &m.token_out,
+            0,
+            [1; 32],
+            [2; 64],
+            [3; 32],
+            fill,
+        );
+        env.send(&[ix], &[&executor]).await.unwrap();
+        let intent: TradeIntent = env.account(&intent_key).await;
+        assert_eq!(intent.remaining_amount, remaining);
+    }
+
+    let intent: TradeIntent = env.account(&intent_key).await;
+    assert_eq!(intent.kind, IntentKind::Limit);
+    assert_eq!(intent.limit_price, PRICE_SCALE);
+    assert!(intent.is_filled());
+    // the result accumulates every fill
+    let result: ExecutionResult = env.account(&pda::result(&intent_key).0).await;
+    assert_eq!((result.executed_amount, result.received_amount), (300, 450));
+    let history: ExecutionHistory = env.account(&pda::execution_history(&m.vault).0).await;
+    let recent: Vec<_> = history.recent().map(|r| (r.executed_amount, r.received_amount)).collect();
+    assert_eq!(recent, vec![(200, 300), (100, 150)]);
+}
+
+#[tokio::test]
+async fn cancel_intent_keeps_partial_fills() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+    let ix = instructions::set_price_feed(&admin, &m.token_in, &m.token_out, PRICE_SCALE);
+    env.send(&[ix], &[]).await.unwrap();
+
+    let ix = instructions::submit_limit_intent(
+        &m.user.pubkey(),
+        &m.vault,
+        0,
+        &m.token_in,
+        &m.token_out,
+        300,
+        50,
+        0,
+        PRICE_SCALE,
+        0,
+    );
+    env.send(&[ix], &[&m.user]).await.unwrap();
+    let ix = instructions::execute_trade_with_feed(
+        &executor.pubkey(),
+        &m.vault,
+        &m.user.pubkey(),
+        0,
+        &m.token_in,
+        &m.token_out,
+        0,
+        [1; 32],
+        [2; 64],
+        [3; 32],
+        100,
+    );
+    env.send(&[ix], &[&executor]).await.unwrap();
+
+    // only the intent's user can cancel it
+    let stranger = env.funded().await;
+    let mut ix = instructions::cancel_intent(&m.user.pubkey(), &m.vault, 0);
+    ix.accounts[0].pubkey = stranger.pubkey();
+    assert!(env.send(&[ix], &[&stranger]).await.is_err());
+
+    let seq_before = env.account::<VaultAccount>(&m.vault).await.event_seq;
+    env.send(&[instructions::cancel_intent(&m.user.pubkey(), &m.vault, 0)], &[&m.user])
+        .await
+        .unwrap();
+    let intent: TradeIntent = env.account(&pda::intent(&m.user.pubkey(), &m.vault, 0).0).await;
+    assert!(intent.cancelled);
+    assert_eq!(intent.remaining_amount, 200);
+    let v: VaultAccount = env.account(&m.vault).await;
+    assert_eq!((v.execution_count, v.event_seq), (1, seq_before + 1));
+}
+
+#[tokio::test]
+async fn cancelled_and_filled_intents_do_not_block_the_next() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+    let user = m.user.pubkey();
+    let submit = |intent_id, amount| {
+        instructions::submit_trade_intent(&user, &m.vault, intent_id, &m.token_in, &m.token_out, amount, 50, 0)
+    };
+    let execute = |intent_id, amount| {
+        instructions::execute_trade(
+            &executor.pubkey(),
+            &m.vault,
+            &user,
+            intent_id,
+            &m.token_in,
+            &m.token_out,
+            0,
+            [1; 32],
+            [2; 64],
+            [3; 32],
+            amount,
+        )
+    };
+
+    env.send(&[submit(0, 100)], &[&m.user]).await.unwrap();
+    env.send(&[instructions::cancel_intent(&user, &m.vault, 0)], &[&m.user])
+        .await
+        .unwrap();
+    // ids only move forward; a stale id no longer derives the intent address
+    assert!(env.send(&[submit(0, 100)], &[&m.user]).await.is_err());
+    env.send(&[submit(1, 200)], &[&m.user]).await.unwrap();
+    env.send(&[execute(1, 200)], &[&executor]).await.unwrap();
+    env.send(&[submit(2, 300)], &[&m.user]).await.unwrap();
+    env.send(&[execute(2, 300)], &[&executor]).await.unwrap();
+
+    let counter: UserIntentCounter = env.account(&pda::intent_counter(&user, &m.vault).0).await;
+    assert_eq!(counter.next_intent_id, 3);
+    let cancelled: TradeIntent = env.account(&pda::intent(&user, &m.vault, 0).0).await;
+    assert!(cancelled.cancelled);
+    assert_eq!(cancelled.remaining_amount, 100);
+    assert!(!env.exists(&pda::result(&pda::intent(&user, &m.vault, 0).0).0).await);
+    // each fill lands in its own intent's result
+    for (intent_id, amount) in [(1, 200), (2, 300)] {
+        let intent_key = pda::intent(&user, &m.vault, intent_id).0;
+        let intent: TradeIntent = env.account(&intent_key).await;
+        assert_eq!(intent.intent_id, intent_id);
+        assert!(intent.is_filled());
+        let result: ExecutionResult = env.account(&pda::result(&intent_key).0).await;
+        assert_eq!(result.executed_amount, amount);
+    }
+}
+
+#[tokio::test]
+async fn trade_shortfall_feeds_the_circuit_breaker() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+    // 10% of the 2_000 held trips the breaker
+    let ix = instructions::configure_circuit_breaker(&m.owner.pubkey(), &m.vault, 3_600, 1_000);
+    env.send(&[ix], &[&m.owner]).await.unwrap();
+    let user = m.user.pubkey();
+    let execute = |priced: bool, intent_id, amount| {
+        let execute = match priced {
+            true => instructions::execute_trade_with_feed,
+            false => instructions::execute_trade,
+        };
+        execute(
+            &executor.pubkey(),
+            &m.vault,
+            &user,
+            intent_id,
+            &m.token_in,
+            &m.token_out,
+            0,
+            [1; 32],
+            [2; 64],
+            [3; 32],
+            amount,
+        )
+    };
+    for (id, amount) in [(0, 100), (1, 400)] {
+        let ix = instructions::submit_trade_intent(&user, &m.vault, id, &m.token_in, &m.token_out, amount, 50, 0);
+        env.send(&[ix], &[&m.user]).await.unwrap();
+    }
+
+    // with the breaker on, a fill cannot skip being valued
+    let ix = instructions::set_price_feed(&admin, &m.token_in, &m.token_out, 2 * PRICE_SCALE);
+    env.send(&[ix], &[]).await.unwrap();
+    assert_shadow_error(env.send(&[execute(false, 0, 100)], &[&executor]).await, ShadowError::PriceFeedRequired);
+
+    // the placeholder fills 1:1 against a feed of 2: 100 out short, worth 50 in
+    env.send(&[execute(true, 0, 100)], &[&executor]).await.unwrap();
+    let v: VaultAccount = env.account(&m.vault).await;
+    assert_eq!((v.pause_flags, v.circuit_breaker.window_outflow), (0, 50));
+
+    // 200 more in losses would cross 10%: the vault pauses and nothing fills
+    let events = env.send_with_events(&[execute(true, 1, 400)], &[&executor]).await.unwrap();
+    assert!(events.is_empty());
+    let v: VaultAccount = env.account(&m.vault).await;
+    assert_eq!((v.pause_flags, v.circuit_breaker.window_outflow), (PauseFlags::ALL, 0));
+    assert_eq!(v.execution_count, 1);
+    let intent: TradeIntent = env.account(&pda::intent(&user, &m.vault, 1).0).await;
+    assert_eq!(intent.remaining_amount, 400);
+    assert_shadow_error(env.send(&[execute(true, 1, 400)], &[&executor]).await, ShadowError::VaultPaused);
+}
+
+#[tokio::test]
+async fn oco_intent_triggers_once_and_executes() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+    let set_price = |price| instructions::set_price_feed(&admin, &m.token_in, &m.token_out, price);
+    env.send(&[set_price(PRICE_SCALE)], &[]).await.unwrap();
+
+    // stop-loss at 0.9, take-profit at 1.2
+    let ix = instructions::submit_conditional_intent(
+        &m.user.pubkey(),
+        &m.vault,
+        0,
+        &m.token_in,
+        &m.token_out,
+        300,
+        50,
+        0,
+        PRICE_SCALE * 9 / 10,
+        PRICE_SCALE * 12 / 10,
+        0,
+    );
+    env.send(&[ix], &[&m.user]).await.unwrap();
+    let intent_key = pda::intent(&m.user.pubkey(), &m.vault, 0).0;
+
+    // any signer may trigger once the feed crosses a leg
+    let keeper = env.funded().await;
+    env.send(&[set_price(PRICE_SCALE * 13 / 10)], &[]).await.unwrap();
+    let intent: TradeIntent = env.account(&intent_key).await;
+    env.send(&[instructions::trigger_intent(&keeper.pubkey(), &intent)], &[&keeper])
+        .await
+        .unwrap();
+    let intent: TradeIntent = env.account(&intent_key).await;
+    assert_eq!(intent.kind, IntentKind::Conditional);
+    assert_eq!(intent.triggered, Some(TriggerLeg::TakeProfit));
+
+    // the stop-loss leg can no longer fire
+    env.send(&[set_price(PRICE_SCALE / 2)], &[]).await.unwrap();
+    let ix = instructions::trigger_intent(&keeper.pubkey(), &intent);
+    assert_shadow_error(env.send(&[ix], &[&keeper]).await, ShadowError::AlreadyTriggered);
+
+    let ix = instructions::execute_trade(
+        &executor.pubkey(),
+        &m.vault,
+        &m.user.pubkey(),
+        0,
+        &m.token_in,
+        &m.token_out,
+        0,
+        [1; 32],
+        [2; 64],
+        [3; 32],
+        300,
+    );
+    env.send(&[ix], &[&executor]).await.unwrap();
+    let intent: TradeIntent = env.account(&intent_key).await;
+    assert!(intent.is_filled());
+    let result: ExecutionResult = env.account(&pda::result(&intent_key).0).await;
+    assert_eq!(result.executed_amount, 300);
+}
+
+#[tokio::test]
+async fn lamport_tip_paid_to_settling_executor() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+    let ix = instructions::set_price_feed(&admin, &m.token_in, &m.token_out, PRICE_SCALE);
+    env.send(&[ix], &[]).await.unwrap();
+
+    let ix = instructions::submit_limit_intent(
+        &m.user.pubkey(),
+        &m.vault,
+        0,
+        &m.token_in,
+        &m.token_out,
+        300,
+        50,
+        0,
+        PRICE_SCALE,
+        0,
+    );
+    env.send(&[ix], &[&m.user]).await.unwrap();
+    for _ in 0..2 {
+        let ix = instructions::tip_intent_lamports(&m.user.pubkey(), &m.vault, 0, 2_500);
+        env.send(&[ix], &[&m.user]).await.unwrap();
+    }
+    let intent_key = pda::intent(&m.user.pubkey(), &m.vault, 0).0;
+    let intent: TradeIntent = env.account(&intent_key).await;
+    assert_eq!((intent.tip_mint, intent.tip_amount), (None, 5_000));
+
+    // the tip is only paid by the fill that settles the intent
+    let fill = |fill_amount| {
+        instructions::execute_trade_with_feed(
+            &executor.pubkey(),
+            &m.vault,
+            &m.user.pubkey(),
+            0,
+            &m.token_in,
+            &m.token_out,
+            0,
+            [1; 32],
+            [2; 64],
+            [3; 32],
+            fill_amount,
+        )
+    };
+    let intent_before = env.lamports(&intent_key).await;
+    env.send(&[fill(100)], &[&executor]).await.unwrap();
+    assert_eq!(env.lamports(&intent_key).await, intent_before);
+    let result: ExecutionResult = env.account(&pda::result(&intent_key).0).await;
+    assert_eq!(result.tip_paid, 0);
+
+    let executor_before = env.lamports(&executor.pubkey()).await;
+    env.send(&[fill(200)], &[&executor]).await.unwrap();
+    assert_eq!(env.lamports(&executor.pubkey()).await - executor_before, 5_000);
+    assert_eq!(intent_before - env.lamports(&intent_key).await, 5_000);
+    let result: ExecutionResult = env.account(&pda::result(&intent_key).0).await;
+    assert_eq!((result.tip_mint, result.tip_paid), (None, 5_000));
+    let intent: TradeIntent = env.account(&intent_key).await;
+    assert_eq!(intent.tip_amount, 0);
+}
+
+#[tokio::test]
+async fn token_tip_paid_to_settling_executor() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+    let executor = env.funded().await;
+    let admin = env.admin();
+    env.send(&[instructions::set_executor(&admin, &executor.pubkey(), true)], &[])
+        .await
+        .unwrap();
+    let tip_mint = env.create_mint().await;
+    let user_ata = env.mint_to(&tip_mint, &m.user.pubkey(), 1_000).await;
+    let executor_ata = env.mint_to(&tip_mint, &executor.pubkey(), 0).await;
+
+    let ix = instructions::submit_trade_intent(&m.user.pubkey(), &m.vault, 0, &m.token_in, &m.token_out, 300, 50, 1);
+    env.send(&[ix], &[&m.user]).await.unwrap();
+    let ix = instructions::tip_intent_tokens(&m.user.pubkey(), &m.vault, 0, &tip_mint, 400);
+    env.send(&[ix], &[&m.user]).await.unwrap();
+    assert_eq!(env.token_balance(&user_ata).await, 600);
+
+    let intent_key = pda::intent(&m.user.pubkey(), &m.vault, 0).0;
+    let intent: TradeIntent = env.account(&intent_key).await;
+    assert_eq!((intent.tip_mint, intent.tip_amount), (Some(tip_mint), 400));
+    let mut ix = instructions::execute_trade(
+        &executor.pubkey(),
+        &m.vault,
+        &m.user.pubkey(),
+        0,
+        &m.token_in,
+        &m.token_out,
+        1,
+        [1; 32],
+        [2; 64],
+        [3; 32],
+        300,
+    );
+    // settling a token-tipped intent needs the escrow and payout accounts
+    let without_tip_accounts = ix.clone();
+    assert_shadow_error(
+        env.send(&[without_tip_accounts], &[&executor]).await,
+        ShadowError::InvalidAccount,
+    );
+    ix.accounts.extend(instructions::tip_accounts(&intent, &executor.pubkey()));
+
+    let escrow = spl_associated_token_account::get_associated_token_address(&intent_key, &tip_mint);
+    let escrow_rent = env.lamports(&escrow).await;
+    let user_before = env.lamports(&m.user.pubkey()).await;
+    env.send(&[ix], &[&executor]).await.unwrap();
+
+    // the emptied escrow closes and its rent goes back to the intent owner
+    assert_eq!(env.token_balance(&executor_ata).await, 400);
+    assert!(!env.exists(&escrow).await);
+    assert_eq!(env.lamports(&m.user.pubkey()).await - user_before, escrow_rent);
+    let result: ExecutionResult = env.account(&pda::result(&intent_key).0).await;
+    assert_eq!((result.tip_mint, result.tip_paid), (Some(tip_mint), 400));
+}
+
+#[tokio::test]
+async fn cancel_refunds_tip_of_expired_intent() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+    let now = env.now().await;
+
+    let ix = instructions::submit_limit_intent(
+        &m.user.pubkey(),
+        &m.vault,
+        0,
+        &m.token_in,
+        &m.token_out,
+        300,
+        50,
+        0,
+        PRICE_SCALE,
+        now + 60,
+    );
+    env.send(&[ix], &[&m.user]).await.unwrap();
+    let ix = instructions::tip_intent_lamports(&m.user.pubkey(), &m.vault, 0, 7_000);
+    env.send(&[ix], &[&m.user]).await.unwrap();
+    env.advance_clock(120).await;
+
+    // an expired intent takes no more tips but still refunds on cancel
+    let ix = instructions::tip_intent_lamports(&m.user.pubkey(), &m.vault, 0, 1_000);
+    assert_shadow_error(env.send(&[ix], &[&m.user]).await, ShadowError::IntentExpired);
+    let user_before = env.lamports(&m.user.pubkey()).await;
+    env.send(&[instructions::cancel_intent(&m.user.pubkey(), &m.vault, 0)], &[&m.user])
+        .await
+        .unwrap();
+    assert_eq!(env.lamports(&m.user.pubkey()).await - user_before, 7_000);
+    let intent: TradeIntent = env.account(&pda::intent(&m.user.pubkey(), &m.vault, 0).0).await;
+    assert!(intent.cancelled);
+    assert_eq!(intent.tip_amount, 0);
+}
+
+#[tokio::test]
+async fn cancel_refunds_token_tip_and_closes_escrow() {
+    let mut env = TestEnv::new().await;
+    let m = market(&mut env).await;
+    let tip_mint = env.create_mint().await;
+    let user_ata = env.mint_to(&tip_mint, &m.user.pubkey(), 1_000).await;
+
+    let ix = instructions::submit_trade_intent(&m.user.pubkey(), &m.vault, 0, &m.token_in, &m.token_out, 300, 50, 1);
+    env.send(&[ix], &[&m.user]).await.unwrap();
+    let ix = instructions::tip_intent_tokens(&m.user.pubkey(), &m.vault, 0, &tip_mint, 400);
+    env.send(&[ix], &[&m.user]).await.unwrap();
+
+    let intent_key = pda::intent(&m.user.pubkey(), &m.vault, 0).0;
+    let intent: TradeIntent = env.account(&intent_key).await;
+    let escrow = spl_associated_token_account::get_associated_token_address(&intent_key, &tip_mint);
+    let mut ix = instructions::cancel_intent(&m.user.pubkey(), &m.vault, 0);
+    ix.accounts.extend(instructions::tip_accounts(&intent, &m.user.pubkey()));
+    env.send(&[ix], &[&m.user]).await.unwrap();
+
+    assert_eq!(env.token_balance(&user_ata).await, 1_000);
+    assert!(!env.exists(&escrow).await);
+}
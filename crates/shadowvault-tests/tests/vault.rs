@@ -0,0 +1,329 @@
+use shadowvault::errors::ShadowError;
+use shadowvault::event_decoder::ShadowVaultEvent;
+use shadowvault::merkle;
+use shadowvault::state::{CustodyLedger, PauseFlags, UserPosition, VaultAccount, WithdrawalRateLimit};
+use shadowvault::WithdrawOutcome;
+use shadowvault_client::{instructions, pda};
+use shadowvault_tests::{assert_shadow_error, TestEnv};
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+
+#[tokio::test]
+async fn initialize_vault_assigns_sequential_ids() {
+    let mut env = TestEnv::new().await;
+    let owner = env.funded().await;
+
+    let first = env.create_vault(&owner).await;
+    let second = env.create_vault(&owner).await;
+    assert_eq!(first, pda::vault(&owner.pubkey(), 0).0);
+    assert_eq!(second, pda::vault(&owner.pubkey(), 1).0);
+
+    let vault: VaultAccount = env.account(&second).await;
+    assert_eq!(vault.owner, owner.pubkey());
+    assert_eq!(vault.guardian, owner.pubkey());
+    assert_eq!(vault.vault_id, 1);
+    assert_eq!(vault.encrypted_strategy_hash, [7; 32]);
+    assert_eq!(vault.tvl, 0);
+    assert_eq!(vault.event_seq, 1);
+}
+
+#[tokio::test]
+async fn deposit_books_ledger_and_position() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+
+    env.fund_and_deposit(&user, &vault, &mint, 1_000).await.unwrap();
+
+    let v: VaultAccount = env.account(&vault).await;
+    assert_eq!(v.tvl, 1_000);
+    assert_eq!(v.custody_mint_count, 1);
+    let ledger: CustodyLedger = env.account(&pda::ledger(&vault, &mint).0).await;
+    assert_eq!(ledger.balance, 1_000);
+    assert_eq!(ledger.total_shares, 1_000 * CustodyLedger::VIRTUAL_SHARES);
+    let position: UserPosition = env.account(&pda::position(&vault, &mint, &user.pubkey()).0).await;
+    assert_eq!(position.shares, ledger.total_shares);
+    assert_eq!(env.token_balance(&get_associated_token_address(&vault, &mint)).await, 1_000);
+}
+
+#[tokio::test]
+async fn deposit_for_credits_beneficiary() {
+    let mut env = TestEnv::new().await;
+    let (owner, payer) = (env.funded().await, env.funded().await);
+    let beneficiary = env.funded().await.pubkey();
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+
+    let payer_ata = env.mint_to(&mint, &payer.pubkey(), 500).await;
+    let ix = instructions::deposit_for(&payer.pubkey(), &vault, &mint, &beneficiary, 500, vec![]);
+
+    // the payer authorizes the transfer out of their ATA
+    let mut unsigned = ix.clone();
+    unsigned.accounts[0].is_signer = false;
+    assert!(env.send(&[unsigned], &[]).await.is_err());
+
+    let events = env.send_with_events(&[ix], &[&payer]).await.unwrap();
+    let deposited = events
+        .iter()
+        .find_map(|e| match e {
+            ShadowVaultEvent::Deposited(d) => Some(d.clone()),
+            _ => None,
+        })
+        .expect("Deposited event");
+    assert_eq!((deposited.user, deposited.beneficiary), (payer.pubkey(), beneficiary));
+
+    let position: UserPosition = env.account(&pda::position(&vault, &mint, &beneficiary).0).await;
+    assert_eq!(position.owner, beneficiary);
+    assert_eq!(position.shares, deposited.shares);
+    assert!(position.shares > 0);
+    assert!(!env.exists(&pda::position(&vault, &mint, &payer.pubkey()).0).await);
+    assert_eq!(env.token_balance(&payer_ata).await, 0);
+}
+
+#[tokio::test]
+async fn deposit_for_checks_the_beneficiary_allowlist() {
+    let mut env = TestEnv::new().await;
+    let (owner, payer) = (env.funded().await, env.funded().await);
+    let beneficiary = env.funded().await.pubkey();
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+    env.mint_to(&mint, &payer.pubkey(), 500).await;
+
+    // an unlisted payer may fund a listed beneficiary
+    let ix = instructions::set_allowlist(&owner.pubkey(), &vault, merkle::leaf(&beneficiary), true);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ix = instructions::deposit_for(&payer.pubkey(), &vault, &mint, &beneficiary, 200, vec![]);
+    env.send(&[ix], &[&payer]).await.unwrap();
+
+    // but a listed payer cannot fund an unlisted beneficiary
+    let ix = instructions::set_allowlist(&owner.pubkey(), &vault, merkle::leaf(&payer.pubkey()), true);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ix = instructions::deposit_for(&payer.pubkey(), &vault, &mint, &beneficiary, 200, vec![]);
+    assert_shadow_error(env.send(&[ix], &[&payer]).await, ShadowError::NotAllowlisted);
+}
+
+#[tokio::test]
+async fn owner_withdraws_to_own_account() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+    env.fund_and_deposit(&user, &vault, &mint, 1_000).await.unwrap();
+
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 400);
+    env.send(&[ix], &[&owner]).await.unwrap();
+
+    let v: VaultAccount = env.account(&vault).await;
+    assert_eq!(v.tvl, 600);
+    let ledger: CustodyLedger = env.account(&pda::ledger(&vault, &mint).0).await;
+    assert_eq!(ledger.balance, 600);
+    assert_eq!(env.token_balance(&get_associated_token_address(&owner.pubkey(), &mint)).await, 400);
+}
+
+#[tokio::test]
+async fn guardian_pauses_and_owner_unpauses() {
+    let mut env = TestEnv::new().await;
+    let (owner, guardian) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+
+    let ix = instructions::set_guardian(&owner.pubkey(), &vault, &guardian.pubkey());
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let flags = PauseFlags::DEPOSITS | PauseFlags::WITHDRAWALS;
+    let ix = instructions::pause_vault(&guardian.pubkey(), &vault, flags, true);
+    env.send(&[ix], &[&guardian]).await.unwrap();
+
+    let v: VaultAccount = env.account(&vault).await;
+    assert_eq!(v.guardian, guardian.pubkey());
+    assert_eq!(v.pause_flags, flags);
+
+    let ix = instructions::pause_vault(&owner.pubkey(), &vault, PauseFlags::DEPOSITS, false);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let v: VaultAccount = env.account(&vault).await;
+    assert_eq!(v.pause_flags, PauseFlags::WITHDRAWALS);
+}
+
+#[tokio::test]
+async fn circuit_breaker_trips_on_large_outflow() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+    env.fund_and_deposit(&user, &vault, &mint, 1_000).await.unwrap();
+    let custody = get_associated_token_address(&vault, &mint);
+
+    let ix = instructions::configure_circuit_breaker(&owner.pubkey(), &vault, 3_600, 5_000);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 400);
+    let outcome: WithdrawOutcome = env.send_with_return(&[ix], &[&owner]).await.unwrap();
+    assert_eq!((outcome.withdrawn, outcome.breaker_tripped), (400, false));
+    let v: VaultAccount = env.account(&vault).await;
+    assert_eq!((v.pause_flags, v.circuit_breaker.window_outflow), (0, 400));
+
+    // 400 + 100 of 1_000 would reach the 50% threshold, so nothing moves
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 100);
+    let outcome: WithdrawOutcome = env.send_with_return(&[ix], &[&owner]).await.unwrap();
+    assert_eq!((outcome.withdrawn, outcome.breaker_tripped), (0, true));
+    let v: VaultAccount = env.account(&vault).await;
+    assert_eq!(v.pause_flags, PauseFlags::ALL);
+    assert_eq!((v.tvl, v.circuit_breaker.window_outflow), (600, 0));
+    assert_eq!(env.token_balance(&custody).await, 600);
+    let recipient = get_associated_token_address(&owner.pubkey(), &mint);
+    assert_eq!(env.token_balance(&recipient).await, 400);
+}
+
+#[tokio::test]
+async fn withdrawal_above_breaker_headroom_is_refused() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+    env.fund_and_deposit(&user, &vault, &mint, 1_000).await.unwrap();
+    let custody = get_associated_token_address(&vault, &mint);
+
+    let ix = instructions::configure_circuit_breaker(&owner.pubkey(), &vault, 3_600, 2_000);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    // a single withdrawal of 90% never gets past the 20% threshold
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 900);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    assert_eq!(env.token_balance(&custody).await, 1_000);
+    let v: VaultAccount = env.account(&vault).await;
+    assert_eq!((v.tvl, v.pause_flags), (1_000, PauseFlags::ALL));
+    let ledger: CustodyLedger = env.account(&pda::ledger(&vault, &mint).0).await;
+    assert_eq!(ledger.balance, 1_000);
+
+    // the pause holds until the owner lifts it
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 100);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::VaultPaused);
+    let ix = instructions::pause_vault(&owner.pubkey(), &vault, PauseFlags::ALL, false);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 100);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    assert_eq!(env.token_balance(&custody).await, 900);
+}
+
+#[tokio::test]
+async fn set_withdrawal_limit_tightens_immediately() {
+    let mut env = TestEnv::new().await;
+    let owner = env.funded().await;
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+
+    let ix = instructions::set_withdrawal_limit(&owner.pubkey(), &vault, &mint, 100, 0);
+    env.send(&[ix], &[&owner]).await.unwrap();
+
+    let limit: WithdrawalRateLimit = env.account(&pda::rate_limit(&vault, &mint).0).await;
+    assert_eq!(limit.max_amount, 100);
+    assert_eq!(limit.next_max_amount, 100);
+
+    // loosening waits for the next epoch
+    let ix = instructions::set_withdrawal_limit(&owner.pubkey(), &vault, &mint, 1_000, 0);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let limit: WithdrawalRateLimit = env.account(&pda::rate_limit(&vault, &mint).0).await;
+    assert_eq!(limit.max_amount, 100);
+    assert_eq!(limit.next_max_amount, 1_000);
+}
+
+#[tokio::test]
+async fn loosened_limit_waits_even_after_an_idle_epoch() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+    env.fund_and_deposit(&user, &vault, &mint, 1_000).await.unwrap();
+    let ix = instructions::set_withdrawal_limit(&owner.pubkey(), &vault, &mint, 100, 0);
+    env.send(&[ix], &[&owner]).await.unwrap();
+
+    // nothing withdrawn in the new epoch: loosening must not promote at once
+    env.advance_epoch().await;
+    let ix = instructions::set_withdrawal_limit(&owner.pubkey(), &vault, &mint, 1_000, 0);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 101);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::WithdrawalRateLimited);
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 100);
+    env.send(&[ix], &[&owner]).await.unwrap();
+
+    env.advance_epoch().await;
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 500);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let limit: WithdrawalRateLimit = env.account(&pda::rate_limit(&vault, &mint).0).await;
+    assert_eq!((limit.max_amount, limit.withdrawn_this_epoch), (1_000, 500));
+}
+
+#[tokio::test]
+async fn donation_does_not_raise_withdrawal_cap() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+    env.fund_and_deposit(&user, &vault, &mint, 1_000).await.unwrap();
+
+    // 10% of the 1_000 booked, whatever lands in custody directly
+    let ix = instructions::set_withdrawal_limit(&owner.pubkey(), &vault, &mint, 0, 1_000);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    env.mint_to(&mint, &vault, 9_000).await;
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 101);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::WithdrawalRateLimited);
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 100);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let limit: WithdrawalRateLimit = env.account(&pda::rate_limit(&vault, &mint).0).await;
+    assert_eq!(limit.epoch_start_balance, 1_000);
+}
+
+#[tokio::test]
+async fn reconcile_reports_and_resyncs_donations() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let (a, b) = (env.create_mint().await, env.create_mint().await);
+    env.fund_and_deposit(&user, &vault, &a, 1_000).await.unwrap();
+    env.fund_and_deposit(&user, &vault, &b, 2_000).await.unwrap();
+
+    // permissionless report leaves the books alone
+    env.mint_to(&a, &vault, 50).await;
+    let ix = instructions::reconcile(&user.pubkey(), &vault, &[a, b], false);
+    env.send(&[ix], &[&user]).await.unwrap();
+    let v: VaultAccount = env.account(&vault).await;
+    assert_eq!(v.tvl, 3_000);
+
+    // the donation is parked outside share pricing; a shortfall is written down
+    let ledger_b = pda::ledger(&vault, &b).0;
+    env.overwrite::<CustodyLedger>(&ledger_b, |l| l.balance = 2_100).await;
+    let ix = instructions::reconcile(&owner.pubkey(), &vault, &[b, a], true);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ledger: CustodyLedger = env.account(&pda::ledger(&vault, &a).0).await;
+    assert_eq!((ledger.balance, ledger.unallocated), (1_000, 50));
+    let ledger: CustodyLedger = env.account(&ledger_b).await;
+    assert_eq!((ledger.balance, ledger.unallocated), (2_000, 0));
+    let v: VaultAccount = env.account(&vault).await;
+    assert_eq!(v.tvl, 3_000);
+
+    // only the owner can fold the parked donation into the share price
+    let ix = instructions::allocate_surplus(&user.pubkey(), &vault, &a);
+    assert_shadow_error(env.send(&[ix], &[&user]).await, ShadowError::Unauthorized);
+    let ix = instructions::allocate_surplus(&owner.pubkey(), &vault, &a);
+    env.send(std::slice::from_ref(&ix), &[&owner]).await.unwrap();
+    let ledger: CustodyLedger = env.account(&pda::ledger(&vault, &a).0).await;
+    assert_eq!((ledger.balance, ledger.unallocated), (1_050, 0));
+    let v: VaultAccount = env.account(&vault).await;
+    assert_eq!(v.tvl, 3_050);
+    assert_shadow_error(env.send(&[ix], &[&owner]).await, ShadowError::InvalidAmount);
+}
+
+#[tokio::test]
+async fn allowlisted_depositor_is_accepted() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+
+    // single-leaf tree: the root is the leaf and the proof is empty
+    let root = merkle::leaf(&user.pubkey());
+    let ix = instructions::set_allowlist(&owner.pubkey(), &vault, root, true);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let v: VaultAccount = env.account(&vault).await;
+    assert!(v.allowlist_enabled);
+    assert_eq!(v.allowlist_root, root);
+
+    env.fund_and_deposit(&user, &vault, &mint, 10).await.unwrap();
+}
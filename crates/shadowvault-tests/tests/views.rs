@@ -0,0 +1,151 @@
+use shadowvault::state::{CustodyLedger, PauseFlags, UserPosition, PRICE_SCALE};
+use shadowvault::{DepositPreview, PositionView, VaultSummary, WithdrawOutcome, WithdrawPreview};
+use shadowvault_client::{instructions, pda};
+use shadowvault_tests::TestEnv;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn vault_summary_covers_each_ledger() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let (a, b) = (env.create_mint().await, env.create_mint().await);
+    env.fund_and_deposit(&user, &vault, &a, 1_000).await.unwrap();
+    env.fund_and_deposit(&user, &vault, &b, 2_000).await.unwrap();
+
+    let summary: VaultSummary = env.view(instructions::get_vault_summary(&vault, &[a, b])).await.unwrap();
+    assert_eq!(summary.tvl, 3_000);
+    assert_eq!(summary.custody_mint_count, 2);
+    assert_eq!(summary.pause_flags, 0);
+    assert!(!summary.protocol_paused);
+    assert_eq!(summary.breaker_headroom, u64::MAX);
+    // first deposits mint VIRTUAL_SHARES shares per unit
+    let price = PRICE_SCALE / CustodyLedger::VIRTUAL_SHARES;
+    let mints: Vec<_> = summary.mints.iter().map(|m| (m.mint, m.balance, m.price_per_share)).collect();
+    assert_eq!(mints, vec![(a, 1_000, price), (b, 2_000, price)]);
+
+    let ix = instructions::pause_vault(&owner.pubkey(), &vault, PauseFlags::DEPOSITS, true);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let summary: VaultSummary = env.view(instructions::get_vault_summary(&vault, &[])).await.unwrap();
+    assert_eq!(summary.pause_flags, PauseFlags::DEPOSITS);
+    assert!(summary.mints.is_empty());
+}
+
+#[tokio::test]
+async fn position_ignores_donations() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+
+    // nothing deposited yet: an empty position, not an error
+    let view: PositionView = env.view(instructions::get_position(&vault, &mint, &user.pubkey())).await.unwrap();
+    assert_eq!((view.owner, view.shares, view.assets), (user.pubkey(), 0, 0));
+
+    env.fund_and_deposit(&user, &vault, &mint, 1_000).await.unwrap();
+    env.mint_to(&mint, &vault, 500).await;
+    let ix = instructions::reconcile(&owner.pubkey(), &vault, &[mint], true);
+    env.send(&[ix], &[&owner]).await.unwrap();
+
+    let view: PositionView = env.view(instructions::get_position(&vault, &mint, &user.pubkey())).await.unwrap();
+    let position: UserPosition = env.account(&pda::position(&vault, &mint, &user.pubkey()).0).await;
+    let ledger: CustodyLedger = env.account(&pda::ledger(&vault, &mint).0).await;
+    assert_eq!(view.shares, position.shares);
+    assert_eq!(view.assets, ledger.assets_for_shares(position.shares).unwrap());
+    assert_eq!((view.ledger_balance, view.total_shares), (ledger.balance, ledger.total_shares));
+    assert_eq!(view.price_per_share, ledger.price_per_share().unwrap());
+    // the donation is parked as unallocated, so the share price holds
+    assert_eq!(ledger.unallocated, 500);
+    assert_eq!(view.assets, 1_000);
+    assert_eq!(view.price_per_share, PRICE_SCALE / CustodyLedger::VIRTUAL_SHARES);
+}
+
+#[tokio::test]
+async fn preview_deposit_matches_minted_shares() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+    env.fund_and_deposit(&user, &vault, &mint, 1_000).await.unwrap();
+    env.mint_to(&mint, &vault, 300).await;
+    let ix = instructions::reconcile(&owner.pubkey(), &vault, &[mint], true);
+    env.send(&[ix], &[&owner]).await.unwrap();
+
+    let preview: DepositPreview = env.view(instructions::preview_deposit(&vault, &mint, 650)).await.unwrap();
+    assert!(preview.open);
+    assert!(!preview.allowlist_enabled);
+
+    let position = pda::position(&vault, &mint, &user.pubkey()).0;
+    let before = env.account::<UserPosition>(&position).await.shares;
+    env.fund_and_deposit(&user, &vault, &mint, 650).await.unwrap();
+    let after = env.account::<UserPosition>(&position).await.shares;
+    assert_eq!(after - before, preview.shares);
+
+    let ix = instructions::pause_vault(&owner.pubkey(), &vault, PauseFlags::DEPOSITS, true);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let preview: DepositPreview = env.view(instructions::preview_deposit(&vault, &mint, 650)).await.unwrap();
+    assert!(!preview.open);
+}
+
+#[tokio::test]
+async fn preview_withdraw_applies_rate_limit_and_breaker() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+    env.fund_and_deposit(&user, &vault, &mint, 1_000).await.unwrap();
+
+    let preview: WithdrawPreview = env.view(instructions::preview_withdraw(&vault, &mint, 1_000)).await.unwrap();
+    assert_eq!(preview.withdrawable, 1_000);
+    assert_eq!(preview.rate_limit_remaining, u64::MAX);
+    assert!(preview.allowed && !preview.trips_breaker);
+
+    let ix = instructions::set_withdrawal_limit(&owner.pubkey(), &vault, &mint, 300, 0);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let ix = instructions::configure_circuit_breaker(&owner.pubkey(), &vault, 3_600, 5_000);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    env.send(&[instructions::withdraw(&owner.pubkey(), &vault, &mint, 200)], &[&owner])
+        .await
+        .unwrap();
+
+    // 100 left under the limit; 200 of 1_000 out, so 299 more stays under 50%
+    let preview: WithdrawPreview = env.view(instructions::preview_withdraw(&vault, &mint, 150)).await.unwrap();
+    assert_eq!(preview.rate_limit_remaining, 100);
+    assert_eq!(preview.withdrawable, 100);
+    assert_eq!(preview.breaker_headroom, 299);
+    assert!(!preview.allowed && !preview.trips_breaker);
+
+    let preview: WithdrawPreview = env.view(instructions::preview_withdraw(&vault, &mint, 100)).await.unwrap();
+    assert!(preview.allowed);
+    env.send(&[instructions::withdraw(&owner.pubkey(), &vault, &mint, 100)], &[&owner])
+        .await
+        .unwrap();
+
+    let ix = instructions::pause_vault(&owner.pubkey(), &vault, PauseFlags::WITHDRAWALS, true);
+    env.send(&[ix], &[&owner]).await.unwrap();
+    let preview: WithdrawPreview = env.view(instructions::preview_withdraw(&vault, &mint, 1)).await.unwrap();
+    assert!(!preview.open && !preview.allowed);
+}
+
+#[tokio::test]
+async fn preview_withdraw_refuses_amounts_above_breaker_headroom() {
+    let mut env = TestEnv::new().await;
+    let (owner, user) = (env.funded().await, env.funded().await);
+    let vault = env.create_vault(&owner).await;
+    let mint = env.create_mint().await;
+    env.fund_and_deposit(&user, &vault, &mint, 1_000).await.unwrap();
+    let ix = instructions::configure_circuit_breaker(&owner.pubkey(), &vault, 3_600, 2_000);
+    env.send(&[ix], &[&owner]).await.unwrap();
+
+    // 200 of 1_000 trips the 20% threshold, so 199 is the most that moves
+    let preview: WithdrawPreview = env.view(instructions::preview_withdraw(&vault, &mint, 200)).await.unwrap();
+    assert_eq!((preview.breaker_headroom, preview.withdrawable), (199, 199));
+    assert!(preview.open && preview.trips_breaker && !preview.allowed);
+    let preview: WithdrawPreview = env.view(instructions::preview_withdraw(&vault, &mint, 199)).await.unwrap();
+    assert!(preview.allowed && !preview.trips_breaker);
+
+    // and the program agrees
+    let ix = instructions::withdraw(&owner.pubkey(), &vault, &mint, 200);
+    let outcome: WithdrawOutcome = env.send_with_return(&[ix], &[&owner]).await.unwrap();
+    assert!(outcome.breaker_tripped);
+}
@@ -14,4 +14,80 @@ pub enum ShadowError {
     InvalidAccount,
     #[msg("Trade execution failed")] 
     TradeFailed,
+    #[msg("Invalid MPC proof")] 
+    InvalidProof,
+    #[msg("Invalid pause flags")] 
+    InvalidPauseFlags,
+    #[msg("Invalid circuit breaker configuration")] 
+    InvalidCircuitBreakerConfig,
+    #[msg("Withdrawal exceeds the per-epoch rate limit for this mint")] 
+    WithdrawalRateLimited,
+    #[msg("Invalid withdrawal limit: bps must be <= 10000")] 
+    InvalidWithdrawalLimit,
+    #[msg("Reconciliation must cover every custody mint exactly once, sorted by mint")] 
+    IncompleteReconciliation,
+    #[msg("Deposit too small: would mint zero shares")] 
+    ZeroShares,
+    #[msg("Depositor is not on the vault allowlist")] 
+    NotAllowlisted,
+    #[msg("Token pair is not enabled for this vault")] 
+    PairNotAllowed,
+    #[msg("Intent exceeds the token pair limits")] 
+    PairLimitExceeded,
+    #[msg("Invalid trade pair configuration")] 
+    InvalidPairConfig,
+    #[msg("Protocol is paused")] 
+    ProtocolPaused,
+    #[msg("Signer is not a registered executor")] 
+    UnauthorizedExecutor,
+    #[msg("Invalid protocol configuration")] 
+    InvalidProtocolConfig,
+    #[msg("Executor set is full")] 
+    TooManyExecutors,
+    #[msg("View result would exceed the return data limit")] 
+    ViewTooLarge,
+    #[msg("Strategy is not enabled for this vault")] 
+    StrategyDisabled,
+    #[msg("Token pair is not allowed by the strategy")] 
+    StrategyPairNotAllowed,
+    #[msg("Intent exceeds the strategy limits")] 
+    StrategyLimitExceeded,
+    #[msg("Strategy cooldown has not elapsed")] 
+    StrategyCoolingDown,
+    #[msg("Invalid strategy configuration")] 
+    InvalidStrategyConfig,
+    #[msg("Invalid TWAP schedule: 2-100 slices of at least 1 and a positive interval")] 
+    InvalidTwapSchedule,
+    #[msg("Instruction does not match the intent kind")] 
+    WrongIntentKind,
+    #[msg("Intent is already filled")] 
+    IntentFilled,
+    #[msg("Next TWAP slice is not due yet")] 
+    SliceNotDue,
+    #[msg("Only limit intents can be partially filled")] 
+    PartialFillNotAllowed,
+    #[msg("Execution price does not meet the intent's limit price")] 
+    LimitPriceNotMet,
+    #[msg("Intent has expired")] 
+    IntentExpired,
+    #[msg("Intent was cancelled")] 
+    IntentCancelled,
+    #[msg("Invalid limit order: limit price must be positive and expiry in the future")] 
+    InvalidLimitOrder,
+    #[msg("Invalid trigger: set a stop-loss, a take-profit, or both with stop-loss below take-profit")] 
+    InvalidTrigger,
+    #[msg("Price must be positive and the pair's mints distinct")] 
+    InvalidPrice,
+    #[msg("Price feed is too old to act on")] 
+    StalePrice,
+    #[msg("Feed price has not crossed a trigger")] 
+    TriggerNotMet,
+    #[msg("Conditional intent has not been triggered")] 
+    IntentNotTriggered,
+    #[msg("Conditional intent is already triggered")] 
+    AlreadyTriggered,
+    #[msg("Tip must stay in the intent's existing tip denomination")] 
+    TipMismatch,
+    #[msg("Limit fills and trades on a vault with a circuit breaker need the pair's price feed")] 
+    PriceFeedRequired,
 }
@@ -0,0 +1,155 @@
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+// Declares `ShadowVaultEvent` with one variant per event and a decoder that
+// dispatches on the 8-byte discriminator, so the two cannot drift apart
+macro_rules! shadowvault_events {
+    ($($name:ident),* $(,)?) => {
+        /// Any event emitted by the program, via event CPI or `emit!` logs
+        #[derive(Clone, Debug)]
+        pub enum ShadowVaultEvent {
+            $($name(crate::$name),)*
+        }
+
+        /// Names of every decodable event, as returned by `ShadowVaultEvent::name`
+        pub const EVENT_NAMES: &[&str] = &[$(stringify!($name)),*];
+
+        /// Decodes `discriminator ++ borsh(event)`, the payload shared by
+        /// event CPI data and `Program data:` log lines
+        pub fn decode_event(data: &[u8]) -> Option<ShadowVaultEvent> {
+            if data.len() < 8 {
+                return None;
+            }
+            let (disc, mut body) = data.split_at(8);
+            $(
+                if disc == crate::$name::DISCRIMINATOR {
+                    return AnchorDeserialize::deserialize(&mut body)
+                        .ok()
+                        .map(ShadowVaultEvent::$name);
+                }
+            )*
+            None
+        }
+
+        impl ShadowVaultEvent {
+            /// Schema version carried by the event
+            pub fn version(&self) -> u8 {
+                match self {
+                    $(ShadowVaultEvent::$name(e) => e.version,)*
+                }
+            }
+
+            /// Sequence number; per vault for vault events, protocol-wide
+            /// for config events (see `is_protocol_event`)
+            pub fn seq(&self) -> u64 {
+                match self {
+                    $(ShadowVaultEvent::$name(e) => e.seq,)*
+                }
+            }
+
+            /// Event struct name, e.g. `"Deposited"`
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(ShadowVaultEvent::$name(_) => stringify!($name),)*
+                }
+            }
+
+            /// On-chain unix timestamp recorded by the handler
+            pub fn timestamp(&self) -> i64 {
+                match self {
+                    $(ShadowVaultEvent::$name(e) => e.timestamp,)*
+                }
+            }
+        }
+    };
+}
+
+shadowvault_events!(
+    VaultInitialized,
+    Deposited,
+    TradeIntentSubmitted,
+    TradeExecuted,
+    IntentTriggered,
+    IntentTipped,
+    IntentCancelled,
+    Withdrawn,
+    VaultPaused,
+    GuardianUpdated,
+    CircuitBreakerConfigured,
+    CircuitBreakerTripped,
+    WithdrawalLimitUpdated,
+    ReconciliationReport,
+    SurplusAllocated,
+    AllowlistUpdated,
+    TradePairUpdated,
+    StrategyConfigUpdated,
+    ProtocolInitialized,
+    ProtocolFeeUpdated,
+    ProtocolPauseSet,
+    ExecutorUpdated,
+    ProtocolAdminTransferStarted,
+    ProtocolAdminTransferred,
+    PriceFeedUpdated,
+);
+
+impl ShadowVaultEvent {
+    /// Vault the event belongs to; `None` for protocol config events
+    pub fn vault(&self) -> Option<Pubkey> {
+        use ShadowVaultEvent::*;
+        match self {
+            VaultInitialized(e) => Some(e.vault),
+            Deposited(e) => Some(e.vault),
+            TradeIntentSubmitted(e) => Some(e.vault),
+            TradeExecuted(e) => Some(e.vault),
+            IntentTriggered(e) => Some(e.vault),
+            IntentTipped(e) => Some(e.vault),
+            IntentCancelled(e) => Some(e.vault),
+            Withdrawn(e) => Some(e.vault),
+            VaultPaused(e) => Some(e.vault),
+            GuardianUpdated(e) => Some(e.vault),
+            CircuitBreakerConfigured(e) => Some(e.vault),
+            CircuitBreakerTripped(e) => Some(e.vault),
+            WithdrawalLimitUpdated(e) => Some(e.vault),
+            ReconciliationReport(e) => Some(e.vault),
+            SurplusAllocated(e) => Some(e.vault),
+            AllowlistUpdated(e) => Some(e.vault),
+            TradePairUpdated(e) => Some(e.vault),
+            StrategyConfigUpdated(e) => Some(e.vault),
+            ProtocolInitialized(_)
+            | ProtocolFeeUpdated(_)
+            | ProtocolPauseSet(_)
+            | ExecutorUpdated(_)
+            | ProtocolAdminTransferStarted(_)
+            | ProtocolAdminTransferred(_)
+            | PriceFeedUpdated(_) => None,
+        }
+    }
+
+    /// Whether `seq` comes from `ProtocolConfig` rather than a vault
+    pub fn is_protocol_event(&self) -> bool {
+        self.vault().is_none()
+    }
+}
+
+/// Decodes the data of one self-CPI inner instruction. Layout is
+/// `EVENT_IX_TAG_LE ++ event discriminator ++ borsh(event)`; returns `None`
+/// for anything that is not a known event.
+pub fn decode_event_cpi(data: &[u8]) -> Option<ShadowVaultEvent> {
+    decode_event(data.strip_prefix(&EVENT_IX_TAG_LE[..])?)
+}
+
+/// Collects events from a transaction's inner instructions, given as
+/// `(program_id, data)` pairs in execution order. Only instructions invoked
+/// on this program are considered, so spoofed payloads from other programs
+/// are ignored.
+pub fn decode_inner_instructions<'a, I>(inner: I) -> Vec<ShadowVaultEvent>
+where
+    I: IntoIterator<Item = (&'a Pubkey, &'a [u8])>,
+{
+    inner
+        .into_iter()
+        .filter(|(program_id, _)| **program_id == crate::ID)
+        .filter_map(|(_, data)| decode_event_cpi(data))
+        .collect()
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::errors::ShadowError;
+use crate::state::{CustodyLedger, VaultAccount};
+
+#[derive(Accounts)]
+pub struct AllocateSurplus<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ ShadowError::Unauthorized,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [CustodyLedger::SEED_PREFIX, vault.key().as_ref(), token_mint.key().as_ref()],
+        bump = ledger.bump,
+    )]
+    pub ledger: Account<'info, CustodyLedger>,
+
+    #[account(
+        associated_token::mint = token_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_ata: Account<'info, TokenAccount>,
+}
+
+pub fn handler(ctx: Context<AllocateSurplus>) -> Result<()> {
+    let ledger = &mut ctx.accounts.ledger;
+    // withdrawals since the last resync may have spent part of the surplus
+    let custody_surplus = ctx.accounts.vault_ata.amount.saturating_sub(ledger.balance);
+    let amount = ledger.unallocated.min(custody_surplus);
+    require!(amount > 0, ShadowError::InvalidAmount);
+
+    ledger.balance = ledger.balance.checked_add(amount).ok_or(ShadowError::MathOverflow)?;
+    ledger.unallocated = 0;
+    let vault = &mut ctx.accounts.vault;
+    vault.tvl = vault.tvl.checked_add(amount).ok_or(ShadowError::MathOverflow)?;
+
+    let seq = vault.next_event_seq()?;
+    emit!(crate::SurplusAllocated {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: vault.key(),
+        mint: ledger.mint,
+        amount,
+        ledger_balance: ledger.balance,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+
+use crate::errors::ShadowError;
+use crate::instructions::tip_intent::release_tip;
+use crate::state::{TradeIntent, VaultAccount};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelIntent<'info> {
+    /// Receives any keeper tip back
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        seeds = [
+            TradeIntent::SEED_PREFIX,
+            user.key().as_ref(),
+            vault.key().as_ref(),
+            intent.intent_id.to_le_bytes().as_ref(),
+        ],
+        bump = intent.bump,
+    )]
+    pub intent: Account<'info, TradeIntent>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, CancelIntent<'info>>) -> Result<()> {
+    let intent = &mut ctx.accounts.intent;
+    require!(!intent.cancelled, ShadowError::IntentCancelled);
+    require!(!intent.is_filled(), ShadowError::IntentFilled);
+
+    // the account stays, so its fill records and result keep their history
+    intent.cancelled = true;
+    // token tips also need the intent's and the user's ATA as remaining accounts
+    release_tip(
+        intent,
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    let seq = ctx.accounts.vault.next_event_seq()?;
+    emit_cpi!(crate::IntentCancelled {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: ctx.accounts.vault.key(),
+        intent: ctx.accounts.intent.key(),
+        user: ctx.accounts.user.key(),
+        remaining_amount: ctx.accounts.intent.remaining_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
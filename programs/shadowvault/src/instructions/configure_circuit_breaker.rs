@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ShadowError;
+use crate::state::{CircuitBreaker, VaultAccount};
+
+#[derive(Accounts)]
+pub struct ConfigureCircuitBreaker<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ ShadowError::Unauthorized,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+}
+
+pub fn handler(ctx: Context<ConfigureCircuitBreaker>, window_secs: i64, threshold_bps: u16) -> Result<()> {
+    require!(window_secs >= 0, ShadowError::InvalidCircuitBreakerConfig);
+    if window_secs > 0 {
+        require!(
+            threshold_bps > 0 && threshold_bps <= 10_000,
+            ShadowError::InvalidCircuitBreakerConfig
+        );
+    }
+
+    // start a fresh window so past outflow does not count against new limits
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &mut ctx.accounts.vault;
+    vault.circuit_breaker = CircuitBreaker {
+        window_secs,
+        threshold_bps,
+        window_start: now,
+        window_outflow: 0,
+    };
+
+    let seq = vault.next_event_seq()?;
+    emit!(crate::CircuitBreakerConfigured {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: vault.key(),
+        window_secs: vault.circuit_breaker.window_secs,
+        threshold_bps: vault.circuit_breaker.threshold_bps,
+        timestamp: now,
+    });
+    Ok(())
+}
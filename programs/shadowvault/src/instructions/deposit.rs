@@ -3,13 +3,20 @@ use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 
 use crate::errors::ShadowError;
-use crate::state::VaultAccount;
+use crate::state::{CustodyLedger, PauseFlags, ProtocolConfig, UserPosition, VaultAccount};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
+    #[account(
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
     pub token_mint: Account<'info, Mint>,
 
     #[account(
@@ -22,7 +29,7 @@ pub struct Deposit<'info> {
 
     #[account(
         mut,
-        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref()],
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
         bump = vault.bump,
     )]
     pub vault: Account<'info, VaultAccount>,
@@ -36,19 +43,54 @@ pub struct Deposit<'info> {
     )]
     pub vault_ata: Account<'info, TokenAccount>,
 
+    /// Book balance for this mint; created on the first deposit of the mint
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [CustodyLedger::SEED_PREFIX, vault.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        space = CustodyLedger::space(),
+    )]
+    pub ledger: Account<'info, CustodyLedger>,
+
+    /// Depositor's share balance for this mint
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [
+            UserPosition::SEED_PREFIX,
+            vault.key().as_ref(),
+            token_mint.key().as_ref(),
+            user.key().as_ref(),
+        ],
+        bump,
+        space = UserPosition::space(),
+    )]
+    pub position: Account<'info, UserPosition>,
+
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+pub fn handler(ctx: Context<Deposit>, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ShadowError::ProtocolPaused);
     require!(amount > 0, ShadowError::InvalidAmount);
-    require!(!ctx.accounts.vault.is_paused, ShadowError::VaultPaused);
+    require!(!ctx.accounts.vault.is_paused(PauseFlags::DEPOSITS), ShadowError::VaultPaused);
+    require!(
+        ctx.accounts.vault.is_allowed_depositor(&ctx.accounts.user.key(), &proof),
+        ShadowError::NotAllowlisted
+    );
 
-    let signer_seeds: &[&[u8]] = &[VaultAccount::SEED_PREFIX, ctx.accounts.vault.owner.as_ref()];
+    let vault_id = ctx.accounts.vault.vault_id.to_le_bytes();
+    let signer_seeds: &[&[u8]] = &[VaultAccount::SEED_PREFIX, ctx.accounts.vault.owner.as_ref(), &vault_id];
     let (_pda, bump) = Pubkey::find_program_address(signer_seeds, ctx.program_id);
     require_eq!(bump, ctx.accounts.vault.bump, ShadowError::InvalidAccount);
 
+    // price shares before the transfer lands
+    let shares = ctx.accounts.ledger.shares_for_deposit(amount)?;
+    require!(shares > 0, ShadowError::ZeroShares);
+
     // transfer from user -> vault custody
     let cpi_accounts = Transfer {
         from: ctx.accounts.user_ata.to_account_info(),
@@ -58,12 +100,70 @@ pub fn handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
     token::transfer(cpi_ctx, amount)?;
 
+    let vault_key = ctx.accounts.vault.key();
+    let mint_key = ctx.accounts.token_mint.key();
+    let beneficiary = ctx.accounts.user.key();
+    if ctx.accounts.ledger.open_if_new(vault_key, mint_key, ctx.bumps.ledger) {
+        ctx.accounts.vault.register_custody_mint()?;
+    }
+    ctx.accounts
+        .position
+        .open_if_new(vault_key, mint_key, beneficiary, ctx.bumps.position);
+
+    book_deposit(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.ledger,
+        &mut ctx.accounts.position,
+        amount,
+        shares,
+    )?;
+
+    let seq = ctx.accounts.vault.next_event_seq()?;
+    emit_cpi!(crate::Deposited {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: vault_key,
+        user: beneficiary,
+        beneficiary,
+        amount,
+        shares,
+        new_tvl: ctx.accounts.vault.tvl,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Books a deposit that has already landed in custody: raises TVL and the
+/// mint ledger and credits the position. Shared with `deposit_for`.
+pub(crate) fn book_deposit(
+    vault: &mut VaultAccount,
+    ledger: &mut CustodyLedger,
+    position: &mut UserPosition,
+    amount: u64,
+    shares: u64,
+) -> Result<()> {
     // increase TVL
-    let vault = &mut ctx.accounts.vault;
     vault.tvl = vault
         .tvl
         .checked_add(amount)
         .ok_or(ShadowError::MathOverflow)?;
 
+    // book the deposit against this mint's ledger
+    ledger.balance = ledger
+        .balance
+        .checked_add(amount)
+        .ok_or(ShadowError::MathOverflow)?;
+    ledger.total_shares = ledger
+        .total_shares
+        .checked_add(shares)
+        .ok_or(ShadowError::MathOverflow)?;
+
+    // credit the beneficiary's position
+    position.shares = position
+        .shares
+        .checked_add(shares)
+        .ok_or(ShadowError::MathOverflow)?;
+
     Ok(())
 }
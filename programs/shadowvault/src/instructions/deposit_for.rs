@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::errors::ShadowError;
+use crate::instructions::deposit::book_deposit;
+use crate::state::{CustodyLedger, PauseFlags, ProtocolConfig, UserPosition, VaultAccount};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount: u64, beneficiary: Pubkey)]
+pub struct DepositFor<'info> {
+    /// Funds the deposit and signs the transfer
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// Vault custody ATA owned by the vault PDA
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [CustodyLedger::SEED_PREFIX, vault.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        space = CustodyLedger::space(),
+    )]
+    pub ledger: Account<'info, CustodyLedger>,
+
+    /// Beneficiary's share balance for this mint; rent paid by the payer
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [
+            UserPosition::SEED_PREFIX,
+            vault.key().as_ref(),
+            token_mint.key().as_ref(),
+            beneficiary.as_ref(),
+        ],
+        bump,
+        space = UserPosition::space(),
+    )]
+    pub position: Account<'info, UserPosition>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<DepositFor>,
+    amount: u64,
+    beneficiary: Pubkey,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ShadowError::ProtocolPaused);
+    require!(amount > 0, ShadowError::InvalidAmount);
+    require!(beneficiary != Pubkey::default(), ShadowError::InvalidAccount);
+    require!(!ctx.accounts.vault.is_paused(PauseFlags::DEPOSITS), ShadowError::VaultPaused);
+    require!(
+        ctx.accounts.vault.is_allowed_depositor(&beneficiary, &proof),
+        ShadowError::NotAllowlisted
+    );
+
+    // price shares before the transfer lands
+    let shares = ctx.accounts.ledger.shares_for_deposit(amount)?;
+    require!(shares > 0, ShadowError::ZeroShares);
+
+    // transfer from payer -> vault custody
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.payer_ata.to_account_info(),
+        to: ctx.accounts.vault_ata.to_account_info(),
+        authority: ctx.accounts.payer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    let vault_key = ctx.accounts.vault.key();
+    let mint_key = ctx.accounts.token_mint.key();
+    if ctx.accounts.ledger.open_if_new(vault_key, mint_key, ctx.bumps.ledger) {
+        ctx.accounts.vault.register_custody_mint()?;
+    }
+    ctx.accounts
+        .position
+        .open_if_new(vault_key, mint_key, beneficiary, ctx.bumps.position);
+
+    book_deposit(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.ledger,
+        &mut ctx.accounts.position,
+        amount,
+        shares,
+    )?;
+
+    let seq = ctx.accounts.vault.next_event_seq()?;
+    emit_cpi!(crate::Deposited {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: vault_key,
+        user: ctx.accounts.payer.key(),
+        beneficiary,
+        amount,
+        shares,
+        new_tvl: ctx.accounts.vault.tvl,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -2,35 +2,69 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
 use crate::errors::ShadowError;
-use crate::state::{ExecutionResult, TradeIntent, VaultAccount};
+use crate::instructions::tip_intent::release_tip;
+use crate::instructions::withdraw::emit_breaker_tripped;
+use crate::state::{
+    ExecutionHistory, ExecutionRecord, ExecutionResult, IntentKind, PauseFlags, PriceFeed,
+    ProtocolConfig, StrategyConfig, TradeIntent, TradePair, VaultAccount,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ExecuteTrade<'info> {
     /// Vault authority must sign to execute trades (e.g., off-chain MPC executor)
+    #[account(mut)]
     pub authority: Signer<'info>,
 
+    #[account(
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
     #[account(
         mut,
-        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref()],
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
         bump = vault.bump,
     )]
     pub vault: Account<'info, VaultAccount>,
 
     #[account(
         mut,
-        seeds = [TradeIntent::SEED_PREFIX, intent.user.as_ref(), vault.key().as_ref()],
+        seeds = [
+            TradeIntent::SEED_PREFIX,
+            intent.user.as_ref(),
+            vault.key().as_ref(),
+            intent.intent_id.to_le_bytes().as_ref(),
+        ],
         bump = intent.bump,
     )]
     pub intent: Account<'info, TradeIntent>,
 
+    #[account(
+        mut,
+        seeds = [StrategyConfig::SEED_PREFIX, vault.key().as_ref(), &[intent.strategy_type]],
+        bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    /// Prices limit fills and values shortfalls for the circuit breaker;
+    /// required for limit intents and while the vault's breaker is enabled
+    #[account(
+        seeds = [PriceFeed::SEED_PREFIX, intent.token_in.as_ref(), intent.token_out.as_ref()],
+        bump = price_feed.bump,
+    )]
+    pub price_feed: Option<Account<'info, PriceFeed>>,
+
     /// Custody accounts (for illustration; routing is executed via CPIs to DEXes)
     #[account(mut)]
     pub vault_token_in: Account<'info, TokenAccount>,
     #[account(mut)]
     pub vault_token_out: Account<'info, TokenAccount>,
 
+    /// Cumulative over the intent's fills; created by the first
     #[account(
-        init,
+        init_if_needed,
         payer = authority,
         seeds = [ExecutionResult::SEED_PREFIX, intent.key().as_ref()],
         bump,
@@ -38,17 +72,56 @@ pub struct ExecuteTrade<'info> {
     )]
     pub result: Account<'info, ExecutionResult>,
 
+    /// Recent executions on this vault; created on first use
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [ExecutionHistory::SEED_PREFIX, vault.key().as_ref()],
+        bump,
+        space = ExecutionHistory::space(),
+    )]
+    pub history: AccountLoader<'info, ExecutionHistory>,
+
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(
-    ctx: Context<ExecuteTrade>,
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteTrade<'info>>,
     encrypted_params: [u8; 32],  // Encrypted trade params from Arcium MPC
     mpc_proof: [u8; 64],          // Zero-knowledge proof from MPC computation
     computation_id: [u8; 32],     // Arcium computation ID for auditability
+    fill_amount: u64,             // Whole remaining amount unless a limit order
 ) -> Result<()> {
-    require!(!ctx.accounts.vault.is_paused, ShadowError::VaultPaused);
+    let intent = &ctx.accounts.intent;
+    match intent.kind {
+        IntentKind::Market => require!(
+            fill_amount == intent.remaining_amount,
+            ShadowError::PartialFillNotAllowed
+        ),
+        IntentKind::Conditional => {
+            require!(intent.triggered.is_some(), ShadowError::IntentNotTriggered);
+            require!(
+                fill_amount == intent.remaining_amount,
+                ShadowError::PartialFillNotAllowed
+            );
+        }
+        IntentKind::Limit => require!(
+            fill_amount > 0 && fill_amount <= intent.remaining_amount,
+            ShadowError::InvalidAmount
+        ),
+        IntentKind::Twap => return err!(ShadowError::WrongIntentKind),
+    }
+    let now = Clock::get()?.unix_timestamp;
+    authorize(
+        &ctx.accounts.config,
+        &ctx.accounts.authority.key(),
+        &ctx.accounts.vault,
+        &ctx.accounts.intent,
+        &mut ctx.accounts.strategy,
+        ctx.program_id,
+        now,
+    )?;
 
     // ARCIUM MPC INTEGRATION:
     // 1. Verify MPC zero-knowledge proof
@@ -66,45 +139,180 @@ pub fn handler(
     // - Perform routed swaps via CPI to DEX adapters (Jupiter/Raydium/Orca)
     // - Enforce slippage from the signed intent without revealing strategy
     
-    let executed_amount = ctx.accounts.intent.amount;
-    let received_amount = executed_amount; // placeholder for demo
+    let executed_amount = fill_amount;
+    let received_amount = if ctx.accounts.intent.kind == IntentKind::Limit {
+        // No swap is routed yet, so a limit fill is valued at the pair's
+        // reference price; the 1:1 placeholder would meet any limit up to 1
+        let feed = ctx
+            .accounts
+            .price_feed
+            .as_ref()
+            .ok_or(ShadowError::PriceFeedRequired)?;
+        require!(feed.is_fresh(now), ShadowError::StalePrice);
+        let received_amount = feed.quote(executed_amount)?;
+        // Limit orders only fill at the limit price or better
+        ctx.accounts.intent.check_limit(executed_amount, received_amount)?;
+        received_amount
+    } else {
+        executed_amount // placeholder for demo
+    };
 
-    // Update counters
+    // A shortfall against the feed counts as outflow; one past the breaker's
+    // headroom pauses the vault and leaves the intent unfilled
+    let feed = ctx.accounts.price_feed.as_ref();
     let vault = &mut ctx.accounts.vault;
-    vault.execution_count = vault
-        .execution_count
-        .checked_add(1)
-        .ok_or(ShadowError::MathOverflow)?;
+    if let Some(window_outflow) = book_trade_loss(vault, feed, executed_amount, received_amount, now)? {
+        return emit_breaker_tripped(vault, window_outflow, now);
+    }
+    ctx.accounts.intent.record_fill(executed_amount, now)?;
+    // the fill that completes the intent earns its keeper tip
+    let tip_mint = ctx.accounts.intent.tip_mint;
+    let tip_paid = if ctx.accounts.intent.is_filled() {
+        release_tip(
+            &mut ctx.accounts.intent,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            ctx.remaining_accounts,
+        )?
+    } else {
+        0
+    };
+    book_execution(
+        &mut ctx.accounts.vault,
+        &ctx.accounts.history,
+        ctx.bumps.history,
+        &ctx.accounts.intent,
+        executed_amount,
+        received_amount,
+    )?;
 
-    // Record result account with MPC metadata
+    // Record result account with MPC metadata, accumulating partial fills
     let result = &mut ctx.accounts.result;
     result.intent = ctx.accounts.intent.key();
-    result.executed_amount = executed_amount;
-    result.received_amount = received_amount;
-
-    let (_pda, bump) = Pubkey::find_program_address(
-        &[ExecutionResult::SEED_PREFIX, ctx.accounts.intent.key().as_ref()],
-        ctx.program_id,
-    );
-    result.bump = bump;
+    result.executed_amount = result
+        .executed_amount
+        .checked_add(executed_amount)
+        .ok_or(ShadowError::MathOverflow)?;
+    result.received_amount = result
+        .received_amount
+        .checked_add(received_amount)
+        .ok_or(ShadowError::MathOverflow)?;
+    result.bump = ctx.bumps.result;
     result.success = true;
+    if tip_paid > 0 {
+        result.tip_mint = tip_mint;
+        result.tip_paid = tip_paid;
+    }
 
     // Emit event with Arcium MPC metadata
-    emit!(crate::TradeExecuted {
+    let seq = ctx.accounts.vault.next_event_seq()?;
+    emit_cpi!(crate::TradeExecuted {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
         vault: ctx.accounts.vault.key(),
         intent: ctx.accounts.intent.key(),
+        executor: ctx.accounts.authority.key(),
         executed_amount,
         received_amount,
         success: true,
+        timestamp: now,
     });
 
     Ok(())
 }
 
+/// Checks shared by every execution path: pauses, executor registration,
+/// that the intent is still open, and the intent's strategy, which the owner
+/// may have tightened since the intent was submitted. Starts the strategy's
+/// cooldown.
+pub(crate) fn authorize(
+    config: &ProtocolConfig,
+    authority: &Pubkey,
+    vault: &Account<VaultAccount>,
+    intent: &TradeIntent,
+    strategy: &mut StrategyConfig,
+    program_id: &Pubkey,
+    now: i64,
+) -> Result<()> {
+    require!(!config.paused, ShadowError::ProtocolPaused);
+    require!(config.is_executor(authority), ShadowError::UnauthorizedExecutor);
+    require!(!vault.is_paused(PauseFlags::EXECUTIONS), ShadowError::VaultPaused);
+    require!(!intent.cancelled, ShadowError::IntentCancelled);
+    require!(!intent.is_filled(), ShadowError::IntentFilled);
+    require!(!intent.is_expired(now), ShadowError::IntentExpired);
+
+    let (pair, _) = Pubkey::find_program_address(
+        &[
+            TradePair::SEED_PREFIX,
+            vault.key().as_ref(),
+            intent.token_in.as_ref(),
+            intent.token_out.as_ref(),
+        ],
+        program_id,
+    );
+    let slice_amount = TradeIntent::max_slice_amount(intent.amount, intent.slices);
+    strategy.check_intent(&pair, slice_amount, intent.max_slippage_bps)?;
+    strategy.record_execution(now)
+}
+
+/// Values the fill's shortfall against the feed price in token_in and books
+/// it as circuit breaker outflow; `Some(window_outflow)` if that trips it.
+/// Nothing to measure while the breaker is disabled.
+pub(crate) fn book_trade_loss(
+    vault: &mut Account<VaultAccount>,
+    feed: Option<&Account<PriceFeed>>,
+    executed_amount: u64,
+    received_amount: u64,
+    now: i64,
+) -> Result<Option<u64>> {
+    if !vault.circuit_breaker.is_enabled() {
+        return Ok(None);
+    }
+    let feed = feed.ok_or(ShadowError::PriceFeedRequired)?;
+    require!(feed.is_fresh(now), ShadowError::StalePrice);
+    let shortfall = feed.quote(executed_amount)?.saturating_sub(received_amount);
+    vault.record_outflow(feed.value_in(shortfall)?, now)
+}
+
+/// Books an execution on the vault: counters and history
+pub(crate) fn book_execution(
+    vault: &mut Account<VaultAccount>,
+    history: &AccountLoader<ExecutionHistory>,
+    history_bump: u8,
+    intent: &Account<TradeIntent>,
+    executed_amount: u64,
+    received_amount: u64,
+) -> Result<()> {
+    // Update counters
+    vault.execution_count = vault
+        .execution_count
+        .checked_add(1)
+        .ok_or(ShadowError::MathOverflow)?;
+
+    // A history created by init_if_needed has no discriminator until exit
+    let mut history = match history.load_mut() {
+        Ok(history) => history,
+        Err(_) => {
+            let mut history = history.load_init()?;
+            history.vault = vault.key();
+            history.bump = history_bump;
+            history
+        }
+    };
+    history.push(ExecutionRecord::new(
+        intent.key(),
+        intent.token_in,
+        intent.token_out,
+        executed_amount,
+        received_amount,
+        Clock::get()?.slot,
+    )?)
+}
+
 /// Verify MPC zero-knowledge proof from Arcium network
 /// Production: Full cryptographic verification
 /// Hackathon: Basic validation
-fn verify_mpc_proof(params: &[u8; 32], proof: &[u8; 64]) -> bool {
+pub(crate) fn verify_mpc_proof(params: &[u8; 32], proof: &[u8; 64]) -> bool {
     // Verify proof is not empty
     if proof.iter().all(|&b| b == 0) {
         return false;
@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::errors::ShadowError;
+use crate::instructions::execute_trade::{authorize, book_execution, verify_mpc_proof};
+use crate::instructions::tip_intent::release_tip;
+use crate::state::{
+    ExecutionHistory, IntentKind, ProtocolConfig, StrategyConfig, TradeIntent, TwapFill, VaultAccount,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteTwapSlice<'info> {
+    /// Registered executor cranking the schedule
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        seeds = [
+            TradeIntent::SEED_PREFIX,
+            intent.user.as_ref(),
+            vault.key().as_ref(),
+            intent.intent_id.to_le_bytes().as_ref(),
+        ],
+        bump = intent.bump,
+    )]
+    pub intent: Account<'info, TradeIntent>,
+
+    #[account(
+        mut,
+        seeds = [StrategyConfig::SEED_PREFIX, vault.key().as_ref(), &[intent.strategy_type]],
+        bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    /// Custody accounts (for illustration; routing is executed via CPIs to DEXes)
+    #[account(mut)]
+    pub vault_token_in: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault_token_out: Account<'info, TokenAccount>,
+
+    /// Fill record of the slice being executed, indexed by `slices_done`
+    #[account(
+        init,
+        payer = authority,
+        seeds = [TwapFill::SEED_PREFIX, intent.key().as_ref(), intent.slices_done.to_le_bytes().as_ref()],
+        bump,
+        space = TwapFill::space(),
+    )]
+    pub fill: Account<'info, TwapFill>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [ExecutionHistory::SEED_PREFIX, vault.key().as_ref()],
+        bump,
+        space = ExecutionHistory::space(),
+    )]
+    pub history: AccountLoader<'info, ExecutionHistory>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteTwapSlice<'info>>,
+    encrypted_params: [u8; 32],
+    mpc_proof: [u8; 64],
+) -> Result<()> {
+    require!(ctx.accounts.intent.kind == IntentKind::Twap, ShadowError::WrongIntentKind);
+    let now = Clock::get()?.unix_timestamp;
+    authorize(
+        &ctx.accounts.config,
+        &ctx.accounts.authority.key(),
+        &ctx.accounts.vault,
+        &ctx.accounts.intent,
+        &mut ctx.accounts.strategy,
+        ctx.program_id,
+        now,
+    )?;
+    require!(now >= ctx.accounts.intent.next_execution_ts, ShadowError::SliceNotDue);
+    require!(
+        verify_mpc_proof(&encrypted_params, &mpc_proof),
+        ShadowError::InvalidProof
+    );
+
+    let intent = &mut ctx.accounts.intent;
+    let slice = intent.slices_done;
+    let executed_amount = intent.next_slice_amount();
+    let received_amount = executed_amount; // placeholder, as in execute_trade
+    intent.record_fill(executed_amount, now)?;
+    let tip_paid = if intent.is_filled() {
+        release_tip(
+            intent,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            ctx.remaining_accounts,
+        )?
+    } else {
+        0
+    };
+
+    book_execution(
+        &mut ctx.accounts.vault,
+        &ctx.accounts.history,
+        ctx.bumps.history,
+        &ctx.accounts.intent,
+        executed_amount,
+        received_amount,
+    )?;
+
+    let fill = &mut ctx.accounts.fill;
+    fill.intent = ctx.accounts.intent.key();
+    fill.slice = slice;
+    fill.executed_amount = executed_amount;
+    fill.received_amount = received_amount;
+    fill.timestamp = now;
+    fill.bump = ctx.bumps.fill;
+    fill.tip_paid = tip_paid;
+
+    let seq = ctx.accounts.vault.next_event_seq()?;
+    emit_cpi!(crate::TradeExecuted {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: ctx.accounts.vault.key(),
+        intent: ctx.accounts.intent.key(),
+        executor: ctx.accounts.authority.key(),
+        executed_amount,
+        received_amount,
+        success: true,
+        timestamp: now,
+    });
+
+    Ok(())
+}
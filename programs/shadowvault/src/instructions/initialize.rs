@@ -1,8 +1,9 @@
 use anchor_lang::prelude::*;
 
-use crate::state::VaultAccount;
+use crate::state::{CircuitBreaker, OwnerVaultCounter, ProtocolConfig, VaultAccount};
 use crate::errors::ShadowError;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeVault<'info> {
     #[account(mut)]
@@ -11,10 +12,30 @@ pub struct InitializeVault<'info> {
     /// Vault owner with authority to pause/withdraw
     pub owner: Signer<'info>,
 
+    #[account(
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// Allocates this owner's vault ids; created with the first vault
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [OwnerVaultCounter::SEED_PREFIX, owner.key().as_ref()],
+        bump,
+        space = OwnerVaultCounter::space(),
+    )]
+    pub owner_counter: Account<'info, OwnerVaultCounter>,
+
     #[account(
         init,
         payer = payer,
-        seeds = [VaultAccount::SEED_PREFIX, owner.key().as_ref()],
+        seeds = [
+            VaultAccount::SEED_PREFIX,
+            owner.key().as_ref(),
+            owner_counter.next_vault_id.to_le_bytes().as_ref(),
+        ],
         bump,
         space = VaultAccount::space(),
     )]
@@ -24,20 +45,53 @@ pub struct InitializeVault<'info> {
 }
 
 pub fn handler(ctx: Context<InitializeVault>, encrypted_strategy_hash: [u8; 32]) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ShadowError::ProtocolPaused);
+
+    // claim the next id for this owner
+    let counter = &mut ctx.accounts.owner_counter;
+    let vault_id = counter.next_vault_id;
+    counter.owner = ctx.accounts.owner.key();
+    counter.bump = ctx.bumps.owner_counter;
+    counter.next_vault_id = vault_id
+        .checked_add(1)
+        .ok_or(ShadowError::MathOverflow)?;
+
     let vault = &mut ctx.accounts.vault;
     vault.owner = ctx.accounts.owner.key();
+    vault.vault_id = vault_id;
+    vault.guardian = ctx.accounts.owner.key();
     vault.encrypted_strategy_hash = encrypted_strategy_hash;
     vault.tvl = 0;
     vault.execution_count = 0;
-    vault.is_paused = false;
+    vault.pause_flags = 0;
+    vault.circuit_breaker = CircuitBreaker::default();
+    vault.custody_mint_count = 0;
+    vault.allowlist_enabled = false;
+    vault.allowlist_root = [0u8; 32];
+    vault.event_seq = 0;
 
     let (_pda, bump) = Pubkey::find_program_address(
-        &[VaultAccount::SEED_PREFIX, ctx.accounts.owner.key().as_ref()],
+        &[
+            VaultAccount::SEED_PREFIX,
+            ctx.accounts.owner.key().as_ref(),
+            vault_id.to_le_bytes().as_ref(),
+        ],
         ctx.program_id,
     );
     vault.bump = bump;
 
     // simple owner check
     require_keys_eq!(vault.owner, ctx.accounts.owner.key(), ShadowError::Unauthorized);
+
+    let seq = vault.next_event_seq()?;
+    emit_cpi!(crate::VaultInitialized {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: vault.key(),
+        owner: vault.owner,
+        vault_id,
+        encrypted_strategy_hash,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+use crate::errors::ShadowError;
+use crate::state::ProtocolConfig;
+
+#[derive(Accounts)]
+pub struct InitializeProtocol<'info> {
+    /// Becomes the protocol admin; must be the program's upgrade authority,
+    /// so nobody can front-run the deployer to the config
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// This program's ProgramData, derived the way the upgradeable loader does
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ShadowError::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump,
+        space = ProtocolConfig::space(),
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeProtocol>, protocol_fee_bps: u16, fee_recipient: Pubkey) -> Result<()> {
+    require!(
+        protocol_fee_bps <= ProtocolConfig::MAX_PROTOCOL_FEE_BPS,
+        ShadowError::InvalidProtocolConfig
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.pending_admin = Pubkey::default();
+    config.protocol_fee_bps = protocol_fee_bps;
+    config.fee_recipient = fee_recipient;
+    config.paused = false;
+    config.executors = Vec::new();
+    config.event_seq = 0;
+    config.bump = ctx.bumps.config;
+
+    let seq = config.next_event_seq()?;
+    emit!(crate::ProtocolInitialized {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        admin: config.admin,
+        protocol_fee_bps: config.protocol_fee_bps,
+        fee_recipient: config.fee_recipient,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -1,17 +1,68 @@
+pub mod initialize_protocol;
+pub use initialize_protocol::*;
+
+pub mod protocol_admin;
+pub use protocol_admin::*;
+
 pub mod initialize;
 pub use initialize::*;
 
 pub mod deposit;
 pub use deposit::*;
 
+pub mod deposit_for;
+pub use deposit_for::*;
+
 pub mod submit_trade_intent;
 pub use submit_trade_intent::*;
 
 pub mod execute_trade;
 pub use execute_trade::*;
 
+pub mod execute_twap_slice;
+pub use execute_twap_slice::*;
+
+pub mod cancel_intent;
+pub use cancel_intent::*;
+
+pub mod trigger_intent;
+pub use trigger_intent::*;
+
+pub mod tip_intent;
+pub use tip_intent::*;
+
 pub mod withdraw;
 pub use withdraw::*;
 
 pub mod pause_vault;
 pub use pause_vault::*;
+
+pub mod set_guardian;
+pub use set_guardian::*;
+
+pub mod configure_circuit_breaker;
+pub use configure_circuit_breaker::*;
+
+pub mod set_withdrawal_limit;
+pub use set_withdrawal_limit::*;
+
+pub mod reconcile;
+pub use reconcile::*;
+
+pub mod allocate_surplus;
+pub use allocate_surplus::*;
+
+pub mod set_allowlist;
+pub use set_allowlist::*;
+
+pub mod set_trade_pair;
+pub use set_trade_pair::*;
+
+pub mod set_strategy_config;
+pub use set_strategy_config::*;
+
+pub mod set_price_feed;
+pub use set_price_feed::*;
+
+pub mod views;
+pub use views::*;
@@ -1,22 +1,48 @@
 use anchor_lang::prelude::*;
 
 use crate::errors::ShadowError;
-use crate::state::VaultAccount;
+use crate::state::{PauseFlags, VaultAccount};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct TogglePause<'info> {
+    /// Vault owner or guardian
     pub authority: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref()],
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
         bump = vault.bump,
     )]
     pub vault: Account<'info, VaultAccount>,
 }
 
-pub fn handler(ctx: Context<TogglePause>, pause: bool) -> Result<()> {
-    require_keys_eq!(ctx.accounts.vault.owner, ctx.accounts.authority.key(), ShadowError::Unauthorized);
-    ctx.accounts.vault.is_paused = pause;
+pub fn handler(ctx: Context<TogglePause>, flags: u8, pause: bool) -> Result<()> {
+    require!(
+        ctx.accounts.vault.is_pause_authority(&ctx.accounts.authority.key()),
+        ShadowError::Unauthorized
+    );
+    require!(
+        flags != 0 && flags & !PauseFlags::ALL == 0,
+        ShadowError::InvalidPauseFlags
+    );
+
+    let vault = &mut ctx.accounts.vault;
+    if pause {
+        vault.pause_flags |= flags;
+    } else {
+        vault.pause_flags &= !flags;
+    }
+
+    let seq = vault.next_event_seq()?;
+    emit_cpi!(crate::VaultPaused {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: vault.key(),
+        authority: ctx.accounts.authority.key(),
+        pause_flags: vault.pause_flags,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
     Ok(())
 }
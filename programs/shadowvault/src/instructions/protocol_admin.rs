@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ShadowError;
+use crate::state::ProtocolConfig;
+
+#[derive(Accounts)]
+pub struct UpdateProtocolConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+        has_one = admin @ ShadowError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptProtocolAdmin<'info> {
+    /// Must match `config.pending_admin`
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+pub fn set_fee(ctx: Context<UpdateProtocolConfig>, protocol_fee_bps: u16, fee_recipient: Pubkey) -> Result<()> {
+    require!(
+        protocol_fee_bps <= ProtocolConfig::MAX_PROTOCOL_FEE_BPS,
+        ShadowError::InvalidProtocolConfig
+    );
+    let config = &mut ctx.accounts.config;
+    config.protocol_fee_bps = protocol_fee_bps;
+    config.fee_recipient = fee_recipient;
+
+    let seq = config.next_event_seq()?;
+    emit!(crate::ProtocolFeeUpdated {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        protocol_fee_bps: config.protocol_fee_bps,
+        fee_recipient: config.fee_recipient,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub fn set_pause(ctx: Context<UpdateProtocolConfig>, paused: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.paused = paused;
+
+    let seq = config.next_event_seq()?;
+    emit!(crate::ProtocolPauseSet {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        admin: config.admin,
+        paused: config.paused,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub fn set_executor(ctx: Context<UpdateProtocolConfig>, executor: Pubkey, enabled: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let executors = &mut config.executors;
+    let registered = executors.contains(&executor);
+    if enabled && !registered {
+        require!(
+            executors.len() < ProtocolConfig::MAX_EXECUTORS,
+            ShadowError::TooManyExecutors
+        );
+        executors.push(executor);
+    } else if !enabled && registered {
+        executors.retain(|k| *k != executor);
+    }
+
+    let seq = config.next_event_seq()?;
+    emit!(crate::ExecutorUpdated {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        executor,
+        enabled: config.is_executor(&executor),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Step one of the admin handover; the new admin must accept
+pub fn transfer_admin(ctx: Context<UpdateProtocolConfig>, new_admin: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.pending_admin = new_admin;
+
+    let seq = config.next_event_seq()?;
+    emit!(crate::ProtocolAdminTransferStarted {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        admin: config.admin,
+        pending_admin: config.pending_admin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub fn accept_admin(ctx: Context<AcceptProtocolAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    require!(config.pending_admin != Pubkey::default(), ShadowError::Unauthorized);
+    require_keys_eq!(config.pending_admin, ctx.accounts.new_admin.key(), ShadowError::Unauthorized);
+    let previous_admin = config.admin;
+    config.admin = config.pending_admin;
+    config.pending_admin = Pubkey::default();
+
+    let seq = config.next_event_seq()?;
+    emit!(crate::ProtocolAdminTransferred {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        previous_admin,
+        new_admin: config.admin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::TokenAccount;
+
+use crate::errors::ShadowError;
+use crate::state::{CustodyLedger, VaultAccount};
+use crate::{MintDelta, ReconciliationReport};
+
+#[derive(Accounts)]
+pub struct Reconcile<'info> {
+    /// Anyone may reconcile; only the vault owner may resync
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+    // remaining_accounts: (ledger, custody ATA) per mint, ascending by mint,
+    // covering all `vault.custody_mint_count` ledgers; ledgers writable to resync
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Reconcile<'info>>,
+    resync: bool,
+) -> Result<()> {
+    if resync {
+        require_keys_eq!(ctx.accounts.vault.owner, ctx.accounts.caller.key(), ShadowError::Unauthorized);
+    }
+
+    let vault_key = ctx.accounts.vault.key();
+    let ledger_tvl = ctx.accounts.vault.tvl;
+    let pairs = ctx.remaining_accounts;
+    require!(
+        pairs.len() == 2 * ctx.accounts.vault.custody_mint_count as usize,
+        ShadowError::IncompleteReconciliation
+    );
+
+    let mut deltas = Vec::with_capacity(pairs.len() / 2);
+    let mut custody_total: u64 = 0;
+    let mut booked_total: u64 = 0;
+    let mut last_mint: Option<Pubkey> = None;
+
+    for pair in pairs.chunks_exact(2) {
+        let mut ledger: Account<'info, CustodyLedger> = Account::try_from(&pair[0])?;
+        let custody: Account<'info, TokenAccount> = Account::try_from(&pair[1])?;
+
+        // Strictly ascending mints rule out passing one ledger twice
+        require!(
+            last_mint.map_or(true, |m| m < ledger.mint),
+            ShadowError::IncompleteReconciliation
+        );
+        last_mint = Some(ledger.mint);
+
+        let (ledger_pda, _) = Pubkey::find_program_address(
+            &[CustodyLedger::SEED_PREFIX, vault_key.as_ref(), ledger.mint.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(ledger.key(), ledger_pda, ShadowError::InvalidAccount);
+        require_keys_eq!(
+            custody.key(),
+            get_associated_token_address(&vault_key, &ledger.mint),
+            ShadowError::InvalidAccount
+        );
+
+        custody_total = custody_total
+            .checked_add(custody.amount)
+            .ok_or(ShadowError::MathOverflow)?;
+        deltas.push(MintDelta {
+            mint: ledger.mint,
+            ledger_balance: ledger.balance,
+            custody_balance: custody.amount,
+            delta: custody.amount as i128 - ledger.balance as i128,
+        });
+
+        // A shortfall is a real loss and reprices shares down; a surplus is
+        // parked in `unallocated` so a donation can't inflate the share price
+        if resync {
+            ledger.balance = ledger.balance.min(custody.amount);
+            ledger.unallocated = custody.amount - ledger.balance;
+            ledger.exit(ctx.program_id)?;
+        }
+        booked_total = booked_total
+            .checked_add(ledger.balance)
+            .ok_or(ShadowError::MathOverflow)?;
+    }
+
+    let vault = &mut ctx.accounts.vault;
+    if resync {
+        vault.tvl = booked_total;
+    }
+
+    let seq = vault.next_event_seq()?;
+    emit!(ReconciliationReport {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: vault_key,
+        caller: ctx.accounts.caller.key(),
+        ledger_tvl,
+        custody_total,
+        deltas,
+        resynced: resync,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
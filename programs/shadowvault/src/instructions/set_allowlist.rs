@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ShadowError;
+use crate::state::VaultAccount;
+
+#[derive(Accounts)]
+pub struct SetAllowlist<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ ShadowError::Unauthorized,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+}
+
+pub fn handler(ctx: Context<SetAllowlist>, root: [u8; 32], enabled: bool) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    vault.allowlist_root = root;
+    vault.allowlist_enabled = enabled;
+
+    let seq = vault.next_event_seq()?;
+    emit!(crate::AllowlistUpdated {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: vault.key(),
+        root: vault.allowlist_root,
+        enabled: vault.allowlist_enabled,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
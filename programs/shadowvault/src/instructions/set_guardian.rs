@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ShadowError;
+use crate::state::VaultAccount;
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ ShadowError::Unauthorized,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+}
+
+pub fn handler(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let previous_guardian = vault.guardian;
+    vault.guardian = guardian;
+
+    let seq = vault.next_event_seq()?;
+    emit!(crate::GuardianUpdated {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: vault.key(),
+        previous_guardian,
+        new_guardian: vault.guardian,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
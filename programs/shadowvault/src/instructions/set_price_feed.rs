@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::errors::ShadowError;
+use crate::state::{PriceFeed, ProtocolConfig};
+
+#[derive(Accounts)]
+pub struct SetPriceFeed<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+        has_one = admin @ ShadowError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub token_in_mint: Account<'info, Mint>,
+    pub token_out_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [PriceFeed::SEED_PREFIX, token_in_mint.key().as_ref(), token_out_mint.key().as_ref()],
+        bump,
+        space = PriceFeed::space(),
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetPriceFeed>, price: u64) -> Result<()> {
+    require_keys_neq!(
+        ctx.accounts.token_in_mint.key(),
+        ctx.accounts.token_out_mint.key(),
+        ShadowError::InvalidPrice
+    );
+    require!(price > 0, ShadowError::InvalidPrice);
+
+    let now = Clock::get()?.unix_timestamp;
+    let feed = &mut ctx.accounts.price_feed;
+    feed.token_in = ctx.accounts.token_in_mint.key();
+    feed.token_out = ctx.accounts.token_out_mint.key();
+    feed.price = price;
+    feed.updated_at = now;
+    feed.bump = ctx.bumps.price_feed;
+
+    let seq = ctx.accounts.config.next_event_seq()?;
+    emit!(crate::PriceFeedUpdated {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        token_in: feed.token_in,
+        token_out: feed.token_out,
+        price,
+        timestamp: now,
+    });
+    Ok(())
+}
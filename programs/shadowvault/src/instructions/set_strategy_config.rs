@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ShadowError;
+use crate::state::{StrategyConfig, VaultAccount};
+
+#[derive(Accounts)]
+#[instruction(strategy_type: u8)]
+pub struct SetStrategyConfig<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ ShadowError::Unauthorized,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [StrategyConfig::SEED_PREFIX, vault.key().as_ref(), &[strategy_type]],
+        bump,
+        space = StrategyConfig::space(),
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<SetStrategyConfig>,
+    strategy_type: u8,
+    enabled: bool,
+    allowed_pairs: Vec<Pubkey>,
+    max_amount: u64,
+    max_slippage_bps: u16,
+    cooldown_secs: i64,
+) -> Result<()> {
+    require!(
+        allowed_pairs.len() <= StrategyConfig::MAX_ALLOWED_PAIRS,
+        ShadowError::InvalidStrategyConfig
+    );
+    require!(max_slippage_bps <= 10_000, ShadowError::InvalidStrategyConfig);
+    require!(cooldown_secs >= 0, ShadowError::InvalidStrategyConfig);
+
+    // the cooldown clock survives updates, so re-saving cannot reset it
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.vault = ctx.accounts.vault.key();
+    strategy.strategy_type = strategy_type;
+    strategy.enabled = enabled;
+    strategy.allowed_pairs = allowed_pairs;
+    strategy.max_amount = max_amount;
+    strategy.max_slippage_bps = max_slippage_bps;
+    strategy.cooldown_secs = cooldown_secs;
+    strategy.bump = ctx.bumps.strategy;
+
+    let seq = ctx.accounts.vault.next_event_seq()?;
+    emit!(crate::StrategyConfigUpdated {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: strategy.vault,
+        strategy_type,
+        enabled,
+        allowed_pairs: strategy.allowed_pairs.clone(),
+        max_amount,
+        max_slippage_bps,
+        cooldown_secs,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::errors::ShadowError;
+use crate::state::{TradePair, VaultAccount};
+
+#[derive(Accounts)]
+pub struct SetTradePair<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ ShadowError::Unauthorized,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub token_in_mint: Account<'info, Mint>,
+    pub token_out_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [
+            TradePair::SEED_PREFIX,
+            vault.key().as_ref(),
+            token_in_mint.key().as_ref(),
+            token_out_mint.key().as_ref(),
+        ],
+        bump,
+        space = TradePair::space(),
+    )]
+    pub pair: Account<'info, TradePair>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<SetTradePair>,
+    enabled: bool,
+    max_amount: u64,
+    max_slippage_bps: u16,
+) -> Result<()> {
+    require_keys_neq!(
+        ctx.accounts.token_in_mint.key(),
+        ctx.accounts.token_out_mint.key(),
+        ShadowError::InvalidPairConfig
+    );
+    require!(max_slippage_bps <= 10_000, ShadowError::InvalidPairConfig);
+
+    let pair = &mut ctx.accounts.pair;
+    pair.vault = ctx.accounts.vault.key();
+    pair.token_in = ctx.accounts.token_in_mint.key();
+    pair.token_out = ctx.accounts.token_out_mint.key();
+    pair.enabled = enabled;
+    pair.max_amount = max_amount;
+    pair.max_slippage_bps = max_slippage_bps;
+    pair.bump = ctx.bumps.pair;
+
+    let seq = ctx.accounts.vault.next_event_seq()?;
+    emit!(crate::TradePairUpdated {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: pair.vault,
+        token_in: pair.token_in,
+        token_out: pair.token_out,
+        enabled: pair.enabled,
+        max_amount: pair.max_amount,
+        max_slippage_bps: pair.max_slippage_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::errors::ShadowError;
+use crate::instructions::views::load_optional;
+use crate::state::{CustodyLedger, VaultAccount, WithdrawalRateLimit};
+
+#[derive(Accounts)]
+pub struct SetWithdrawalLimit<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+        has_one = owner @ ShadowError::Unauthorized,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [WithdrawalRateLimit::SEED_PREFIX, vault.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        space = WithdrawalRateLimit::space(),
+    )]
+    pub rate_limit: Account<'info, WithdrawalRateLimit>,
+
+    /// CHECK: ledger PDA; absent until the mint's first deposit
+    #[account(
+        seeds = [CustodyLedger::SEED_PREFIX, vault.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub ledger: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetWithdrawalLimit>, max_amount: u64, max_bps: u16) -> Result<()> {
+    require!(max_bps <= 10_000, ShadowError::InvalidWithdrawalLimit);
+
+    let rate_limit = &mut ctx.accounts.rate_limit;
+    if rate_limit.vault == Pubkey::default() {
+        rate_limit.vault = ctx.accounts.vault.key();
+        rate_limit.mint = ctx.accounts.token_mint.key();
+        rate_limit.bump = ctx.bumps.rate_limit;
+    }
+
+    // Open the current epoch first, or the next withdrawal would roll a
+    // stale one and promote a loosened limit straight away
+    let clock = Clock::get()?;
+    let ledger_balance = load_optional::<CustodyLedger>(&ctx.accounts.ledger)?.map_or(0, |l| l.balance);
+    rate_limit.roll_epoch(clock.epoch, ledger_balance);
+
+    rate_limit.next_max_amount = max_amount;
+    rate_limit.next_max_bps = max_bps;
+
+    // Tightening applies now; loosening waits for the epoch to roll
+    let immediate = rate_limit.is_tightening(max_amount, max_bps);
+    if immediate {
+        rate_limit.max_amount = max_amount;
+        rate_limit.max_bps = max_bps;
+    }
+
+    let seq = ctx.accounts.vault.next_event_seq()?;
+    emit!(crate::WithdrawalLimitUpdated {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: rate_limit.vault,
+        mint: rate_limit.mint,
+        max_amount: rate_limit.next_max_amount,
+        max_bps: rate_limit.next_max_bps,
+        effective_epoch: if immediate { clock.epoch } else { clock.epoch + 1 },
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
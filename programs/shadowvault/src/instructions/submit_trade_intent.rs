@@ -1,29 +1,72 @@
 use anchor_lang::prelude::*;
 
 use crate::errors::ShadowError;
-use crate::state::{TradeIntent, VaultAccount};
+use crate::state::{
+    IntentKind, PauseFlags, ProtocolConfig, StrategyConfig, TradeIntent, TradePair, UserIntentCounter,
+    VaultAccount,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
+#[instruction(token_in: Pubkey, token_out: Pubkey, amount: u64, max_slippage_bps: u16, strategy_type: u8)]
 pub struct SubmitTradeIntent<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
+    #[account(
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
     #[account(
         mut,
-        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref()],
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
         bump = vault.bump,
     )]
     pub vault: Account<'info, VaultAccount>,
 
+    /// Allocates this user's intent ids on the vault; created with the first
+    /// intent
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [UserIntentCounter::SEED_PREFIX, user.key().as_ref(), vault.key().as_ref()],
+        bump,
+        space = UserIntentCounter::space(),
+    )]
+    pub intent_counter: Account<'info, UserIntentCounter>,
+
+    /// A fresh account per intent, so a cancelled or filled one keeps its
+    /// fills and result while the user submits the next
     #[account(
         init,
         payer = user,
-        seeds = [TradeIntent::SEED_PREFIX, user.key().as_ref(), vault.key().as_ref()],
+        seeds = [
+            TradeIntent::SEED_PREFIX,
+            user.key().as_ref(),
+            vault.key().as_ref(),
+            intent_counter.next_intent_id.to_le_bytes().as_ref(),
+        ],
         bump,
         space = TradeIntent::space(),
     )]
     pub intent: Account<'info, TradeIntent>,
 
+    /// Owner-approved pair; missing PDA means the pair was never allowed
+    #[account(
+        seeds = [TradePair::SEED_PREFIX, vault.key().as_ref(), token_in.as_ref(), token_out.as_ref()],
+        bump = pair.bump,
+    )]
+    pub pair: Account<'info, TradePair>,
+
+    /// Owner-configured strategy the intent runs under
+    #[account(
+        seeds = [StrategyConfig::SEED_PREFIX, vault.key().as_ref(), &[strategy_type]],
+        bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, StrategyConfig>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -35,31 +78,187 @@ pub fn handler(
     max_slippage_bps: u16,
     strategy_type: u8,
 ) -> Result<()> {
+    let terms = IntentTerms::single(IntentKind::Market);
+    open_intent(ctx, token_in, token_out, amount, max_slippage_bps, strategy_type, terms)
+}
+
+/// TWAP/DCA: `amount` split into `slices` executed at least
+/// `interval_secs` apart; the first slice is due immediately
+#[allow(clippy::too_many_arguments)]
+pub fn twap_handler(
+    ctx: Context<SubmitTradeIntent>,
+    token_in: Pubkey,
+    token_out: Pubkey,
+    amount: u64,
+    max_slippage_bps: u16,
+    strategy_type: u8,
+    slices: u16,
+    interval_secs: i64,
+) -> Result<()> {
+    require!(
+        (2..=TradeIntent::MAX_SLICES).contains(&slices) && amount >= slices as u64 && interval_secs > 0,
+        ShadowError::InvalidTwapSchedule
+    );
+    let terms = IntentTerms {
+        slices,
+        interval_secs,
+        ..IntentTerms::single(IntentKind::Twap)
+    };
+    open_intent(ctx, token_in, token_out, amount, max_slippage_bps, strategy_type, terms)
+}
+
+/// Limit order: fills, possibly in parts, only at `limit_price` or better
+/// until filled, cancelled or past `expires_at` (0 = never)
+#[allow(clippy::too_many_arguments)]
+pub fn limit_handler(
+    ctx: Context<SubmitTradeIntent>,
+    token_in: Pubkey,
+    token_out: Pubkey,
+    amount: u64,
+    max_slippage_bps: u16,
+    strategy_type: u8,
+    limit_price: u64,
+    expires_at: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        limit_price > 0 && (expires_at == 0 || expires_at > now),
+        ShadowError::InvalidLimitOrder
+    );
+    let terms = IntentTerms {
+        limit_price,
+        expires_at,
+        ..IntentTerms::single(IntentKind::Limit)
+    };
+    open_intent(ctx, token_in, token_out, amount, max_slippage_bps, strategy_type, terms)
+}
+
+/// Stop-loss and/or take-profit: fills in full once `trigger_intent` sees
+/// the pair's price feed cross a trigger; with both set, an OCO pair
+#[allow(clippy::too_many_arguments)]
+pub fn conditional_handler(
+    ctx: Context<SubmitTradeIntent>,
+    token_in: Pubkey,
+    token_out: Pubkey,
+    amount: u64,
+    max_slippage_bps: u16,
+    strategy_type: u8,
+    stop_loss_price: u64,
+    take_profit_price: u64,
+    expires_at: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let legs_ok = match (stop_loss_price, take_profit_price) {
+        (0, 0) => false,
+        (0, _) | (_, 0) => true,
+        (stop, take) => stop < take,
+    };
+    require!(
+        legs_ok && (expires_at == 0 || expires_at > now),
+        ShadowError::InvalidTrigger
+    );
+    let terms = IntentTerms {
+        stop_loss_price,
+        take_profit_price,
+        expires_at,
+        ..IntentTerms::single(IntentKind::Conditional)
+    };
+    open_intent(ctx, token_in, token_out, amount, max_slippage_bps, strategy_type, terms)
+}
+
+/// Kind-specific terms fixed at submission
+struct IntentTerms {
+    kind: IntentKind,
+    slices: u16,
+    interval_secs: i64,
+    limit_price: u64,
+    expires_at: i64,
+    stop_loss_price: u64,
+    take_profit_price: u64,
+}
+
+impl IntentTerms {
+    fn single(kind: IntentKind) -> Self {
+        Self {
+            kind,
+            slices: 1,
+            interval_secs: 0,
+            limit_price: 0,
+            expires_at: 0,
+            stop_loss_price: 0,
+            take_profit_price: 0,
+        }
+    }
+}
+
+fn open_intent(
+    ctx: Context<SubmitTradeIntent>,
+    token_in: Pubkey,
+    token_out: Pubkey,
+    amount: u64,
+    max_slippage_bps: u16,
+    strategy_type: u8,
+    terms: IntentTerms,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ShadowError::ProtocolPaused);
     require!(amount > 0, ShadowError::InvalidAmount);
-    require!(!ctx.accounts.vault.is_paused, ShadowError::VaultPaused);
+    require!(!ctx.accounts.vault.is_paused(PauseFlags::INTENTS), ShadowError::VaultPaused);
+    // each slice is its own swap, so size limits apply per slice
+    let slice_amount = TradeIntent::max_slice_amount(amount, terms.slices);
+    ctx.accounts.pair.check_intent(slice_amount, max_slippage_bps)?;
+    ctx.accounts
+        .strategy
+        .check_intent(&ctx.accounts.pair.key(), slice_amount, max_slippage_bps)?;
+
+    // claim the next id for this user and vault
+    let counter = &mut ctx.accounts.intent_counter;
+    let intent_id = counter.next_intent_id;
+    counter.user = ctx.accounts.user.key();
+    counter.vault = ctx.accounts.vault.key();
+    counter.bump = ctx.bumps.intent_counter;
+    counter.next_intent_id = intent_id
+        .checked_add(1)
+        .ok_or(ShadowError::MathOverflow)?;
 
     let clock = Clock::get()?;
     let intent = &mut ctx.accounts.intent;
     intent.user = ctx.accounts.user.key();
     intent.vault = ctx.accounts.vault.key();
+    intent.intent_id = intent_id;
     intent.token_in = token_in;
     intent.token_out = token_out;
     intent.amount = amount;
     intent.max_slippage_bps = max_slippage_bps;
     intent.strategy_type = strategy_type;
     intent.timestamp = clock.unix_timestamp;
+    intent.kind = terms.kind;
+    intent.slices = terms.slices;
+    intent.slice_interval_secs = terms.interval_secs;
+    intent.remaining_amount = amount;
+    intent.slices_done = 0;
+    intent.next_execution_ts = clock.unix_timestamp;
+    intent.limit_price = terms.limit_price;
+    intent.expires_at = terms.expires_at;
+    intent.cancelled = false;
+    intent.stop_loss_price = terms.stop_loss_price;
+    intent.take_profit_price = terms.take_profit_price;
+    intent.triggered = None;
 
     let (_pda, bump) = Pubkey::find_program_address(
         &[
             TradeIntent::SEED_PREFIX,
             ctx.accounts.user.key().as_ref(),
             ctx.accounts.vault.key().as_ref(),
+            intent_id.to_le_bytes().as_ref(),
         ],
         ctx.program_id,
     );
     intent.bump = bump;
 
-    emit!(crate::TradeIntentSubmitted {
+    let seq = ctx.accounts.vault.next_event_seq()?;
+    emit_cpi!(crate::TradeIntentSubmitted {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
         vault: ctx.accounts.vault.key(),
         intent: intent.key(),
         user: ctx.accounts.user.key(),
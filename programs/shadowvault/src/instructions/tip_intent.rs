@@ -0,0 +1,230 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{get_associated_token_address, AssociatedToken};
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+
+use crate::errors::ShadowError;
+use crate::state::{ProtocolConfig, TradeIntent, VaultAccount};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TipIntentLamports<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        seeds = [
+            TradeIntent::SEED_PREFIX,
+            user.key().as_ref(),
+            vault.key().as_ref(),
+            intent.intent_id.to_le_bytes().as_ref(),
+        ],
+        bump = intent.bump,
+    )]
+    pub intent: Account<'info, TradeIntent>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TipIntentTokens<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        seeds = [
+            TradeIntent::SEED_PREFIX,
+            user.key().as_ref(),
+            vault.key().as_ref(),
+            intent.intent_id.to_le_bytes().as_ref(),
+        ],
+        bump = intent.bump,
+    )]
+    pub intent: Account<'info, TradeIntent>,
+
+    pub tip_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = tip_mint,
+        associated_token::authority = user,
+    )]
+    pub user_ata: Account<'info, TokenAccount>,
+
+    /// Tip escrow, owned by the intent PDA
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = tip_mint,
+        associated_token::authority = intent,
+    )]
+    pub intent_ata: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn lamports_handler(ctx: Context<TipIntentLamports>, amount: u64) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ShadowError::ProtocolPaused);
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.intent.add_tip(None, amount, now)?;
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.user.to_account_info(),
+                to: ctx.accounts.intent.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let seq = ctx.accounts.vault.next_event_seq()?;
+    emit_cpi!(crate::IntentTipped {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: ctx.accounts.vault.key(),
+        intent: ctx.accounts.intent.key(),
+        user: ctx.accounts.user.key(),
+        tip_mint: None,
+        amount,
+        tip_amount: ctx.accounts.intent.tip_amount,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub fn tokens_handler(ctx: Context<TipIntentTokens>, amount: u64) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ShadowError::ProtocolPaused);
+    let now = Clock::get()?.unix_timestamp;
+    let tip_mint = ctx.accounts.tip_mint.key();
+    ctx.accounts.intent.add_tip(Some(tip_mint), amount, now)?;
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user_ata.to_account_info(),
+                to: ctx.accounts.intent_ata.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let seq = ctx.accounts.vault.next_event_seq()?;
+    emit_cpi!(crate::IntentTipped {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: ctx.accounts.vault.key(),
+        intent: ctx.accounts.intent.key(),
+        user: ctx.accounts.user.key(),
+        tip_mint: Some(tip_mint),
+        amount,
+        tip_amount: ctx.accounts.intent.tip_amount,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+/// Pays the intent's whole tip to `recipient` and returns the amount. Token
+/// tips need remaining accounts `[intent ATA, recipient ATA, intent owner]`,
+/// all writable; the emptied escrow is closed and its rent goes to the owner.
+pub(crate) fn release_tip<'info>(
+    intent: &mut Account<'info, TradeIntent>,
+    recipient: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<u64> {
+    let amount = intent.tip_amount;
+    if amount == 0 {
+        return Ok(0);
+    }
+    intent.tip_amount = 0;
+
+    let Some(tip_mint) = intent.tip_mint else {
+        // the intent is program-owned, so its surplus lamports move directly
+        let intent_info = intent.to_account_info();
+        let mut from = intent_info.try_borrow_mut_lamports()?;
+        let mut to = recipient.try_borrow_mut_lamports()?;
+        **from = from.checked_sub(amount).ok_or(ShadowError::MathOverflow)?;
+        **to = to.checked_add(amount).ok_or(ShadowError::MathOverflow)?;
+        return Ok(amount);
+    };
+
+    let [escrow, destination, owner, ..] = remaining_accounts else {
+        return err!(ShadowError::InvalidAccount);
+    };
+    require_keys_eq!(owner.key(), intent.user, ShadowError::InvalidAccount);
+    require_keys_eq!(
+        escrow.key(),
+        get_associated_token_address(&intent.key(), &tip_mint),
+        ShadowError::InvalidAccount
+    );
+    require_keys_eq!(
+        destination.key(),
+        get_associated_token_address(recipient.key, &tip_mint),
+        ShadowError::InvalidAccount
+    );
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        TradeIntent::SEED_PREFIX,
+        intent.user.as_ref(),
+        intent.vault.as_ref(),
+        &intent.intent_id.to_le_bytes(),
+        &[intent.bump],
+    ]];
+    // tokens sent to the escrow outside `tip_intent` go with the tip, so
+    // the account is always empty and can close
+    let amount = token::accessor::amount(escrow)?;
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            Transfer {
+                from: escrow.clone(),
+                to: destination.clone(),
+                authority: intent.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+    token::close_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        CloseAccount {
+            account: escrow.clone(),
+            destination: owner.clone(),
+            authority: intent.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+    Ok(amount)
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ShadowError;
+use crate::state::{IntentKind, PriceFeed, ProtocolConfig, TradeIntent, VaultAccount};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TriggerIntent<'info> {
+    /// Any keeper; the feed, not the signer, decides whether it fires
+    pub keeper: Signer<'info>,
+
+    #[account(
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        seeds = [
+            TradeIntent::SEED_PREFIX,
+            intent.user.as_ref(),
+            vault.key().as_ref(),
+            intent.intent_id.to_le_bytes().as_ref(),
+        ],
+        bump = intent.bump,
+    )]
+    pub intent: Account<'info, TradeIntent>,
+
+    #[account(
+        seeds = [PriceFeed::SEED_PREFIX, intent.token_in.as_ref(), intent.token_out.as_ref()],
+        bump = price_feed.bump,
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+}
+
+pub fn handler(ctx: Context<TriggerIntent>) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ShadowError::ProtocolPaused);
+    let now = Clock::get()?.unix_timestamp;
+    let intent = &mut ctx.accounts.intent;
+    require!(intent.kind == IntentKind::Conditional, ShadowError::WrongIntentKind);
+    require!(!intent.cancelled, ShadowError::IntentCancelled);
+    require!(!intent.is_filled(), ShadowError::IntentFilled);
+    require!(!intent.is_expired(now), ShadowError::IntentExpired);
+    require!(intent.triggered.is_none(), ShadowError::AlreadyTriggered);
+
+    let feed = &ctx.accounts.price_feed;
+    require!(feed.is_fresh(now), ShadowError::StalePrice);
+    let leg = intent.trigger_leg(feed.price).ok_or(ShadowError::TriggerNotMet)?;
+    // the other leg of an OCO pair can no longer fire
+    intent.triggered = Some(leg);
+    intent.next_execution_ts = now;
+
+    let seq = ctx.accounts.vault.next_event_seq()?;
+    emit_cpi!(crate::IntentTriggered {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: ctx.accounts.vault.key(),
+        intent: ctx.accounts.intent.key(),
+        keeper: ctx.accounts.keeper.key(),
+        leg,
+        price: feed.price,
+        timestamp: now,
+    });
+    Ok(())
+}
@@ -0,0 +1,227 @@
+//! Read-only views. Each handler returns its result as an Anchor return
+//! value (`set_return_data`), so clients simulate the instruction and read
+//! the program's own math instead of re-implementing it. Nothing is written.
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::errors::ShadowError;
+use crate::state::{CustodyLedger, PauseFlags, ProtocolConfig, UserPosition, VaultAccount, WithdrawalRateLimit};
+use crate::{DepositPreview, MintSummary, PositionView, VaultSummary, WithdrawPreview};
+
+/// Ledgers one `get_vault_summary` can cover within the 1 KiB return data cap
+pub const MAX_SUMMARY_MINTS: usize = 16;
+
+#[derive(Accounts)]
+pub struct GetVaultSummary<'info> {
+    #[account(
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+    // remaining_accounts: CustodyLedger accounts of this vault to summarize
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct GetPosition<'info> {
+    #[account(
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: ledger PDA; absent until the mint's first deposit
+    #[account(
+        seeds = [CustodyLedger::SEED_PREFIX, vault.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub ledger: UncheckedAccount<'info>,
+
+    /// CHECK: `owner`'s position PDA; absent until credited
+    #[account(
+        seeds = [UserPosition::SEED_PREFIX, vault.key().as_ref(), token_mint.key().as_ref(), owner.as_ref()],
+        bump,
+    )]
+    pub position: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewDeposit<'info> {
+    #[account(
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: ledger PDA; absent until the mint's first deposit
+    #[account(
+        seeds = [CustodyLedger::SEED_PREFIX, vault.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub ledger: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewWithdraw<'info> {
+    #[account(
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: ledger PDA; absent until the mint's first deposit
+    #[account(
+        seeds = [CustodyLedger::SEED_PREFIX, vault.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub ledger: UncheckedAccount<'info>,
+
+    /// CHECK: custody ATA; absent until the mint's first deposit
+    #[account(address = get_associated_token_address(&vault.key(), &token_mint.key()) @ ShadowError::InvalidAccount)]
+    pub vault_ata: UncheckedAccount<'info>,
+
+    /// CHECK: rate limit PDA; absent until the first withdrawal or limit
+    #[account(
+        seeds = [WithdrawalRateLimit::SEED_PREFIX, vault.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub rate_limit: UncheckedAccount<'info>,
+}
+
+pub fn get_vault_summary<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetVaultSummary<'info>>,
+) -> Result<VaultSummary> {
+    require!(
+        ctx.remaining_accounts.len() <= MAX_SUMMARY_MINTS,
+        ShadowError::ViewTooLarge
+    );
+
+    let vault = &ctx.accounts.vault;
+    let mut mints = Vec::with_capacity(ctx.remaining_accounts.len());
+    for info in ctx.remaining_accounts {
+        let ledger: Account<'info, CustodyLedger> = Account::try_from(info)?;
+        require_keys_eq!(ledger.vault, vault.key(), ShadowError::InvalidAccount);
+        mints.push(MintSummary {
+            mint: ledger.mint,
+            balance: ledger.balance,
+            total_shares: ledger.total_shares,
+            price_per_share: ledger.price_per_share()?,
+        });
+    }
+
+    Ok(VaultSummary {
+        tvl: vault.tvl,
+        custody_mint_count: vault.custody_mint_count,
+        execution_count: vault.execution_count,
+        pause_flags: vault.pause_flags,
+        protocol_paused: ctx.accounts.config.paused,
+        breaker_headroom: vault.breaker_headroom(Clock::get()?.unix_timestamp),
+        mints,
+    })
+}
+
+pub fn get_position(ctx: Context<GetPosition>, owner: Pubkey) -> Result<PositionView> {
+    let ledger = load_ledger(&ctx.accounts.ledger, &ctx.accounts.vault, &ctx.accounts.token_mint)?;
+    let shares = load_optional::<UserPosition>(&ctx.accounts.position)?.map_or(0, |p| p.shares);
+
+    Ok(PositionView {
+        owner,
+        shares,
+        assets: ledger.assets_for_shares(shares)?,
+        ledger_balance: ledger.balance,
+        total_shares: ledger.total_shares,
+        price_per_share: ledger.price_per_share()?,
+    })
+}
+
+pub fn preview_deposit(ctx: Context<PreviewDeposit>, amount: u64) -> Result<DepositPreview> {
+    let vault = &ctx.accounts.vault;
+    let ledger = load_ledger(&ctx.accounts.ledger, vault, &ctx.accounts.token_mint)?;
+
+    Ok(DepositPreview {
+        shares: ledger.shares_for_deposit(amount)?,
+        price_per_share: ledger.price_per_share()?,
+        open: !ctx.accounts.config.paused && !vault.is_paused(PauseFlags::DEPOSITS),
+        allowlist_enabled: vault.allowlist_enabled,
+    })
+}
+
+pub fn preview_withdraw(ctx: Context<PreviewWithdraw>, amount: u64) -> Result<WithdrawPreview> {
+    let vault = &ctx.accounts.vault;
+    let ledger = load_ledger(&ctx.accounts.ledger, vault, &ctx.accounts.token_mint)?;
+    let custody = load_optional::<TokenAccount>(&ctx.accounts.vault_ata)?.map_or(0, |a| a.amount);
+    let clock = Clock::get()?;
+
+    // roll a copy the way `withdraw` would, so a new epoch's limits apply
+    let rate_limit_remaining = match load_optional::<WithdrawalRateLimit>(&ctx.accounts.rate_limit)? {
+        Some(mut limit) => {
+            limit.roll_epoch(clock.epoch, ledger.balance);
+            limit.remaining()
+        }
+        None => u64::MAX,
+    };
+    let breaker_headroom = vault.breaker_headroom(clock.unix_timestamp);
+    // `withdraw` moves nothing past the breaker's headroom
+    let withdrawable = ledger
+        .balance
+        .min(custody)
+        .min(rate_limit_remaining)
+        .min(breaker_headroom);
+    let trips_breaker = amount > breaker_headroom;
+    let open = !ctx.accounts.config.paused && !vault.is_paused(PauseFlags::WITHDRAWALS);
+
+    Ok(WithdrawPreview {
+        withdrawable,
+        rate_limit_remaining,
+        breaker_headroom,
+        trips_breaker,
+        open,
+        allowed: open && !trips_breaker && amount > 0 && amount <= withdrawable,
+    })
+}
+
+/// Deserializes `info` if it holds an initialized `T`, `None` if it was never created
+pub(crate) fn load_optional<T: AccountDeserialize + Owner>(info: &AccountInfo) -> Result<Option<T>> {
+    if info.owner != &T::owner() || info.data_is_empty() {
+        return Ok(None);
+    }
+    T::try_deserialize(&mut &info.data.borrow()[..]).map(Some)
+}
+
+/// The mint's ledger, or an empty one before its first deposit
+fn load_ledger(info: &AccountInfo, vault: &Account<VaultAccount>, mint: &Account<Mint>) -> Result<CustodyLedger> {
+    Ok(load_optional(info)?.unwrap_or(CustodyLedger {
+        vault: vault.key(),
+        mint: mint.key(),
+        balance: 0,
+        total_shares: 0,
+        unallocated: 0,
+        bump: 0,
+    }))
+}
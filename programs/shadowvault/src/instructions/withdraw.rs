@@ -3,18 +3,27 @@ use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 
 use crate::errors::ShadowError;
-use crate::state::VaultAccount;
+use crate::state::{CustodyLedger, PauseFlags, ProtocolConfig, VaultAccount, WithdrawalRateLimit};
+use crate::WithdrawOutcome;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
     /// Vault owner or delegated authority
+    #[account(mut)]
     pub authority: Signer<'info>,
 
+    #[account(
+        seeds = [ProtocolConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
     pub token_mint: Account<'info, Mint>,
 
     #[account(
         mut,
-        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref()],
+        seeds = [VaultAccount::SEED_PREFIX, vault.owner.as_ref(), vault.vault_id.to_le_bytes().as_ref()],
         bump = vault.bump,
         has_one = owner @ ShadowError::Unauthorized,
     )]
@@ -41,22 +50,65 @@ pub struct Withdraw<'info> {
     )]
     pub recipient_ata: Account<'info, TokenAccount>,
 
+    /// Per-epoch outflow cap for this mint; created unlimited on first use
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [WithdrawalRateLimit::SEED_PREFIX, vault.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        space = WithdrawalRateLimit::space(),
+    )]
+    pub rate_limit: Account<'info, WithdrawalRateLimit>,
+
+    #[account(
+        mut,
+        seeds = [CustodyLedger::SEED_PREFIX, vault.key().as_ref(), token_mint.key().as_ref()],
+        bump = ledger.bump,
+    )]
+    pub ledger: Account<'info, CustodyLedger>,
+
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<WithdrawOutcome> {
+    require!(!ctx.accounts.config.paused, ShadowError::ProtocolPaused);
     require!(amount > 0, ShadowError::InvalidAmount);
+    require!(!ctx.accounts.vault.is_paused(PauseFlags::WITHDRAWALS), ShadowError::VaultPaused);
 
     // Only owner can withdraw by default
     require_keys_eq!(ctx.accounts.vault.owner, ctx.accounts.authority.key(), ShadowError::Unauthorized);
 
+    // An outflow past the breaker's headroom pauses the vault instead of
+    // moving funds; an error would roll the pause back, so the outcome says
+    // nothing moved
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &mut ctx.accounts.vault;
+    if let Some(window_outflow) = vault.record_outflow(amount, now)? {
+        emit_breaker_tripped(vault, window_outflow, now)?;
+        return Ok(WithdrawOutcome {
+            withdrawn: 0,
+            breaker_tripped: true,
+        });
+    }
+
     // Derive signer seeds for PDA authority if needed for outbound transfer
-    let seeds: &[&[u8]] = &[VaultAccount::SEED_PREFIX, ctx.accounts.vault.owner.as_ref()];
+    let vault_id = ctx.accounts.vault.vault_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[VaultAccount::SEED_PREFIX, ctx.accounts.vault.owner.as_ref(), &vault_id];
     let (_pda, bump) = Pubkey::find_program_address(seeds, ctx.program_id);
     require_eq!(bump, ctx.accounts.vault.bump, ShadowError::InvalidAccount);
-    let signer_seeds: &[&[&[u8]]] = &[&[VaultAccount::SEED_PREFIX, ctx.accounts.vault.owner.as_ref(), &[bump]]];
+    let signer_seeds: &[&[&[u8]]] = &[&[VaultAccount::SEED_PREFIX, ctx.accounts.vault.owner.as_ref(), &vault_id, &[bump]]];
+
+    // Enforce the per-epoch rate limit before any funds move
+    let rate_limit = &mut ctx.accounts.rate_limit;
+    if rate_limit.vault == Pubkey::default() {
+        rate_limit.vault = ctx.accounts.vault.key();
+        rate_limit.mint = ctx.accounts.token_mint.key();
+        rate_limit.bump = ctx.bumps.rate_limit;
+    }
+    rate_limit.roll_epoch(Clock::get()?.epoch, ctx.accounts.ledger.balance);
+    rate_limit.consume(amount)?;
 
     // Transfer from vault custody -> recipient
     let cpi_accounts = Transfer {
@@ -77,5 +129,41 @@ pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         .checked_sub(amount)
         .ok_or(ShadowError::MathOverflow)?;
 
+    let ledger = &mut ctx.accounts.ledger;
+    ledger.balance = ledger
+        .balance
+        .checked_sub(amount)
+        .ok_or(ShadowError::MathOverflow)?;
+
+    let seq = vault.next_event_seq()?;
+    emit_cpi!(crate::Withdrawn {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: vault.key(),
+        authority: ctx.accounts.authority.key(),
+        amount,
+        new_tvl: vault.tvl,
+        timestamp: now,
+    });
+
+    Ok(WithdrawOutcome {
+        withdrawn: amount,
+        breaker_tripped: false,
+    })
+}
+
+/// Reports a trip `record_outflow` just applied
+pub(crate) fn emit_breaker_tripped(vault: &mut Account<VaultAccount>, window_outflow: u64, now: i64) -> Result<()> {
+    let seq = vault.next_event_seq()?;
+    emit!(crate::CircuitBreakerTripped {
+        version: crate::EVENT_SCHEMA_VERSION,
+        seq,
+        vault: vault.key(),
+        window_outflow,
+        tvl: vault.tvl,
+        threshold_bps: vault.circuit_breaker.threshold_bps,
+        pause_flags: vault.pause_flags,
+        timestamp: now,
+    });
     Ok(())
 }
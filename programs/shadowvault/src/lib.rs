@@ -6,15 +6,23 @@
 // - Encrypted strategy commitments (zero-knowledge proofs ready)
 // - Intent-based trading with MEV protection
 // - PDA-based custody for secure asset management
-// - Emergency pause mechanisms for risk mitigation
+// - Granular emergency pause flags for risk mitigation
 //
 // Architecture:
-// - VaultAccount: Main vault state with encrypted strategy hash
-// - TradeIntent: User-submitted trade requests with privacy guarantees
-// - ExecutionResult: On-chain execution records for transparency
+// - ProtocolConfig: Global admin, fees, emergency pause and executor set
+// - VaultAccount: Main vault state with encrypted strategy hash; one owner
+//   may hold many, indexed by OwnerVaultCounter
+// - TradeIntent: User-submitted trade requests with privacy guarantees;
+//   market, TWAP, limit or stop-loss/take-profit, open until filled,
+//   cancelled or expired; may escrow a keeper tip for the settling executor
+// - ExecutionResult: On-chain execution records for transparency; TWAP
+//   intents record one TwapFill per slice instead
+// - ExecutionHistory: Zero-copy ring of a vault's most recent executions
+// - Views: Read-only instructions returning summaries and previews as
+//   return data for simulation
 //
 // Security Features:
-// - Owner-only withdrawal and pause controls
+// - Owner-only withdrawal; owner/guardian granular pause controls
 // - Reentrancy protection via Anchor's account validation
 // - Overflow-safe arithmetic with checked operations
 // - PDA-based authority for secure token custody
@@ -28,6 +36,8 @@ use anchor_lang::prelude::*;
 pub mod state;
 pub mod instructions;
 pub mod errors;
+pub mod merkle;
+pub mod event_decoder;
 
 use crate::instructions::*;
 use crate::state::*;
@@ -38,7 +48,7 @@ use crate::state::*;
 // IMPORTANT: Replace with your deployed program ID when deploying to devnet/mainnet
 // This must match the program ID in Anchor.toml
 // Generate new ID with: anchor keys list
-declare_id!("ShdwVlt1111111111111111111111111111111111111");
+declare_id!("ShdwVau1t1111111111111111111111111111111111");
 
 // ============================================================================
 // Program Instructions
@@ -54,62 +64,70 @@ pub mod shadowvault {
     ///   This hash commits to the strategy without revealing it on-chain
     /// 
     /// # Security
-    /// - Creates PDA-based vault account owned by the signer
+    /// - Creates PDA-based vault account owned by the signer, seeded by
+    ///   owner and the next id from the owner's `OwnerVaultCounter`, so one
+    ///   owner can run many vaults
     /// - Only the vault owner can pause or withdraw funds
     /// - Strategy hash enables future ZK proof verification
     /// 
     /// # Events
-    /// Emits `VaultInitialized` with vault address and owner
+    /// Emits `VaultInitialized` with vault address and owner (event CPI, see `event_decoder`)
     pub fn initialize_vault(
         ctx: Context<InitializeVault>,
         encrypted_strategy_hash: [u8; 32],
     ) -> Result<()> {
-        let vault_key = ctx.accounts.vault.key();
-        let owner_key = ctx.accounts.owner.key();
-        
-        initialize::handler(ctx, encrypted_strategy_hash)?;
-        
-        // Emit initialization event
-        emit!(VaultInitialized {
-            vault: vault_key,
-            owner: owner_key,
-            encrypted_strategy_hash,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-        
-        Ok(())
+        initialize::handler(ctx, encrypted_strategy_hash)
     }
 
     /// Deposit tokens into the vault
     /// 
     /// # Arguments
     /// * `amount` - Amount of tokens to deposit (must be > 0)
+    /// * `proof` - Merkle proof of the depositor in the vault allowlist
+    ///   (ignored when the allowlist is off)
     /// 
     /// # Security
-    /// - Vault must not be paused
+    /// - Deposits must not be paused
+    /// - Permissioned vaults only accept allowlisted depositors
     /// - Uses SPL token transfer with proper authority checks
     /// - Updates TVL with overflow protection
     /// - Tokens held in vault PDA's associated token account
+    /// - Shares priced from the tracked ledger balance with virtual offsets,
+    ///   so donations to custody cannot inflate price-per-share
     /// 
     /// # Events
-    /// Emits `Deposited` with user, amount, and new TVL
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-        let vault_key = ctx.accounts.vault.key();
-        let user_key = ctx.accounts.user.key();
-        let old_tvl = ctx.accounts.vault.tvl;
-        
-        deposit::handler(ctx, amount)?;
-        
-        // Emit deposit event
-        emit!(Deposited {
-            vault: vault_key,
-            user: user_key,
-            amount,
-            new_tvl: old_tvl + amount,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-        
-        Ok(())
+    /// Emits `Deposited` with user, amount, shares minted, and new TVL (event CPI, see `event_decoder`)
+    pub fn deposit(ctx: Context<Deposit>, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+        deposit::handler(ctx, amount, proof)
+    }
+
+    /// Deposit tokens into the vault on behalf of another account
+    /// 
+    /// # Arguments
+    /// * `amount` - Amount of tokens to deposit (must be > 0)
+    /// * `beneficiary` - Account credited with the minted shares
+    /// * `proof` - Merkle proof of the beneficiary in the vault allowlist
+    ///   (ignored when the allowlist is off)
+    /// 
+    /// # Security
+    /// - Deposits must not be paused
+    /// - Permissioned vaults only accept allowlisted beneficiaries
+    /// - Payer signs the transfer; beneficiary need not sign
+    /// - Share pricing identical to `deposit`
+    /// 
+    /// # Use Cases
+    /// - Custodians and payroll funding client positions
+    /// - Integrators depositing for their users
+    /// 
+    /// # Events
+    /// Emits `Deposited` with both payer (`user`) and beneficiary (event CPI, see `event_decoder`)
+    pub fn deposit_for(
+        ctx: Context<DepositFor>,
+        amount: u64,
+        beneficiary: Pubkey,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        deposit_for::handler(ctx, amount, beneficiary, proof)
     }
 
     /// Submit a trade intent for private execution
@@ -119,7 +137,15 @@ pub mod shadowvault {
     /// * `token_out` - Output token mint address
     /// * `amount` - Amount to trade
     /// * `max_slippage_bps` - Maximum slippage in basis points (e.g., 100 = 1%)
-    /// * `strategy_type` - Strategy identifier (0-255)
+    /// * `strategy_type` - Strategy identifier; must have an enabled `StrategyConfig`
+    /// 
+    /// # Security
+    /// - Intents must not be paused
+    /// - `token_in -> token_out` must be an enabled `TradePair` for the vault,
+    ///   and the intent must fit that pair's size and slippage limits
+    /// - The strategy must allow the pair and the intent must fit its limits
+    /// - Takes the user's next intent id on the vault, so earlier intents
+    ///   keep their accounts
     /// 
     /// # Privacy Model
     /// - Intent is public but execution routing is private
@@ -127,7 +153,7 @@ pub mod shadowvault {
     /// - On-chain verification ensures slippage bounds are respected
     /// 
     /// # Events
-    /// Emits `TradeIntentSubmitted` with intent details
+    /// Emits `TradeIntentSubmitted` with intent details (event CPI, see `event_decoder`)
     pub fn submit_trade_intent(
         ctx: Context<SubmitTradeIntent>,
         token_in: Pubkey,
@@ -146,13 +172,202 @@ pub mod shadowvault {
         )
     }
 
+    /// Submit a TWAP/DCA intent that is worked in slices over time
+    /// 
+    /// # Arguments
+    /// * `token_in`, `token_out`, `amount`, `max_slippage_bps`, `strategy_type` -
+    ///   as in `submit_trade_intent`
+    /// * `slices` - Number of slices `amount` is split into (2-100)
+    /// * `interval_secs` - Minimum seconds between slices
+    /// 
+    /// # Security
+    /// - Same checks as `submit_trade_intent`, with pair and strategy size
+    ///   limits applied to the largest slice rather than the total
+    /// 
+    /// # Events
+    /// Emits `TradeIntentSubmitted` with intent details (event CPI, see `event_decoder`)
+    #[allow(clippy::too_many_arguments)]
+    pub fn submit_twap_intent(
+        ctx: Context<SubmitTradeIntent>,
+        token_in: Pubkey,
+        token_out: Pubkey,
+        amount: u64,
+        max_slippage_bps: u16,
+        strategy_type: u8,
+        slices: u16,
+        interval_secs: i64,
+    ) -> Result<()> {
+        submit_trade_intent::twap_handler(
+            ctx,
+            token_in,
+            token_out,
+            amount,
+            max_slippage_bps,
+            strategy_type,
+            slices,
+            interval_secs,
+        )
+    }
+
+    /// Submit a limit order that fills only at `limit_price` or better
+    /// 
+    /// # Arguments
+    /// * `token_in`, `token_out`, `amount`, `max_slippage_bps`, `strategy_type` -
+    ///   as in `submit_trade_intent`
+    /// * `limit_price` - Least token_out per token_in, scaled by `PRICE_SCALE`
+    /// * `expires_at` - Unix time after which the order stops filling (0 = never)
+    /// 
+    /// # Security
+    /// - Same checks as `submit_trade_intent`
+    /// - May be filled in parts; stays open until filled, cancelled or expired
+    /// 
+    /// # Events
+    /// Emits `TradeIntentSubmitted` with intent details (event CPI, see `event_decoder`)
+    #[allow(clippy::too_many_arguments)]
+    pub fn submit_limit_intent(
+        ctx: Context<SubmitTradeIntent>,
+        token_in: Pubkey,
+        token_out: Pubkey,
+        amount: u64,
+        max_slippage_bps: u16,
+        strategy_type: u8,
+        limit_price: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        submit_trade_intent::limit_handler(
+            ctx,
+            token_in,
+            token_out,
+            amount,
+            max_slippage_bps,
+            strategy_type,
+            limit_price,
+            expires_at,
+        )
+    }
+
+    /// Submit a stop-loss and/or take-profit intent
+    /// 
+    /// # Arguments
+    /// * `token_in`, `token_out`, `amount`, `max_slippage_bps`, `strategy_type` -
+    ///   as in `submit_trade_intent`
+    /// * `stop_loss_price` - Trigger when the pair's feed price falls to or
+    ///   below this, scaled by `PRICE_SCALE` (0 = no stop-loss)
+    /// * `take_profit_price` - Trigger when it rises to or above this (0 = none)
+    /// * `expires_at` - Unix time after which the intent stops filling (0 = never)
+    /// 
+    /// # Security
+    /// - Same checks as `submit_trade_intent`
+    /// - With both prices set the intent is an OCO pair: the first leg to
+    ///   trigger fills the intent and the other can no longer fire
+    /// 
+    /// # Events
+    /// Emits `TradeIntentSubmitted` with intent details (event CPI, see `event_decoder`)
+    #[allow(clippy::too_many_arguments)]
+    pub fn submit_conditional_intent(
+        ctx: Context<SubmitTradeIntent>,
+        token_in: Pubkey,
+        token_out: Pubkey,
+        amount: u64,
+        max_slippage_bps: u16,
+        strategy_type: u8,
+        stop_loss_price: u64,
+        take_profit_price: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        submit_trade_intent::conditional_handler(
+            ctx,
+            token_in,
+            token_out,
+            amount,
+            max_slippage_bps,
+            strategy_type,
+            stop_loss_price,
+            take_profit_price,
+            expires_at,
+        )
+    }
+
+    /// Arm a conditional intent whose trigger the pair's price feed has crossed
+    /// 
+    /// # Security
+    /// - Permissionless: any keeper may call; the program reads the
+    ///   `PriceFeed` and fails unless a trigger is met
+    /// - The feed must be at most `PriceFeed::MAX_AGE_SECS` old
+    /// - Triggers once; execution still goes through `execute_trade`
+    /// 
+    /// # Events
+    /// Emits `IntentTriggered` with the leg and feed price (event CPI, see `event_decoder`)
+    pub fn trigger_intent(ctx: Context<TriggerIntent>) -> Result<()> {
+        trigger_intent::handler(ctx)
+    }
+
+    /// Add a lamport keeper tip to one of the signer's open intents
+    /// 
+    /// # Security
+    /// - Lamports move onto the intent account, above its rent
+    /// - Paid to the executor whose fill completes the intent; refunded by
+    ///   `cancel_intent`, which also serves expired intents
+    /// - An intent's tip stays in one denomination
+    /// 
+    /// # Events
+    /// Emits `IntentTipped` (event CPI, see `event_decoder`)
+    pub fn tip_intent_lamports(ctx: Context<TipIntentLamports>, amount: u64) -> Result<()> {
+        tip_intent::lamports_handler(ctx, amount)
+    }
+
+    /// Add a token keeper tip to one of the signer's open intents
+    /// 
+    /// # Security
+    /// - Tokens move from the user's ATA into the intent's ATA for the mint
+    /// - Paid out and refunded like `tip_intent_lamports`
+    /// 
+    /// # Events
+    /// Emits `IntentTipped` (event CPI, see `event_decoder`)
+    pub fn tip_intent_tokens(ctx: Context<TipIntentTokens>, amount: u64) -> Result<()> {
+        tip_intent::tokens_handler(ctx, amount)
+    }
+
+    /// Cancel one of the signer's open intents on a vault
+    /// 
+    /// # Security
+    /// - Only the intent's user can cancel
+    /// - Not gated by pauses, so users can always pull an order
+    /// - Partial fills already executed stand
+    /// - Refunds any keeper tip; cancelling is also how expired intents
+    ///   return theirs
+    /// 
+    /// # Remaining Accounts
+    /// For a token tip: the intent's ATA and the user's ATA for the tip mint
+    /// 
+    /// # Events
+    /// Emits `IntentCancelled` with the unfilled amount (event CPI, see `event_decoder`)
+    pub fn cancel_intent<'info>(ctx: Context<'_, '_, 'info, 'info, CancelIntent<'info>>) -> Result<()> {
+        cancel_intent::handler(ctx)
+    }
+
     /// Execute a submitted trade intent
     /// 
     /// # Security
-    /// - Only authorized executor can call (MPC network in production)
-    /// - Vault must not be paused
+    /// - Only executors registered in `ProtocolConfig` can call (MPC network
+    ///   in production)
+    /// - Executions must not be paused
+    /// - Re-checks the intent against its strategy and enforces the
+    ///   strategy's cooldown
     /// - Validates slippage against intent parameters
-    /// - Creates immutable execution result record
+    /// - Market intents fill in full; limit intents may fill `fill_amount`
+    ///   of what remains, only at their limit price or better; conditional
+    ///   intents fill in full once triggered
+    /// - Limit fills are priced at the pair's `PriceFeed`, which must be
+    ///   passed and at most `PriceFeed::MAX_AGE_SECS` old
+    /// - While the vault's circuit breaker is enabled the feed is required
+    ///   too: the fill's shortfall against it, in token_in, counts as
+    ///   outflow, and one past the headroom pauses the vault and leaves the
+    ///   intent unfilled
+    /// - The intent must not be cancelled or expired
+    /// - Accumulates fills in the intent's execution result record
+    /// - The fill that completes the intent pays its keeper tip to the
+    ///   executor and records it in the result
     /// 
     /// # Production Notes
     /// Current implementation is a placeholder for hackathon demo.
@@ -161,10 +376,40 @@ pub mod shadowvault {
     /// - Execute actual swaps via CPI to DEX aggregators (Jupiter/Raydium)
     /// - Enforce slippage protection from signed intent
     /// 
+    /// # Remaining Accounts
+    /// For a token tip: the intent's ATA and the executor's ATA for the tip mint
+    /// 
+    /// # Events
+    /// Emits `TradeExecuted` with execution results (event CPI, see `event_decoder`),
+    /// or `CircuitBreakerTripped` alone when the breaker refuses the fill
+    pub fn execute_trade<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteTrade<'info>>,
+        encrypted_params: [u8; 32],
+        mpc_proof: [u8; 64],
+        computation_id: [u8; 32],
+        fill_amount: u64,
+    ) -> Result<()> {
+        execute_trade::handler(ctx, encrypted_params, mpc_proof, computation_id, fill_amount)
+    }
+
+    /// Execute the next slice of a TWAP intent (crank)
+    /// 
+    /// # Security
+    /// - Same executor, pause and strategy checks as `execute_trade`
+    /// - The slice must be due (`next_execution_ts`); the last slice takes
+    ///   the rounding remainder
+    /// - Creates one `TwapFill` record per slice
+    /// - The last slice pays the keeper tip, with the same remaining
+    ///   accounts as `execute_trade`
+    /// 
     /// # Events
-    /// Emits `TradeExecuted` with execution results
-    pub fn execute_trade(ctx: Context<ExecuteTrade>) -> Result<()> {
-        execute_trade::handler(ctx)
+    /// Emits `TradeExecuted` for the slice (event CPI, see `event_decoder`)
+    pub fn execute_twap_slice<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteTwapSlice<'info>>,
+        encrypted_params: [u8; 32],
+        mpc_proof: [u8; 64],
+    ) -> Result<()> {
+        execute_twap_slice::handler(ctx, encrypted_params, mpc_proof)
     }
 
     /// Withdraw tokens from the vault
@@ -174,40 +419,36 @@ pub mod shadowvault {
     /// 
     /// # Security
     /// - Only vault owner can withdraw
+    /// - Withdrawals must not be paused
+    /// - Per-mint, per-epoch rate limit must not be exceeded
+    /// - An amount above the circuit breaker's headroom trips it: the vault
+    ///   pauses and no funds move
     /// - Uses PDA signer seeds for secure token transfer
     /// - Updates TVL with underflow protection
     /// - Requires sufficient balance in vault custody
     /// 
+    /// # Returns
+    /// `WithdrawOutcome` via return data. A trip still succeeds, so the pause
+    /// sticks, and reports `breaker_tripped` with nothing withdrawn
+    /// 
     /// # Events
-    /// Emits `Withdrawn` with withdrawal details
-    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
-        let vault_key = ctx.accounts.vault.key();
-        let authority_key = ctx.accounts.authority.key();
-        let old_tvl = ctx.accounts.vault.tvl;
-        
-        withdraw::handler(ctx, amount)?;
-        
-        // Emit withdrawal event
-        emit!(Withdrawn {
-            vault: vault_key,
-            authority: authority_key,
-            amount,
-            new_tvl: old_tvl.saturating_sub(amount),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-        
-        Ok(())
+    /// Emits `Withdrawn` with withdrawal details (event CPI, see `event_decoder`),
+    /// or `CircuitBreakerTripped` alone when the breaker refuses the amount
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<WithdrawOutcome> {
+        withdraw::handler(ctx, amount)
     }
 
-    /// Pause or unpause the vault (emergency control)
+    /// Set or clear pause flags on the vault (emergency control)
     /// 
     /// # Arguments
-    /// * `pause` - true to pause, false to unpause
+    /// * `flags` - Bitset of `PauseFlags` to update (deposits, intents,
+    ///   executions, withdrawals)
+    /// * `pause` - true to set the given flags, false to clear them
     /// 
     /// # Security
-    /// - Only vault owner can pause/unpause
-    /// - When paused, deposits and trades are blocked
-    /// - Withdrawals remain available to owner even when paused
+    /// - Only vault owner or guardian can change pause flags
+    /// - Each handler checks only the flag for its own operation, so e.g.
+    ///   trading can be halted while deposits keep flowing
     /// 
     /// # Use Cases
     /// - Emergency response to detected exploits
@@ -215,22 +456,318 @@ pub mod shadowvault {
     /// - Risk mitigation during market volatility
     /// 
     /// # Events
-    /// Emits `VaultPaused` with pause state
-    pub fn pause_vault(ctx: Context<TogglePause>, pause: bool) -> Result<()> {
-        let vault_key = ctx.accounts.vault.key();
-        let authority_key = ctx.accounts.authority.key();
-        
-        pause_vault::handler(ctx, pause)?;
-        
-        // Emit pause event
-        emit!(VaultPaused {
-            vault: vault_key,
-            authority: authority_key,
-            is_paused: pause,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-        
-        Ok(())
+    /// Emits `VaultPaused` with the full resulting flag set (event CPI, see `event_decoder`)
+    pub fn pause_vault(ctx: Context<TogglePause>, flags: u8, pause: bool) -> Result<()> {
+        pause_vault::handler(ctx, flags, pause)
+    }
+
+    /// Assign the vault guardian
+    /// 
+    /// # Arguments
+    /// * `guardian` - Key allowed to set and clear pause flags alongside the owner
+    /// 
+    /// # Security
+    /// - Only vault owner can assign the guardian
+    /// - Guardian cannot withdraw or otherwise move funds
+    /// 
+    /// # Events
+    /// Emits `GuardianUpdated` with old and new guardian
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        set_guardian::handler(ctx, guardian)
+    }
+
+    /// Configure the automatic circuit breaker on abnormal outflows
+    /// 
+    /// # Arguments
+    /// * `window_secs` - Length of the rolling outflow window (0 disables)
+    /// * `threshold_bps` - Outflow within one window, in basis points of
+    ///   vault value, that trips the breaker
+    /// 
+    /// # Security
+    /// - Only vault owner can configure the breaker
+    /// - Withdrawals count as outflow, checked before funds move; trades do not,
+    ///   as their legs are in different mints
+    /// - On trip every pause flag is set; the owner or guardian unpauses
+    /// 
+    /// # Events
+    /// Emits `CircuitBreakerConfigured`; later trips emit `CircuitBreakerTripped`
+    pub fn configure_circuit_breaker(
+        ctx: Context<ConfigureCircuitBreaker>,
+        window_secs: i64,
+        threshold_bps: u16,
+    ) -> Result<()> {
+        configure_circuit_breaker::handler(ctx, window_secs, threshold_bps)
+    }
+
+    /// Cap withdrawals of one mint per Solana epoch
+    /// 
+    /// # Arguments
+    /// * `max_amount` - Absolute cap on outflow per epoch (0 = unlimited)
+    /// * `max_bps` - Cap as basis points of the custody ledger balance at
+    ///   epoch start (0 = unlimited); donations to the ATA do not count
+    /// 
+    /// # Security
+    /// - Only vault owner can set limits
+    /// - Tighter limits apply immediately; looser limits apply from the next
+    ///   epoch, bounding what a leaked owner key can drain
+    /// - Counters reset lazily on the first withdrawal of each epoch
+    /// 
+    /// # Events
+    /// Emits `WithdrawalLimitUpdated` with the limits and when they apply
+    pub fn set_withdrawal_limit(
+        ctx: Context<SetWithdrawalLimit>,
+        max_amount: u64,
+        max_bps: u16,
+    ) -> Result<()> {
+        set_withdrawal_limit::handler(ctx, max_amount, max_bps)
+    }
+
+    /// Compare the vault's books against its custody token accounts
+    /// 
+    /// # Arguments
+    /// * `resync` - If true, write shortfalls down to the custody balance and
+    ///   record surpluses as `unallocated`, then set TVL to the booked total
+    ///   (owner only). Surpluses only reach `balance` through
+    ///   `allocate_surplus`, so a donation cannot inflate share prices
+    /// 
+    /// # Remaining Accounts
+    /// One (CustodyLedger, custody ATA) pair per mint, ascending by mint,
+    /// covering every ledger the vault has opened
+    /// 
+    /// # Security
+    /// - Permissionless when `resync` is false
+    /// - Ledger PDAs and canonical vault ATAs are re-derived and checked
+    /// - Pair count must equal `custody_mint_count`, so no mint can be hidden
+    /// 
+    /// # Events
+    /// Emits `ReconciliationReport` with per-mint deltas
+    pub fn reconcile<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Reconcile<'info>>,
+        resync: bool,
+    ) -> Result<()> {
+        reconcile::handler(ctx, resync)
+    }
+
+    /// Fold one mint's `unallocated` surplus into its booked balance
+    /// 
+    /// Raises the mint's share price for every holder, so a donation only
+    /// reaches share pricing once the owner accepts it. The amount is capped
+    /// at the custody ATA's current surplus over the ledger balance
+    /// 
+    /// # Security
+    /// - Only vault owner can allocate
+    /// - Fails with `InvalidAmount` when there is nothing to allocate
+    /// 
+    /// # Events
+    /// Emits `SurplusAllocated` with the amount and new ledger balance
+    pub fn allocate_surplus(ctx: Context<AllocateSurplus>) -> Result<()> {
+        allocate_surplus::handler(ctx)
+    }
+
+    /// Configure the depositor allowlist for a permissioned vault
+    /// 
+    /// # Arguments
+    /// * `root` - Merkle root over keccak256(pubkey) leaves, sorted-pair hashed
+    /// * `enabled` - true to require a proof on every deposit
+    /// 
+    /// # Security
+    /// - Only vault owner can change the allowlist
+    /// - Existing positions are unaffected; only new deposits are gated
+    /// 
+    /// # Events
+    /// Emits `AllowlistUpdated` with the new root and mode
+    pub fn set_allowlist(ctx: Context<SetAllowlist>, root: [u8; 32], enabled: bool) -> Result<()> {
+        set_allowlist::handler(ctx, root, enabled)
+    }
+
+    /// Allow, update or disable a trading pair for the vault
+    /// 
+    /// # Arguments
+    /// * `enabled` - Whether intents may use this pair
+    /// * `max_amount` - Largest intent amount for the pair (0 = unlimited)
+    /// * `max_slippage_bps` - Largest slippage an intent may request (0 = unlimited)
+    /// 
+    /// # Security
+    /// - Only vault owner can manage pairs
+    /// - Both sides must be real SPL mints and must differ
+    /// - Pairs are directional; allow each direction separately
+    /// 
+    /// # Events
+    /// Emits `TradePairUpdated` with the pair settings
+    pub fn set_trade_pair(
+        ctx: Context<SetTradePair>,
+        enabled: bool,
+        max_amount: u64,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        set_trade_pair::handler(ctx, enabled, max_amount, max_slippage_bps)
+    }
+
+    /// Configure the parameters behind one `strategy_type` for the vault
+    /// 
+    /// # Arguments
+    /// * `strategy_type` - Strategy identifier intents refer to (0-255)
+    /// * `enabled` - Whether intents may use this strategy
+    /// * `allowed_pairs` - `TradePair` addresses the strategy may trade, at
+    ///   most `StrategyConfig::MAX_ALLOWED_PAIRS` (empty = any enabled pair)
+    /// * `max_amount` - Largest intent amount (0 = unlimited)
+    /// * `max_slippage_bps` - Largest slippage an intent may request (0 = unlimited)
+    /// * `cooldown_secs` - Minimum seconds between executions (0 = none)
+    /// 
+    /// # Security
+    /// - Only vault owner can manage strategies
+    /// - Checked on submission and again on execution, so changes also apply
+    ///   to pending intents
+    /// 
+    /// # Events
+    /// Emits `StrategyConfigUpdated` with the strategy settings
+    pub fn set_strategy_config(
+        ctx: Context<SetStrategyConfig>,
+        strategy_type: u8,
+        enabled: bool,
+        allowed_pairs: Vec<Pubkey>,
+        max_amount: u64,
+        max_slippage_bps: u16,
+        cooldown_secs: i64,
+    ) -> Result<()> {
+        set_strategy_config::handler(
+            ctx,
+            strategy_type,
+            enabled,
+            allowed_pairs,
+            max_amount,
+            max_slippage_bps,
+            cooldown_secs,
+        )
+    }
+
+    /// Create the protocol-wide config singleton
+    /// 
+    /// # Arguments
+    /// * `protocol_fee_bps` - Protocol fee rate in basis points (reserved;
+    ///   no instruction charges it yet)
+    /// * `fee_recipient` - Account that will receive protocol fees
+    /// 
+    /// # Security
+    /// - Signer must be the program's upgrade authority and becomes protocol
+    ///   admin; run once right after deployment
+    /// - Every operational handler requires this account to exist
+    /// 
+    /// # Events
+    /// Emits `ProtocolInitialized`
+    pub fn initialize_protocol(
+        ctx: Context<InitializeProtocol>,
+        protocol_fee_bps: u16,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        initialize_protocol::handler(ctx, protocol_fee_bps, fee_recipient)
+    }
+
+    /// Update the protocol fee rate and recipient (admin only)
+    /// 
+    /// Both are reserved for fee collection; nothing charges them yet
+    /// 
+    /// # Events
+    /// Emits `ProtocolFeeUpdated`
+    pub fn set_protocol_fee(
+        ctx: Context<UpdateProtocolConfig>,
+        protocol_fee_bps: u16,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        protocol_admin::set_fee(ctx, protocol_fee_bps, fee_recipient)
+    }
+
+    /// Engage or release the global emergency pause (admin only)
+    /// 
+    /// # Security
+    /// - While paused, vault creation, deposits, intents, triggers, tips,
+    ///   executions and withdrawals fail across every vault
+    /// - Risk controls (vault pause flags, limits, allowlists) stay usable so
+    ///   owners can lock down their vaults during the incident
+    /// 
+    /// # Events
+    /// Emits `ProtocolPauseSet`
+    pub fn set_protocol_pause(ctx: Context<UpdateProtocolConfig>, paused: bool) -> Result<()> {
+        protocol_admin::set_pause(ctx, paused)
+    }
+
+    /// Register or remove an executor allowed to call `execute_trade` (admin only)
+    /// 
+    /// # Events
+    /// Emits `ExecutorUpdated`
+    pub fn set_executor(ctx: Context<UpdateProtocolConfig>, executor: Pubkey, enabled: bool) -> Result<()> {
+        protocol_admin::set_executor(ctx, executor, enabled)
+    }
+
+    /// Propose a new protocol admin; takes effect once they accept
+    /// 
+    /// # Events
+    /// Emits `ProtocolAdminTransferStarted`
+    pub fn transfer_protocol_admin(ctx: Context<UpdateProtocolConfig>, new_admin: Pubkey) -> Result<()> {
+        protocol_admin::transfer_admin(ctx, new_admin)
+    }
+
+    /// Accept a pending protocol admin transfer (signed by the new admin)
+    /// 
+    /// # Events
+    /// Emits `ProtocolAdminTransferred`
+    pub fn accept_protocol_admin(ctx: Context<AcceptProtocolAdmin>) -> Result<()> {
+        protocol_admin::accept_admin(ctx)
+    }
+
+    /// Post the reference price for token_in -> token_out (admin only)
+    /// 
+    /// # Arguments
+    /// * `price` - token_out per token_in, scaled by `PRICE_SCALE`
+    /// 
+    /// # Security
+    /// - Conditional intents trigger off this feed, and only while it is
+    ///   fresher than `PriceFeed::MAX_AGE_SECS`
+    /// 
+    /// # Events
+    /// Emits `PriceFeedUpdated`
+    pub fn set_price_feed(ctx: Context<SetPriceFeed>, price: u64) -> Result<()> {
+        set_price_feed::handler(ctx, price)
+    }
+
+    /// View: vault-level totals plus NAV and price-per-share per mint
+    /// 
+    /// # Remaining Accounts
+    /// Up to `MAX_SUMMARY_MINTS` of the vault's `CustodyLedger` accounts, one
+    /// summary entry each, in the order given
+    /// 
+    /// # Returns
+    /// `VaultSummary` via return data; simulate, nothing is written
+    pub fn get_vault_summary<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetVaultSummary<'info>>,
+    ) -> Result<VaultSummary> {
+        views::get_vault_summary(ctx)
+    }
+
+    /// View: `owner`'s shares in one mint and the assets backing them
+    /// 
+    /// # Returns
+    /// `PositionView` via return data; zero shares if no position exists
+    pub fn get_position(ctx: Context<GetPosition>, owner: Pubkey) -> Result<PositionView> {
+        views::get_position(ctx, owner)
+    }
+
+    /// View: shares `deposit` would mint for `amount` right now
+    /// 
+    /// # Returns
+    /// `DepositPreview` via return data; the allowlist proof is not checked
+    pub fn preview_deposit(ctx: Context<PreviewDeposit>, amount: u64) -> Result<DepositPreview> {
+        views::preview_deposit(ctx, amount)
+    }
+
+    /// View: how much the owner may withdraw now, and whether `amount` would
+    /// trip the circuit breaker
+    /// 
+    /// # Returns
+    /// `WithdrawPreview` via return data, with the rate limit rolled to the
+    /// current epoch as `withdraw` would
+    pub fn preview_withdraw(ctx: Context<PreviewWithdraw>, amount: u64) -> Result<WithdrawPreview> {
+        views::preview_withdraw(ctx, amount)
     }
 }
 
@@ -239,29 +776,55 @@ pub mod shadowvault {
 // ============================================================================
 // All events are emitted for transparency while preserving strategy privacy.
 // Indexers can track vault activity without learning trading strategies.
+//
+// Core vault lifecycle events (VaultInitialized, Deposited, TradeIntentSubmitted,
+// TradeExecuted, IntentTriggered, IntentTipped, IntentCancelled, Withdrawn,
+// VaultPaused) go out via event CPI so they survive log truncation; decode
+// them from inner instructions with `event_decoder`.
+//
+// Schema: every event starts with `version` (EVENT_SCHEMA_VERSION) and `seq`.
+// Vault events number from the vault's `event_seq`, protocol events from the
+// config's, each +1 per event, so a consumer that sees a jump has missed one.
+// Fields are read from account state after the handler's mutation.
+
+/// Bumped on any breaking change to an event layout
+pub const EVENT_SCHEMA_VERSION: u8 = 1;
 
 /// Emitted when a new vault is initialized
 #[event]
+#[derive(Clone, Debug)]
 pub struct VaultInitialized {
+    pub version: u8,
+    pub seq: u64,
     pub vault: Pubkey,
     pub owner: Pubkey,
+    pub vault_id: u64,
     pub encrypted_strategy_hash: [u8; 32],
     pub timestamp: i64,
 }
 
-/// Emitted when tokens are deposited into a vault
+/// Emitted when tokens are deposited into a vault; `user` paid and signed,
+/// `beneficiary` received the shares (equal for a plain `deposit`)
 #[event]
+#[derive(Clone, Debug)]
 pub struct Deposited {
+    pub version: u8,
+    pub seq: u64,
     pub vault: Pubkey,
     pub user: Pubkey,
+    pub beneficiary: Pubkey,
     pub amount: u64,
+    pub shares: u64,
     pub new_tvl: u64,
     pub timestamp: i64,
 }
 
 /// Emitted when a trade intent is submitted
 #[event]
+#[derive(Clone, Debug)]
 pub struct TradeIntentSubmitted {
+    pub version: u8,
+    pub seq: u64,
     pub vault: Pubkey,
     pub intent: Pubkey,
     pub user: Pubkey,
@@ -275,17 +838,68 @@ pub struct TradeIntentSubmitted {
 
 /// Emitted when a trade is executed
 #[event]
+#[derive(Clone, Debug)]
 pub struct TradeExecuted {
+    pub version: u8,
+    pub seq: u64,
     pub vault: Pubkey,
     pub intent: Pubkey,
+    pub executor: Pubkey,
     pub executed_amount: u64,
     pub received_amount: u64,
     pub success: bool,
+    pub timestamp: i64,
+}
+
+/// Emitted when a user cancels an open intent
+#[event]
+#[derive(Clone, Debug)]
+pub struct IntentCancelled {
+    pub version: u8,
+    pub seq: u64,
+    pub vault: Pubkey,
+    pub intent: Pubkey,
+    pub user: Pubkey,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a keeper triggers a conditional intent
+#[event]
+#[derive(Clone, Debug)]
+pub struct IntentTriggered {
+    pub version: u8,
+    pub seq: u64,
+    pub vault: Pubkey,
+    pub intent: Pubkey,
+    pub keeper: Pubkey,
+    pub leg: TriggerLeg,
+    pub price: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a user adds a keeper tip to an intent; `tip_mint` is None
+/// for lamports and `tip_amount` is the new total
+#[event]
+#[derive(Clone, Debug)]
+pub struct IntentTipped {
+    pub version: u8,
+    pub seq: u64,
+    pub vault: Pubkey,
+    pub intent: Pubkey,
+    pub user: Pubkey,
+    pub tip_mint: Option<Pubkey>,
+    pub amount: u64,
+    pub tip_amount: u64,
+    pub timestamp: i64,
 }
 
 /// Emitted when tokens are withdrawn from a vault
 #[event]
+#[derive(Clone, Debug)]
 pub struct Withdrawn {
+    pub version: u8,
+    pub seq: u64,
     pub vault: Pubkey,
     pub authority: Pubkey,
     pub amount: u64,
@@ -293,11 +907,303 @@ pub struct Withdrawn {
     pub timestamp: i64,
 }
 
-/// Emitted when a vault is paused or unpaused
+/// Emitted when a vault's pause flags change; `pause_flags` is the full set
 #[event]
+#[derive(Clone, Debug)]
 pub struct VaultPaused {
+    pub version: u8,
+    pub seq: u64,
     pub vault: Pubkey,
     pub authority: Pubkey,
-    pub is_paused: bool,
+    pub pause_flags: u8,
+    pub timestamp: i64,
+}
+
+/// Emitted when the vault owner assigns a new guardian
+#[event]
+#[derive(Clone, Debug)]
+pub struct GuardianUpdated {
+    pub version: u8,
+    pub seq: u64,
+    pub vault: Pubkey,
+    pub previous_guardian: Pubkey,
+    pub new_guardian: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when the owner changes circuit breaker settings
+#[event]
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerConfigured {
+    pub version: u8,
+    pub seq: u64,
+    pub vault: Pubkey,
+    pub window_secs: i64,
+    pub threshold_bps: u16,
+    pub timestamp: i64,
+}
+
+/// Emitted when a withdrawal would push rolling outflow past the drawdown
+/// threshold; the vault pauses itself and the withdrawal moves nothing
+#[event]
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerTripped {
+    pub version: u8,
+    pub seq: u64,
+    pub vault: Pubkey,
+    pub window_outflow: u64,
+    pub tvl: u64,
+    pub threshold_bps: u16,
+    pub pause_flags: u8,
+    pub timestamp: i64,
+}
+
+/// Emitted when the owner sets a per-epoch withdrawal limit for a mint
+#[event]
+#[derive(Clone, Debug)]
+pub struct WithdrawalLimitUpdated {
+    pub version: u8,
+    pub seq: u64,
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub max_amount: u64,
+    pub max_bps: u16,
+    pub effective_epoch: u64,
+    pub timestamp: i64,
+}
+
+// ============================================================================
+// View Results - Returned via return data by the view instructions
+// ============================================================================
+// Prices are scaled by `state::PRICE_SCALE`; u64::MAX stands for "no limit".
+
+/// Returned by `get_vault_summary`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VaultSummary {
+    pub tvl: u64,
+    pub custody_mint_count: u16,
+    pub execution_count: u64,
+    pub pause_flags: u8,
+    pub protocol_paused: bool,
+    // outflow the circuit breaker still tolerates in the current window
+    pub breaker_headroom: u64,
+    pub mints: Vec<MintSummary>,
+}
+
+/// NAV of one mint's book within a vault
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MintSummary {
+    pub mint: Pubkey,
+    pub balance: u64,
+    pub total_shares: u64,
+    pub price_per_share: u64,
+}
+
+/// Returned by `get_position`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PositionView {
+    pub owner: Pubkey,
+    pub shares: u64,
+    // assets the shares convert to, rounded down
+    pub assets: u64,
+    pub ledger_balance: u64,
+    pub total_shares: u64,
+    pub price_per_share: u64,
+}
+
+/// Returned by `preview_deposit`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct DepositPreview {
+    pub shares: u64,
+    pub price_per_share: u64,
+    // neither the protocol nor the vault's deposits are paused
+    pub open: bool,
+    // depositors need a proof; the preview does not check one
+    pub allowlist_enabled: bool,
+}
+
+/// Returned by `preview_withdraw`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct WithdrawPreview {
+    // min of booked balance, custody balance, rate limit and breaker headroom
+    pub withdrawable: u64,
+    pub rate_limit_remaining: u64,
+    pub breaker_headroom: u64,
+    // `withdraw(amount)` would trip the breaker: it pauses the vault and
+    // moves nothing
+    pub trips_breaker: bool,
+    // neither the protocol nor the vault's withdrawals are paused
+    pub open: bool,
+    // `withdraw(amount)` by the owner would succeed
+    pub allowed: bool,
+}
+
+/// Returned by `withdraw`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct WithdrawOutcome {
+    // transferred to the recipient; 0 when the breaker tripped
+    pub withdrawn: u64,
+    // the breaker refused the amount and paused the vault
+    pub breaker_tripped: bool,
+}
+
+/// Book vs custody balance for one mint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MintDelta {
+    pub mint: Pubkey,
+    pub ledger_balance: u64,
+    pub custody_balance: u64,
+    // custody minus ledger; positive means untracked funds in custody
+    pub delta: i128,
+}
+
+/// Emitted by `reconcile`; `ledger_tvl` is the TVL before any resync
+#[event]
+#[derive(Clone, Debug)]
+pub struct ReconciliationReport {
+    pub version: u8,
+    pub seq: u64,
+    pub vault: Pubkey,
+    pub caller: Pubkey,
+    pub ledger_tvl: u64,
+    pub custody_total: u64,
+    pub deltas: Vec<MintDelta>,
+    pub resynced: bool,
+    pub timestamp: i64,
+}
+
+/// Emitted when the owner moves a mint's `unallocated` surplus into its
+/// ledger balance
+#[event]
+#[derive(Clone, Debug)]
+pub struct SurplusAllocated {
+    pub version: u8,
+    pub seq: u64,
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub ledger_balance: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the owner changes the depositor allowlist
+#[event]
+#[derive(Clone, Debug)]
+pub struct AllowlistUpdated {
+    pub version: u8,
+    pub seq: u64,
+    pub vault: Pubkey,
+    pub root: [u8; 32],
+    pub enabled: bool,
+    pub timestamp: i64,
+}
+
+/// Emitted when the owner allows, updates or disables a trading pair
+#[event]
+#[derive(Clone, Debug)]
+pub struct TradePairUpdated {
+    pub version: u8,
+    pub seq: u64,
+    pub vault: Pubkey,
+    pub token_in: Pubkey,
+    pub token_out: Pubkey,
+    pub enabled: bool,
+    pub max_amount: u64,
+    pub max_slippage_bps: u16,
+    pub timestamp: i64,
+}
+
+/// Emitted when the owner creates or updates a strategy config
+#[event]
+#[derive(Clone, Debug)]
+pub struct StrategyConfigUpdated {
+    pub version: u8,
+    pub seq: u64,
+    pub vault: Pubkey,
+    pub strategy_type: u8,
+    pub enabled: bool,
+    pub allowed_pairs: Vec<Pubkey>,
+    pub max_amount: u64,
+    pub max_slippage_bps: u16,
+    pub cooldown_secs: i64,
+    pub timestamp: i64,
+}
+
+/// Emitted once when the protocol config singleton is created
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProtocolInitialized {
+    pub version: u8,
+    pub seq: u64,
+    pub admin: Pubkey,
+    pub protocol_fee_bps: u16,
+    pub fee_recipient: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when the protocol admin changes the fee rate or recipient
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProtocolFeeUpdated {
+    pub version: u8,
+    pub seq: u64,
+    pub protocol_fee_bps: u16,
+    pub fee_recipient: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when the global emergency pause is engaged or released
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProtocolPauseSet {
+    pub version: u8,
+    pub seq: u64,
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+/// Emitted when an executor is registered or removed
+#[event]
+#[derive(Clone, Debug)]
+pub struct ExecutorUpdated {
+    pub version: u8,
+    pub seq: u64,
+    pub executor: Pubkey,
+    pub enabled: bool,
+    pub timestamp: i64,
+}
+
+/// Emitted when the protocol admin proposes a successor
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProtocolAdminTransferStarted {
+    pub version: u8,
+    pub seq: u64,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when the protocol admin posts a reference price
+#[event]
+#[derive(Clone, Debug)]
+pub struct PriceFeedUpdated {
+    pub version: u8,
+    pub seq: u64,
+    pub token_in: Pubkey,
+    pub token_out: Pubkey,
+    pub price: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a pending protocol admin accepts the role
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProtocolAdminTransferred {
+    pub version: u8,
+    pub seq: u64,
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
     pub timestamp: i64,
 }
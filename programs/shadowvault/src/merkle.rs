@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak::hashv;
+
+/// Leaf for a permitted depositor: keccak256(pubkey)
+pub fn leaf(key: &Pubkey) -> [u8; 32] {
+    hashv(&[key.as_ref()]).to_bytes()
+}
+
+/// Verifies `leaf` against `root` using sorted-pair keccak256 hashing, so
+/// proofs carry no left/right flags
+pub fn verify(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == *root
+}
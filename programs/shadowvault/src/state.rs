@@ -1,13 +1,124 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::ShadowError;
+use crate::merkle;
+
+/// Fixed-point scale for prices and price-per-share (1.0 = PRICE_SCALE)
+pub const PRICE_SCALE: u64 = 1_000_000_000;
+
+/// Protocol-wide singleton: admin, fees, global emergency pause and the
+/// executors allowed to settle intents
+#[account]
+pub struct ProtocolConfig {
+    pub admin: Pubkey,
+    // set by transfer_protocol_admin, cleared on accept; default = none
+    pub pending_admin: Pubkey,
+    // reserved: stored and admin-settable, but no instruction charges it yet
+    pub protocol_fee_bps: u16,
+    pub fee_recipient: Pubkey,
+    // global emergency stop checked by every operational handler
+    pub paused: bool,
+    pub executors: Vec<Pubkey>,
+    // sequence number of the last protocol-level event
+    pub event_seq: u64,
+    pub bump: u8,
+}
+
+impl ProtocolConfig {
+    pub const SEED_PREFIX: &'static [u8] = b"protocol_config";
+    pub const MAX_EXECUTORS: usize = 16;
+    pub const MAX_PROTOCOL_FEE_BPS: u16 = 5_000;
+
+    pub fn space() -> usize {
+        8 + // disc
+        32 + // admin
+        32 + // pending_admin
+        2 + // protocol_fee_bps
+        32 + // fee_recipient
+        1 + // paused
+        4 + 32 * Self::MAX_EXECUTORS + // executors
+        8 + // event_seq
+        1 // bump
+    }
+
+    pub fn is_executor(&self, key: &Pubkey) -> bool {
+        self.executors.contains(key)
+    }
+
+    /// Advances and returns the protocol event sequence number
+    pub fn next_event_seq(&mut self) -> Result<u64> {
+        self.event_seq = self
+            .event_seq
+            .checked_add(1)
+            .ok_or(ShadowError::MathOverflow)?;
+        Ok(self.event_seq)
+    }
+}
+
+/// Per-owner allocator for vault ids; seeds [b"owner_vaults", owner]
+#[account]
+pub struct OwnerVaultCounter {
+    pub owner: Pubkey,
+    // id the owner's next vault will take
+    pub next_vault_id: u64,
+    pub bump: u8,
+}
+
+impl OwnerVaultCounter {
+    pub const SEED_PREFIX: &'static [u8] = b"owner_vaults";
+
+    pub fn space() -> usize {
+        8 + // disc
+        32 + // owner
+        8 + // next_vault_id
+        1 // bump
+    }
+}
+
+/// Per-user, per-vault allocator for intent ids; seeds
+/// [b"user_intents", user, vault]
+#[account]
+pub struct UserIntentCounter {
+    pub user: Pubkey,
+    pub vault: Pubkey,
+    // id the user's next intent on this vault will take
+    pub next_intent_id: u64,
+    pub bump: u8,
+}
+
+impl UserIntentCounter {
+    pub const SEED_PREFIX: &'static [u8] = b"user_intents";
+
+    pub fn space() -> usize {
+        8 + // disc
+        32 + // user
+        32 + // vault
+        8 + // next_intent_id
+        1 // bump
+    }
+}
+
 #[account]
 pub struct VaultAccount {
     pub owner: Pubkey,
+    // index among the owner's vaults; part of the PDA seeds
+    pub vault_id: u64,
+    // secondary key allowed to toggle pause flags; cannot move funds
+    pub guardian: Pubkey,
     // 32-byte hash of encrypted strategy commitment; reveals nothing about strategy
     pub encrypted_strategy_hash: [u8; 32],
     pub tvl: u64,
     pub execution_count: u64,
-    pub is_paused: bool,
+    // bitset of PauseFlags; each set bit halts one class of operation
+    pub pause_flags: u8,
+    pub circuit_breaker: CircuitBreaker,
+    // number of CustodyLedger accounts; lets reconcile prove it saw them all
+    pub custody_mint_count: u16,
+    // when enabled, depositors must prove membership under allowlist_root
+    pub allowlist_enabled: bool,
+    pub allowlist_root: [u8; 32],
+    // sequence number of the last event emitted for this vault
+    pub event_seq: u64,
     pub bump: u8,
 }
 
@@ -18,18 +129,539 @@ impl VaultAccount {
         // discriminator + fields
         8 + // anchor discriminator
         32 + // owner
+        8 + // vault_id
+        32 + // guardian
         32 + // encrypted_strategy_hash
         8 + // tvl
         8 + // execution_count
-        1 + // is_paused
+        1 + // pause_flags
+        CircuitBreaker::SIZE + // circuit_breaker
+        2 + // custody_mint_count
+        1 + // allowlist_enabled
+        32 + // allowlist_root
+        8 + // event_seq
         1 // bump
     }
+
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.pause_flags & flag != 0
+    }
+
+    pub fn is_pause_authority(&self, key: &Pubkey) -> bool {
+        *key == self.owner || *key == self.guardian
+    }
+
+    /// Whether `depositor` may deposit, given a proof when the allowlist is on
+    pub fn is_allowed_depositor(&self, depositor: &Pubkey, proof: &[[u8; 32]]) -> bool {
+        !self.allowlist_enabled
+            || merkle::verify(proof, &self.allowlist_root, merkle::leaf(depositor))
+    }
+
+    /// Advances and returns the vault event sequence number. Every vault
+    /// event carries one, starting at 1, so consumers can detect gaps.
+    pub fn next_event_seq(&mut self) -> Result<u64> {
+        self.event_seq = self
+            .event_seq
+            .checked_add(1)
+            .ok_or(ShadowError::MathOverflow)?;
+        Ok(self.event_seq)
+    }
+
+    /// Counts a newly opened CustodyLedger
+    pub fn register_custody_mint(&mut self) -> Result<()> {
+        self.custody_mint_count = self
+            .custody_mint_count
+            .checked_add(1)
+            .ok_or(ShadowError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Books `amount` into the rolling outflow window before it leaves
+    /// custody, while `tvl` still includes it. An outflow above
+    /// `breaker_headroom` is refused instead: every pause flag is set, the
+    /// window restarts, and the window outflow it would have reached is
+    /// returned so the caller can report the trip without moving funds.
+    pub fn record_outflow(&mut self, amount: u64, now: i64) -> Result<Option<u64>> {
+        if !self.circuit_breaker.is_enabled() || amount == 0 {
+            return Ok(None);
+        }
+        let headroom = self.breaker_headroom(now);
+
+        let breaker = &mut self.circuit_breaker;
+        if now.saturating_sub(breaker.window_start) >= breaker.window_secs {
+            breaker.window_start = now;
+            breaker.window_outflow = 0;
+        }
+        let window_outflow = breaker
+            .window_outflow
+            .checked_add(amount)
+            .ok_or(ShadowError::MathOverflow)?;
+        if amount <= headroom {
+            breaker.window_outflow = window_outflow;
+            return Ok(None);
+        }
+
+        breaker.window_start = now;
+        breaker.window_outflow = 0;
+        self.pause_flags |= PauseFlags::ALL;
+        Ok(Some(window_outflow))
+    }
+
+    /// Largest single outflow `record_outflow` would accept at `now` without
+    /// tripping; u64::MAX when the breaker is disabled. `tvl` must still
+    /// include the outflow.
+    pub fn breaker_headroom(&self, now: i64) -> u64 {
+        let breaker = &self.circuit_breaker;
+        if !breaker.is_enabled() {
+            return u64::MAX;
+        }
+        let window_outflow = match now.saturating_sub(breaker.window_start) >= breaker.window_secs {
+            true => 0,
+            false => breaker.window_outflow as u128,
+        };
+        // tvl + window outflow stays fixed as funds leave, so the breaker
+        // trips once the window outflow reaches ceil(base * threshold)
+        let base = self.tvl as u128 + window_outflow;
+        let trip_at = (base * breaker.threshold_bps as u128).div_ceil(10_000);
+        let headroom = trip_at.saturating_sub(window_outflow).saturating_sub(1);
+        u64::try_from(headroom).unwrap_or(u64::MAX)
+    }
+}
+
+/// Rolling-window drawdown guard; a `window_secs` of 0 disables it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct CircuitBreaker {
+    pub window_secs: i64,
+    // outflow within one window, in bps of vault value, that trips the breaker
+    pub threshold_bps: u16,
+    pub window_start: i64,
+    pub window_outflow: u64,
 }
 
+impl CircuitBreaker {
+    pub const SIZE: usize = 8 + // window_secs
+        2 + // threshold_bps
+        8 + // window_start
+        8; // window_outflow
+
+    pub fn is_enabled(&self) -> bool {
+        self.window_secs > 0
+    }
+}
+
+/// Bits of `VaultAccount::pause_flags`
+pub struct PauseFlags;
+
+impl PauseFlags {
+    pub const DEPOSITS: u8 = 1 << 0;
+    pub const INTENTS: u8 = 1 << 1;
+    pub const EXECUTIONS: u8 = 1 << 2;
+    pub const WITHDRAWALS: u8 = 1 << 3;
+    // No fee-accrual bit until a path charges `ProtocolConfig::protocol_fee_bps`
+
+    pub const ALL: u8 = Self::DEPOSITS | Self::INTENTS | Self::EXECUTIONS | Self::WITHDRAWALS;
+}
+
+/// Per-mint book balance of vault custody, compared against the custody ATA
+/// by `reconcile`
+#[account]
+pub struct CustodyLedger {
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub balance: u64,
+    // outstanding depositor shares for this mint
+    pub total_shares: u64,
+    // custody surplus booked by the last resync; held apart from `balance`
+    // so donations never reprice shares
+    pub unallocated: u64,
+    pub bump: u8,
+}
+
+impl CustodyLedger {
+    pub const SEED_PREFIX: &'static [u8] = b"ledger";
+
+    // Virtual offsets make the first-depositor donation attack unprofitable:
+    // an attacker would need to donate ~VIRTUAL_SHARES times the victim's
+    // deposit to round it down to zero shares.
+    pub const VIRTUAL_SHARES: u64 = 1_000;
+    pub const VIRTUAL_ASSETS: u64 = 1;
+
+    pub fn space() -> usize {
+        8 + // disc
+        32 + // vault
+        32 + // mint
+        8 + // balance
+        8 + // total_shares
+        8 + // unallocated
+        1 // bump
+    }
+
+    /// Fills in a ledger created by `init_if_needed`; returns true if it was new
+    pub fn open_if_new(&mut self, vault: Pubkey, mint: Pubkey, bump: u8) -> bool {
+        if self.vault != Pubkey::default() {
+            return false;
+        }
+        self.vault = vault;
+        self.mint = mint;
+        self.bump = bump;
+        true
+    }
+
+    /// Shares minted for depositing `amount`, rounded down in the vault's favour.
+    /// Priced from the tracked `balance`, never the raw custody ATA amount, so
+    /// direct transfers into custody cannot move the price.
+    pub fn shares_for_deposit(&self, amount: u64) -> Result<u64> {
+        mul_div_floor(
+            amount,
+            self.total_shares as u128 + Self::VIRTUAL_SHARES as u128,
+            self.balance as u128 + Self::VIRTUAL_ASSETS as u128,
+        )
+    }
+
+    /// Assets backing `shares`, rounded down in the vault's favour
+    pub fn assets_for_shares(&self, shares: u64) -> Result<u64> {
+        mul_div_floor(
+            shares,
+            self.balance as u128 + Self::VIRTUAL_ASSETS as u128,
+            self.total_shares as u128 + Self::VIRTUAL_SHARES as u128,
+        )
+    }
+
+    /// Assets per share, scaled by PRICE_SCALE
+    pub fn price_per_share(&self) -> Result<u64> {
+        self.assets_for_shares(PRICE_SCALE)
+    }
+}
+
+fn mul_div_floor(value: u64, numerator: u128, denominator: u128) -> Result<u64> {
+    let result = (value as u128)
+        .checked_mul(numerator)
+        .ok_or(ShadowError::MathOverflow)?
+        / denominator;
+    u64::try_from(result).map_err(|_| ShadowError::MathOverflow.into())
+}
+
+/// A depositor's share balance against one mint's ledger
+#[account]
+pub struct UserPosition {
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub shares: u64,
+    pub bump: u8,
+}
+
+impl UserPosition {
+    pub const SEED_PREFIX: &'static [u8] = b"position";
+
+    /// Fills in a position created by `init_if_needed`
+    pub fn open_if_new(&mut self, vault: Pubkey, mint: Pubkey, owner: Pubkey, bump: u8) {
+        if self.vault == Pubkey::default() {
+            self.vault = vault;
+            self.mint = mint;
+            self.owner = owner;
+            self.bump = bump;
+        }
+    }
+
+    pub fn space() -> usize {
+        8 + // disc
+        32 + // vault
+        32 + // mint
+        32 + // owner
+        8 + // shares
+        1 // bump
+    }
+}
+
+/// Per-mint cap on vault outflow within one Solana epoch. Loosened limits
+/// only take effect from the next epoch so a leaked owner key cannot lift
+/// them to drain the vault at once; a limit of 0 means unlimited.
+#[account]
+pub struct WithdrawalRateLimit {
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub max_amount: u64,
+    pub max_bps: u16,
+    // applied when the next epoch opens
+    pub next_max_amount: u64,
+    pub next_max_bps: u16,
+    // epoch the counters below belong to; None until the first withdrawal
+    pub epoch: Option<u64>,
+    // ledger balance when the epoch opened; basis for `max_bps`
+    pub epoch_start_balance: u64,
+    pub withdrawn_this_epoch: u64,
+    pub bump: u8,
+}
+
+impl WithdrawalRateLimit {
+    pub const SEED_PREFIX: &'static [u8] = b"withdraw_limit";
+
+    pub fn space() -> usize {
+        8 + // disc
+        32 + // vault
+        32 + // mint
+        8 + // max_amount
+        2 + // max_bps
+        8 + // next_max_amount
+        2 + // next_max_bps
+        1 + 8 + // epoch
+        8 + // epoch_start_balance
+        8 + // withdrawn_this_epoch
+        1 // bump
+    }
+
+    /// Opens a new epoch if `epoch` differs from the tracked one, promoting
+    /// pending limits and snapshotting the ledger balance. The ledger, not the
+    /// custody ATA, so a direct transfer cannot raise the `max_bps` cap.
+    pub fn roll_epoch(&mut self, epoch: u64, ledger_balance: u64) {
+        if self.epoch == Some(epoch) {
+            return;
+        }
+        self.epoch = Some(epoch);
+        self.epoch_start_balance = ledger_balance;
+        self.withdrawn_this_epoch = 0;
+        self.max_amount = self.next_max_amount;
+        self.max_bps = self.next_max_bps;
+    }
+
+    /// Records `amount` against the current epoch, failing if either cap
+    /// would be exceeded.
+    pub fn consume(&mut self, amount: u64) -> Result<()> {
+        let total = self
+            .withdrawn_this_epoch
+            .checked_add(amount)
+            .ok_or(ShadowError::MathOverflow)?;
+
+        if self.max_amount > 0 {
+            require!(total <= self.max_amount, ShadowError::WithdrawalRateLimited);
+        }
+        if self.max_bps > 0 {
+            let cap = (self.epoch_start_balance as u128) * self.max_bps as u128;
+            require!(
+                (total as u128) * 10_000 <= cap,
+                ShadowError::WithdrawalRateLimited
+            );
+        }
+
+        self.withdrawn_this_epoch = total;
+        Ok(())
+    }
+
+    /// Outflow `consume` would still accept this epoch; u64::MAX when unlimited
+    pub fn remaining(&self) -> u64 {
+        let mut remaining = u64::MAX;
+        if self.max_amount > 0 {
+            remaining = self.max_amount.saturating_sub(self.withdrawn_this_epoch);
+        }
+        if self.max_bps > 0 {
+            let cap = (self.epoch_start_balance as u128) * self.max_bps as u128 / 10_000;
+            let cap = u64::try_from(cap).unwrap_or(u64::MAX);
+            remaining = remaining.min(cap.saturating_sub(self.withdrawn_this_epoch));
+        }
+        remaining
+    }
+
+    /// Whether the (amount, bps) pair is at least as strict as the active one
+    pub fn is_tightening(&self, max_amount: u64, max_bps: u16) -> bool {
+        let amount_ok = self.max_amount == 0 || (max_amount > 0 && max_amount <= self.max_amount);
+        let bps_ok = self.max_bps == 0 || (max_bps > 0 && max_bps <= self.max_bps);
+        amount_ok && bps_ok
+    }
+}
+
+/// Owner-approved trading direction for a vault, token_in -> token_out.
+/// Limits of 0 mean unlimited.
+#[account]
+pub struct TradePair {
+    pub vault: Pubkey,
+    pub token_in: Pubkey,
+    pub token_out: Pubkey,
+    pub enabled: bool,
+    pub max_amount: u64,
+    pub max_slippage_bps: u16,
+    pub bump: u8,
+}
+
+impl TradePair {
+    pub const SEED_PREFIX: &'static [u8] = b"pair";
+
+    pub fn space() -> usize {
+        8 + // disc
+        32 + // vault
+        32 + // token_in
+        32 + // token_out
+        1 + // enabled
+        8 + // max_amount
+        2 + // max_slippage_bps
+        1 // bump
+    }
+
+    /// Checks an intent's size and slippage against this pair's limits
+    pub fn check_intent(&self, amount: u64, max_slippage_bps: u16) -> Result<()> {
+        require!(self.enabled, ShadowError::PairNotAllowed);
+        if self.max_amount > 0 {
+            require!(amount <= self.max_amount, ShadowError::PairLimitExceeded);
+        }
+        if self.max_slippage_bps > 0 {
+            require!(
+                max_slippage_bps <= self.max_slippage_bps,
+                ShadowError::PairLimitExceeded
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Protocol-wide reference price for token_in -> token_out (token_out per
+/// token_in, scaled by `PRICE_SCALE`), posted by the protocol admin.
+/// Conditional intents trigger off it and limit fills are priced at it.
+#[account]
+pub struct PriceFeed {
+    pub token_in: Pubkey,
+    pub token_out: Pubkey,
+    pub price: u64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl PriceFeed {
+    pub const SEED_PREFIX: &'static [u8] = b"price_feed";
+    /// Oldest price a trigger or limit fill may act on
+    pub const MAX_AGE_SECS: i64 = 300;
+
+    pub fn space() -> usize {
+        8 + // disc
+        32 + // token_in
+        32 + // token_out
+        8 + // price
+        8 + // updated_at
+        1 // bump
+    }
+
+    pub fn is_fresh(&self, now: i64) -> bool {
+        now.saturating_sub(self.updated_at) <= Self::MAX_AGE_SECS
+    }
+
+    /// token_out `amount` of token_in is worth at the feed price, rounded down
+    pub fn quote(&self, amount: u64) -> Result<u64> {
+        let out = amount as u128 * self.price as u128 / PRICE_SCALE as u128;
+        u64::try_from(out).map_err(|_| error!(ShadowError::MathOverflow))
+    }
+
+    /// token_in `amount_out` of token_out is worth at the feed price, rounded
+    /// up; the admin never posts a zero price
+    pub fn value_in(&self, amount_out: u64) -> Result<u64> {
+        let value = (amount_out as u128 * PRICE_SCALE as u128).div_ceil(self.price as u128);
+        u64::try_from(value).map_err(|_| error!(ShadowError::MathOverflow))
+    }
+}
+
+/// Owner-defined parameters for one `strategy_type` on a vault. Every
+/// intent names a strategy, which must be configured and enabled. Limits of
+/// 0 mean unlimited; an empty `allowed_pairs` admits any pair the vault
+/// enables.
+#[account]
+pub struct StrategyConfig {
+    pub vault: Pubkey,
+    pub strategy_type: u8,
+    pub enabled: bool,
+    // TradePair addresses this strategy may trade
+    pub allowed_pairs: Vec<Pubkey>,
+    pub max_amount: u64,
+    pub max_slippage_bps: u16,
+    // minimum seconds between two executions under this strategy
+    pub cooldown_secs: i64,
+    pub last_executed_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl StrategyConfig {
+    pub const SEED_PREFIX: &'static [u8] = b"strategy";
+    pub const MAX_ALLOWED_PAIRS: usize = 8;
+
+    pub fn space() -> usize {
+        8 + // disc
+        32 + // vault
+        1 + // strategy_type
+        1 + // enabled
+        4 + 32 * Self::MAX_ALLOWED_PAIRS + // allowed_pairs
+        8 + // max_amount
+        2 + // max_slippage_bps
+        8 + // cooldown_secs
+        1 + 8 + // last_executed_at
+        1 // bump
+    }
+
+    /// Checks an intent on `pair` against this strategy's parameters
+    pub fn check_intent(&self, pair: &Pubkey, amount: u64, max_slippage_bps: u16) -> Result<()> {
+        require!(self.enabled, ShadowError::StrategyDisabled);
+        if !self.allowed_pairs.is_empty() {
+            require!(self.allowed_pairs.contains(pair), ShadowError::StrategyPairNotAllowed);
+        }
+        if self.max_amount > 0 {
+            require!(amount <= self.max_amount, ShadowError::StrategyLimitExceeded);
+        }
+        if self.max_slippage_bps > 0 {
+            require!(
+                max_slippage_bps <= self.max_slippage_bps,
+                ShadowError::StrategyLimitExceeded
+            );
+        }
+        Ok(())
+    }
+
+    /// Starts an execution at `now`, failing while the cooldown runs
+    pub fn record_execution(&mut self, now: i64) -> Result<()> {
+        if let Some(last) = self.last_executed_at {
+            let ready_at = last.checked_add(self.cooldown_secs).ok_or(ShadowError::MathOverflow)?;
+            require!(now >= ready_at, ShadowError::StrategyCoolingDown);
+        }
+        self.last_executed_at = Some(now);
+        Ok(())
+    }
+}
+
+/// How an intent is filled
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntentKind {
+    /// The whole amount in one `execute_trade`
+    Market,
+    /// `slices` parts, one `execute_twap_slice` per `slice_interval_secs`
+    Twap,
+    /// Partially fillable by `execute_trade`; every fill must meet `limit_price`
+    Limit,
+    /// The whole amount in one `execute_trade`, once `trigger_intent` has
+    /// seen the price feed cross `stop_loss_price` or `take_profit_price`
+    Conditional,
+}
+
+/// Which price trigger of a conditional intent fired
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerLeg {
+    /// Feed price fell to or below `stop_loss_price`
+    StopLoss,
+    /// Feed price rose to or above `take_profit_price`
+    TakeProfit,
+}
+
+/// A user's order against a vault. Market and limit intents are a single
+/// slice, so the fill tracking below reads the same for every kind; limit
+/// intents count each partial fill in `slices_done`. Intents stay open until
+/// filled, cancelled or expired.
+///
+/// A conditional intent with both trigger prices is an OCO pair: whichever
+/// leg crosses first triggers the fill, and the other can no longer fire.
+///
+/// A keeper tip goes to the executor whose fill completes the intent and
+/// back to the user on cancel. Lamport tips sit on this account above its
+/// rent; token tips in the intent's ATA for `tip_mint`.
 #[account]
 pub struct TradeIntent {
     pub user: Pubkey,
     pub vault: Pubkey,
+    // index among the user's intents on the vault; part of the PDA seeds
+    pub intent_id: u64,
     pub token_in: Pubkey,
     pub token_out: Pubkey,
     pub amount: u64,
@@ -37,22 +669,132 @@ pub struct TradeIntent {
     pub strategy_type: u8,
     pub timestamp: i64,
     pub bump: u8,
+    pub kind: IntentKind,
+    pub slices: u16,
+    pub slice_interval_secs: i64,
+    pub remaining_amount: u64,
+    pub slices_done: u16,
+    pub next_execution_ts: i64,
+    // least token_out per token_in a fill may realize, scaled by PRICE_SCALE;
+    // 0 for no limit
+    pub limit_price: u64,
+    // unix time after which the intent no longer fills; 0 for never
+    pub expires_at: i64,
+    pub cancelled: bool,
+    // conditional triggers in PRICE_SCALE units of the pair's price feed;
+    // 0 for no such leg
+    pub stop_loss_price: u64,
+    pub take_profit_price: u64,
+    pub triggered: Option<TriggerLeg>,
+    // None for a lamport tip
+    pub tip_mint: Option<Pubkey>,
+    pub tip_amount: u64,
 }
 
 impl TradeIntent {
     pub const SEED_PREFIX: &'static [u8] = b"intent";
+    pub const MAX_SLICES: u16 = 100;
 
     pub fn space() -> usize {
         8 + // disc
         32 + // user
         32 + // vault
+        8 + // intent_id
         32 + // token_in
         32 + // token_out
         8 + // amount
         2 + // max_slippage_bps
         1 + // strategy_type
         8 + // timestamp
-        1 // bump
+        1 + // bump
+        1 + // kind
+        2 + // slices
+        8 + // slice_interval_secs
+        8 + // remaining_amount
+        2 + // slices_done
+        8 + // next_execution_ts
+        8 + // limit_price
+        8 + // expires_at
+        1 + // cancelled
+        8 + // stop_loss_price
+        8 + // take_profit_price
+        1 + 1 + // triggered
+        1 + 32 + // tip_mint
+        8 // tip_amount
+    }
+
+    /// Size of the last and largest slice of `amount` split `slices` ways;
+    /// pair and strategy size limits apply to it
+    pub fn max_slice_amount(amount: u64, slices: u16) -> u64 {
+        let slices = slices.max(1) as u64;
+        amount / slices + amount % slices
+    }
+
+    /// Amount the next slice executes; the last one takes the remainder
+    pub fn next_slice_amount(&self) -> u64 {
+        if self.slices_done.saturating_add(1) >= self.slices {
+            self.remaining_amount
+        } else {
+            self.amount / self.slices as u64
+        }
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.remaining_amount == 0
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at > 0 && now > self.expires_at
+    }
+
+    /// Fails unless receiving `received` for `executed` meets the limit price
+    pub fn check_limit(&self, executed: u64, received: u64) -> Result<()> {
+        let realized = received as u128 * PRICE_SCALE as u128;
+        require!(
+            realized >= self.limit_price as u128 * executed as u128,
+            ShadowError::LimitPriceNotMet
+        );
+        Ok(())
+    }
+
+    /// Leg a feed price of `price` triggers, if any; stop-loss wins should
+    /// both match
+    pub fn trigger_leg(&self, price: u64) -> Option<TriggerLeg> {
+        if self.stop_loss_price > 0 && price <= self.stop_loss_price {
+            Some(TriggerLeg::StopLoss)
+        } else if self.take_profit_price > 0 && price >= self.take_profit_price {
+            Some(TriggerLeg::TakeProfit)
+        } else {
+            None
+        }
+    }
+
+    /// Adds `amount` to the tip, which stays in one denomination
+    pub fn add_tip(&mut self, tip_mint: Option<Pubkey>, amount: u64, now: i64) -> Result<()> {
+        require!(amount > 0, ShadowError::InvalidAmount);
+        require!(!self.cancelled, ShadowError::IntentCancelled);
+        require!(!self.is_filled(), ShadowError::IntentFilled);
+        require!(!self.is_expired(now), ShadowError::IntentExpired);
+        require!(
+            self.tip_amount == 0 || self.tip_mint == tip_mint,
+            ShadowError::TipMismatch
+        );
+        self.tip_mint = tip_mint;
+        self.tip_amount = self.tip_amount.checked_add(amount).ok_or(ShadowError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Books a filled slice of `executed` at `now` and schedules the next
+    pub fn record_fill(&mut self, executed: u64, now: i64) -> Result<()> {
+        self.remaining_amount = self
+            .remaining_amount
+            .checked_sub(executed)
+            .ok_or(ShadowError::MathOverflow)?;
+        self.slices_done = self.slices_done.checked_add(1).ok_or(ShadowError::MathOverflow)?;
+        self.next_execution_ts = now
+            .checked_add(self.slice_interval_secs)
+            .ok_or(ShadowError::MathOverflow)?;
+        Ok(())
     }
 }
 
@@ -63,6 +805,9 @@ pub struct ExecutionResult {
     pub received_amount: u64,
     pub success: bool,
     pub bump: u8,
+    // keeper tip paid on the completing fill; None mint for lamports
+    pub tip_mint: Option<Pubkey>,
+    pub tip_paid: u64,
 }
 
 impl ExecutionResult {
@@ -74,6 +819,117 @@ impl ExecutionResult {
         8 + // executed_amount
         8 + // received_amount
         1 + // success
-        1 // bump
+        1 + // bump
+        1 + 32 + // tip_mint
+        8 // tip_paid
+    }
+}
+
+/// Fill record of one TWAP slice; market intents record an
+/// `ExecutionResult` instead
+#[account]
+pub struct TwapFill {
+    pub intent: Pubkey,
+    pub slice: u16,
+    pub executed_amount: u64,
+    pub received_amount: u64,
+    pub timestamp: i64,
+    pub bump: u8,
+    // keeper tip paid with the last slice, in the intent's tip denomination
+    pub tip_paid: u64,
+}
+
+impl TwapFill {
+    pub const SEED_PREFIX: &'static [u8] = b"fill";
+
+    pub fn space() -> usize {
+        8 + // disc
+        32 + // intent
+        2 + // slice
+        8 + // executed_amount
+        8 + // received_amount
+        8 + // timestamp
+        1 + // bump
+        8 // tip_paid
+    }
+}
+
+/// Ring buffer of a vault's most recent executions, written by
+/// `execute_trade` and each `execute_twap_slice`. Zero-copy, so each trade
+/// touches one slot instead of re-serializing the whole buffer, and clients
+/// read recent activity in one account fetch.
+#[account(zero_copy)]
+pub struct ExecutionHistory {
+    pub vault: Pubkey,
+    // executions ever recorded; the next one lands in records[total % CAPACITY]
+    pub total: u64,
+    pub bump: u8,
+    pub _padding: [u8; 7],
+    // length is CAPACITY, spelled out for the IDL
+    pub records: [ExecutionRecord; 64],
+}
+
+impl ExecutionHistory {
+    pub const SEED_PREFIX: &'static [u8] = b"execution_history";
+    pub const CAPACITY: usize = 64;
+
+    pub fn space() -> usize {
+        8 + std::mem::size_of::<ExecutionHistory>()
+    }
+
+    /// Records an execution, overwriting the oldest once the ring is full
+    pub fn push(&mut self, record: ExecutionRecord) -> Result<()> {
+        let slot = (self.total % Self::CAPACITY as u64) as usize;
+        self.records[slot] = record;
+        self.total = self
+            .total
+            .checked_add(1)
+            .ok_or(ShadowError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Recorded executions, newest first
+    pub fn recent(&self) -> impl Iterator<Item = &ExecutionRecord> {
+        let len = self.total.min(Self::CAPACITY as u64) as usize;
+        let next = (self.total % Self::CAPACITY as u64) as usize;
+        (1..=len).map(move |back| &self.records[(next + Self::CAPACITY - back) % Self::CAPACITY])
+    }
+}
+
+/// One `ExecutionHistory` slot
+#[zero_copy]
+pub struct ExecutionRecord {
+    pub intent: Pubkey,
+    pub token_in: Pubkey,
+    pub token_out: Pubkey,
+    pub executed_amount: u64,
+    pub received_amount: u64,
+    // token_out received per token_in executed, scaled by PRICE_SCALE
+    pub price: u64,
+    pub slot: u64,
+}
+
+impl ExecutionRecord {
+    pub fn new(
+        intent: Pubkey,
+        token_in: Pubkey,
+        token_out: Pubkey,
+        executed_amount: u64,
+        received_amount: u64,
+        slot: u64,
+    ) -> Result<Self> {
+        let price = match executed_amount {
+            0 => 0,
+            _ => mul_div_floor(received_amount, PRICE_SCALE as u128, executed_amount as u128)?,
+        };
+        Ok(Self {
+            intent,
+            token_in,
+            token_out,
+            executed_amount,
+            received_amount,
+            price,
+            slot,
+        })
     }
 }
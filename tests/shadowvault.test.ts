@@ -39,12 +39,37 @@ describe("ShadowVault Protocol Tests", () => {
   let vaultTokenAccount: PublicKey;
   let user: Keypair;
   let userTokenAccount: PublicKey;
+  let rateLimitPDA: PublicKey;
+  let ledgerPDA: PublicKey;
+  let ownerPositionPDA: PublicKey;
+
+  // Vault PDAs are seeded by owner and a per-owner u64 vault id
+  // u64 LE seed, for vault and intent ids
+  const vaultIdSeed = (id: number) => new anchor.BN(id).toArrayLike(Buffer, "le", 8);
+  let ownerCounterPDA: PublicKey;
+
+  // Protocol config singleton shared by every test file
+  const [configPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("protocol_config")],
+    program.programId
+  );
+  // ProgramData under the upgradeable loader; its upgrade authority (the
+  // deployer, `payer` on localnet) is the only signer that may initialize
+  const [programDataPDA] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  );
 
   // Test data
   const encryptedStrategyHash = Array.from(new Uint8Array(32).fill(1));
   const depositAmount = new anchor.BN(1_000_000); // 1 token (6 decimals)
   const withdrawAmount = new anchor.BN(500_000);  // 0.5 token
 
+  // Mirrors `PauseFlags` in programs/shadowvault/src/state.rs
+  const PAUSE_DEPOSITS = 1 << 0;
+  const PAUSE_INTENTS = 1 << 1;
+  const PAUSE_EXECUTIONS = 1 << 2;
+
   before(async () => {
     console.log("\n🔧 Setting up test environment...\n");
 
@@ -66,9 +91,13 @@ describe("ShadowVault Protocol Tests", () => {
     );
     await provider.connection.confirmTransaction(userAirdrop);
 
-    // Derive vault PDA
+    // Derive the owner's vault counter and first vault (id 0) PDAs
+    [ownerCounterPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("owner_vaults"), vaultOwner.publicKey.toBuffer()],
+      program.programId
+    );
     [vaultPDA, vaultBump] = PublicKey.findProgramAddressSync(
-      [Buffer.from("vault"), vaultOwner.publicKey.toBuffer()],
+      [Buffer.from("vault"), vaultOwner.publicKey.toBuffer(), vaultIdSeed(0)],
       program.programId
     );
 
@@ -81,6 +110,27 @@ describe("ShadowVault Protocol Tests", () => {
       6 // 6 decimals
     );
 
+    // Create protocol config (provider wallet is admin) and register the
+    // vault owner as an executor for the execution tests
+    if (!(await provider.connection.getAccountInfo(configPDA))) {
+      await program.methods
+        .initializeProtocol(0, payer.publicKey)
+        .accounts({
+          admin: payer.publicKey,
+          programData: programDataPDA,
+          config: configPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+    await program.methods
+      .setExecutor(vaultOwner.publicKey, true)
+      .accounts({
+        admin: payer.publicKey,
+        config: configPDA,
+      })
+      .rpc();
+
     console.log("✅ Test environment ready");
     console.log("   Vault Owner:", vaultOwner.publicKey.toString());
     console.log("   Vault PDA:", vaultPDA.toString());
@@ -94,7 +144,9 @@ describe("ShadowVault Protocol Tests", () => {
         .accounts({
           payer: payer.publicKey,
           owner: vaultOwner.publicKey,
+          ownerCounter: ownerCounterPDA,
           vault: vaultPDA,
+          config: configPDA,
           systemProgram: SystemProgram.programId,
         })
         .signers([vaultOwner])
@@ -108,12 +160,12 @@ describe("ShadowVault Protocol Tests", () => {
       expect(vault.owner.toString()).to.equal(vaultOwner.publicKey.toString());
       expect(vault.totalValueLocked.toNumber()).to.equal(0);
       expect(vault.executionCount.toNumber()).to.equal(0);
-      expect(vault.isPaused).to.be.false;
+      expect(vault.pauseFlags).to.equal(0);
       expect(vault.bump).to.equal(vaultBump);
       
       console.log("   ✅ Vault initialized successfully");
       console.log("   TVL:", vault.totalValueLocked.toString());
-      console.log("   Paused:", vault.isPaused);
+      console.log("   Pause Flags:", vault.pauseFlags);
     });
 
     it("Should fail to initialize vault twice", async () => {
@@ -123,7 +175,9 @@ describe("ShadowVault Protocol Tests", () => {
           .accounts({
             payer: payer.publicKey,
             owner: vaultOwner.publicKey,
+            ownerCounter: ownerCounterPDA,
             vault: vaultPDA,
+            config: configPDA,
             systemProgram: SystemProgram.programId,
           })
           .signers([vaultOwner])
@@ -135,6 +189,34 @@ describe("ShadowVault Protocol Tests", () => {
         console.log("   ✅ Correctly prevented duplicate initialization");
       }
     });
+
+    it("Should create a second vault for the same owner", async () => {
+      const [secondVaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), vaultOwner.publicKey.toBuffer(), vaultIdSeed(1)],
+        program.programId
+      );
+
+      await program.methods
+        .initializeVault(encryptedStrategyHash)
+        .accounts({
+          payer: payer.publicKey,
+          owner: vaultOwner.publicKey,
+          ownerCounter: ownerCounterPDA,
+          vault: secondVaultPDA,
+          config: configPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([vaultOwner])
+        .rpc();
+
+      const vault = await program.account.vaultAccount.fetch(secondVaultPDA);
+      expect(vault.vaultId.toNumber()).to.equal(1);
+
+      const counter = await program.account.ownerVaultCounter.fetch(ownerCounterPDA);
+      expect(counter.nextVaultId.toNumber()).to.equal(2);
+
+      console.log("   ✅ Second vault created:", secondVaultPDA.toString());
+    });
   });
 
   describe("2. Deposit Tokens", () => {
@@ -168,6 +250,33 @@ describe("ShadowVault Protocol Tests", () => {
         ASSOCIATED_TOKEN_PROGRAM_ID
       ))[0];
 
+      // Derive per-mint withdrawal rate limit PDA
+      [rateLimitPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("withdraw_limit"),
+          vaultPDA.toBuffer(),
+          tokenMint.toBuffer(),
+        ],
+        program.programId
+      );
+
+      // Derive per-mint custody ledger PDA
+      [ledgerPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("ledger"), vaultPDA.toBuffer(), tokenMint.toBuffer()],
+        program.programId
+      );
+
+      // Derive owner's share position PDA
+      [ownerPositionPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("position"),
+          vaultPDA.toBuffer(),
+          tokenMint.toBuffer(),
+          vaultOwner.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
       console.log("   Owner Token Account:", ownerTokenAccount.toString());
     });
 
@@ -176,13 +285,16 @@ describe("ShadowVault Protocol Tests", () => {
       const tvlBefore = vaultBefore.totalValueLocked;
 
       const tx = await program.methods
-        .deposit(depositAmount)
+        .deposit(depositAmount, [])
         .accounts({
           user: vaultOwner.publicKey,
           tokenMint: tokenMint,
           userAta: ownerTokenAccount,
           vault: vaultPDA,
+          config: configPDA,
           vaultAta: vaultTokenAccount,
+          ledger: ledgerPDA,
+          position: ownerPositionPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -207,13 +319,16 @@ describe("ShadowVault Protocol Tests", () => {
     it("Should fail to deposit zero amount", async () => {
       try {
         await program.methods
-          .deposit(new anchor.BN(0))
+          .deposit(new anchor.BN(0), [])
           .accounts({
             user: vaultOwner.publicKey,
             tokenMint: tokenMint,
             userAta: ownerTokenAccount,
             vault: vaultPDA,
+            config: configPDA,
             vaultAta: vaultTokenAccount,
+            ledger: ledgerPDA,
+            position: ownerPositionPDA,
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
@@ -230,9 +345,15 @@ describe("ShadowVault Protocol Tests", () => {
   });
 
   describe("3. Submit Trade Intent", () => {
+    const intentCounterPDA = (who: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("user_intents"), who.toBuffer(), vaultPDA.toBuffer()],
+        program.programId
+      )[0];
     let intentPDA: PublicKey;
     let intentBump: number;
     let tokenOut: PublicKey;
+    let pairPDA: PublicKey;
 
     before(async () => {
       // Create another token for token_out
@@ -250,10 +371,35 @@ describe("ShadowVault Protocol Tests", () => {
           Buffer.from("intent"),
           vaultOwner.publicKey.toBuffer(),
           vaultPDA.toBuffer(),
+          vaultIdSeed(0),
+        ],
+        program.programId
+      );
+
+      // Allow tokenMint -> tokenOut for this vault
+      [pairPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("pair"),
+          vaultPDA.toBuffer(),
+          tokenMint.toBuffer(),
+          tokenOut.toBuffer(),
         ],
         program.programId
       );
 
+      await program.methods
+        .setTradePair(true, new anchor.BN(1_000_000), 500)
+        .accounts({
+          owner: vaultOwner.publicKey,
+          vault: vaultPDA,
+          tokenInMint: tokenMint,
+          tokenOutMint: tokenOut,
+          pair: pairPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([vaultOwner])
+        .rpc();
+
       console.log("   Intent PDA:", intentPDA.toString());
       console.log("   Token Out:", tokenOut.toString());
     });
@@ -274,7 +420,10 @@ describe("ShadowVault Protocol Tests", () => {
         .accounts({
           user: vaultOwner.publicKey,
           vault: vaultPDA,
+          config: configPDA,
+          intentCounter: intentCounterPDA(vaultOwner.publicKey),
           intent: intentPDA,
+          pair: pairPDA,
           systemProgram: SystemProgram.programId,
         })
         .signers([vaultOwner])
@@ -287,6 +436,7 @@ describe("ShadowVault Protocol Tests", () => {
       
       expect(intent.user.toString()).to.equal(vaultOwner.publicKey.toString());
       expect(intent.vault.toString()).to.equal(vaultPDA.toString());
+      expect(intent.intentId.toNumber()).to.equal(0);
       expect(intent.tokenIn.toString()).to.equal(tokenMint.toString());
       expect(intent.tokenOut.toString()).to.equal(tokenOut.toString());
       expect(intent.amount.toNumber()).to.equal(amount.toNumber());
@@ -304,6 +454,7 @@ describe("ShadowVault Protocol Tests", () => {
           Buffer.from("intent"),
           user.publicKey.toBuffer(),
           vaultPDA.toBuffer(),
+          vaultIdSeed(0),
         ],
         program.programId
       );
@@ -320,7 +471,10 @@ describe("ShadowVault Protocol Tests", () => {
           .accounts({
             user: user.publicKey,
             vault: vaultPDA,
+            config: configPDA,
+            intentCounter: intentCounterPDA(user.publicKey),
             intent: newIntentPDA,
+            pair: pairPDA,
             systemProgram: SystemProgram.programId,
           })
           .signers([user])
@@ -332,6 +486,45 @@ describe("ShadowVault Protocol Tests", () => {
         console.log("   ✅ Correctly rejected zero amount");
       }
     });
+
+    it("Should fail to submit intent above the pair limit", async () => {
+      const [newIntentPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("intent"),
+          user.publicKey.toBuffer(),
+          vaultPDA.toBuffer(),
+          vaultIdSeed(0),
+        ],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .submitTradeIntent(
+            tokenMint,
+            tokenOut,
+            new anchor.BN(2_000_000),
+            100,
+            0
+          )
+          .accounts({
+            user: user.publicKey,
+            vault: vaultPDA,
+            config: configPDA,
+            intentCounter: intentCounterPDA(user.publicKey),
+            intent: newIntentPDA,
+            pair: pairPDA,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc();
+
+        expect.fail("Should have thrown PairLimitExceeded error");
+      } catch (error) {
+        expect(error.toString()).to.include("PairLimitExceeded");
+        console.log("   ✅ Correctly rejected oversized intent");
+      }
+    });
   });
 
   describe("4. Execute Trade", () => {
@@ -347,6 +540,7 @@ describe("ShadowVault Protocol Tests", () => {
           Buffer.from("intent"),
           vaultOwner.publicKey.toBuffer(),
           vaultPDA.toBuffer(),
+          vaultIdSeed(0),
         ],
         program.programId
       );
@@ -373,6 +567,7 @@ describe("ShadowVault Protocol Tests", () => {
         .accounts({
           authority: vaultOwner.publicKey,
           vault: vaultPDA,
+          config: configPDA,
           intent: intentPDA,
           vaultTokenIn: vaultTokenIn,
           vaultTokenOut: vaultTokenOut,
@@ -414,9 +609,12 @@ describe("ShadowVault Protocol Tests", () => {
           authority: vaultOwner.publicKey,
           tokenMint: tokenMint,
           vault: vaultPDA,
+          config: configPDA,
           owner: vaultOwner.publicKey,
           vaultAta: vaultTokenAccount,
+          ledger: ledgerPDA,
           recipientAta: ownerTokenAccount,
+          rateLimit: rateLimitPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -446,9 +644,13 @@ describe("ShadowVault Protocol Tests", () => {
             authority: user.publicKey,
             tokenMint: tokenMint,
             vault: vaultPDA,
+            config: configPDA,
             owner: vaultOwner.publicKey,
             vaultAta: vaultTokenAccount,
+            ledger: ledgerPDA,
             recipientAta: ownerTokenAccount,
+            rateLimit: rateLimitPDA,
+            rateLimit: rateLimitPDA,
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
@@ -471,9 +673,13 @@ describe("ShadowVault Protocol Tests", () => {
             authority: vaultOwner.publicKey,
             tokenMint: tokenMint,
             vault: vaultPDA,
+            config: configPDA,
             owner: vaultOwner.publicKey,
             vaultAta: vaultTokenAccount,
+            ledger: ledgerPDA,
             recipientAta: ownerTokenAccount,
+            rateLimit: rateLimitPDA,
+            rateLimit: rateLimitPDA,
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
@@ -490,9 +696,9 @@ describe("ShadowVault Protocol Tests", () => {
   });
 
   describe("6. Pause Vault", () => {
-    it("Should pause the vault (owner only)", async () => {
+    it("Should pause deposits (owner only)", async () => {
       const tx = await program.methods
-        .pauseVault(true)
+        .pauseVault(PAUSE_DEPOSITS, true)
         .accounts({
           authority: vaultOwner.publicKey,
           vault: vaultPDA,
@@ -502,23 +708,26 @@ describe("ShadowVault Protocol Tests", () => {
 
       console.log("   Transaction:", tx);
 
-      // Verify vault is paused
+      // Verify only deposits are paused
       const vault = await program.account.vaultAccount.fetch(vaultPDA);
-      expect(vault.isPaused).to.be.true;
+      expect(vault.pauseFlags).to.equal(PAUSE_DEPOSITS);
 
-      console.log("   ✅ Vault paused successfully");
+      console.log("   ✅ Deposits paused successfully");
     });
 
     it("Should fail to deposit when paused", async () => {
       try {
         await program.methods
-          .deposit(new anchor.BN(100_000))
+          .deposit(new anchor.BN(100_000), [])
           .accounts({
             user: vaultOwner.publicKey,
             tokenMint: tokenMint,
             userAta: ownerTokenAccount,
             vault: vaultPDA,
+            config: configPDA,
             vaultAta: vaultTokenAccount,
+            ledger: ledgerPDA,
+            position: ownerPositionPDA,
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
@@ -533,9 +742,9 @@ describe("ShadowVault Protocol Tests", () => {
       }
     });
 
-    it("Should unpause the vault", async () => {
+    it("Should unpause deposits", async () => {
       const tx = await program.methods
-        .pauseVault(false)
+        .pauseVault(PAUSE_DEPOSITS, false)
         .accounts({
           authority: vaultOwner.publicKey,
           vault: vaultPDA,
@@ -547,15 +756,95 @@ describe("ShadowVault Protocol Tests", () => {
 
       // Verify vault is unpaused
       const vault = await program.account.vaultAccount.fetch(vaultPDA);
-      expect(vault.isPaused).to.be.false;
+      expect(vault.pauseFlags).to.equal(0);
 
       console.log("   ✅ Vault unpaused successfully");
     });
 
+    it("Should halt trading while deposits keep flowing", async () => {
+      await program.methods
+        .pauseVault(PAUSE_INTENTS | PAUSE_EXECUTIONS, true)
+        .accounts({
+          authority: vaultOwner.publicKey,
+          vault: vaultPDA,
+        })
+        .signers([vaultOwner])
+        .rpc();
+
+      await program.methods
+        .deposit(new anchor.BN(100_000), [])
+        .accounts({
+          user: vaultOwner.publicKey,
+          tokenMint: tokenMint,
+          userAta: ownerTokenAccount,
+          vault: vaultPDA,
+          config: configPDA,
+          vaultAta: vaultTokenAccount,
+          ledger: ledgerPDA,
+          position: ownerPositionPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([vaultOwner])
+        .rpc();
+
+      await program.methods
+        .pauseVault(PAUSE_INTENTS | PAUSE_EXECUTIONS, false)
+        .accounts({
+          authority: vaultOwner.publicKey,
+          vault: vaultPDA,
+        })
+        .signers([vaultOwner])
+        .rpc();
+
+      console.log("   ✅ Deposit accepted with trading paused");
+    });
+
+    it("Should let the guardian pause deposits", async () => {
+      await program.methods
+        .setGuardian(user.publicKey)
+        .accounts({
+          owner: vaultOwner.publicKey,
+          vault: vaultPDA,
+        })
+        .signers([vaultOwner])
+        .rpc();
+
+      await program.methods
+        .pauseVault(PAUSE_DEPOSITS, true)
+        .accounts({
+          authority: user.publicKey,
+          vault: vaultPDA,
+        })
+        .signers([user])
+        .rpc();
+
+      await program.methods
+        .pauseVault(PAUSE_DEPOSITS, false)
+        .accounts({
+          authority: user.publicKey,
+          vault: vaultPDA,
+        })
+        .signers([user])
+        .rpc();
+
+      await program.methods
+        .setGuardian(vaultOwner.publicKey)
+        .accounts({
+          owner: vaultOwner.publicKey,
+          vault: vaultPDA,
+        })
+        .signers([vaultOwner])
+        .rpc();
+
+      console.log("   ✅ Guardian toggled deposit pause");
+    });
+
     it("Should fail to pause as non-owner", async () => {
       try {
         await program.methods
-          .pauseVault(true)
+          .pauseVault(PAUSE_DEPOSITS, true)
           .accounts({
             authority: user.publicKey,
             vault: vaultPDA,
@@ -571,6 +860,60 @@ describe("ShadowVault Protocol Tests", () => {
     });
   });
 
+  describe("6b. Protocol Pause", () => {
+    it("Should block deposits across vaults while the protocol is paused", async () => {
+      await program.methods
+        .setProtocolPause(true)
+        .accounts({ admin: payer.publicKey, config: configPDA })
+        .rpc();
+
+      try {
+        await program.methods
+          .deposit(new anchor.BN(100_000), [])
+          .accounts({
+            user: vaultOwner.publicKey,
+            config: configPDA,
+            tokenMint: tokenMint,
+            userAta: ownerTokenAccount,
+            vault: vaultPDA,
+            vaultAta: vaultTokenAccount,
+            ledger: ledgerPDA,
+            position: ownerPositionPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([vaultOwner])
+          .rpc();
+
+        expect.fail("Should have thrown ProtocolPaused error");
+      } catch (error) {
+        expect(error.toString()).to.include("ProtocolPaused");
+        console.log("   ✅ Correctly blocked deposit during protocol pause");
+      } finally {
+        await program.methods
+          .setProtocolPause(false)
+          .accounts({ admin: payer.publicKey, config: configPDA })
+          .rpc();
+      }
+    });
+
+    it("Should fail to pause the protocol as non-admin", async () => {
+      try {
+        await program.methods
+          .setProtocolPause(true)
+          .accounts({ admin: user.publicKey, config: configPDA })
+          .signers([user])
+          .rpc();
+
+        expect.fail("Should have thrown Unauthorized error");
+      } catch (error) {
+        expect(error.toString()).to.include("Unauthorized");
+        console.log("   ✅ Correctly rejected non-admin protocol pause");
+      }
+    });
+  });
+
   describe("7. Event Emissions", () => {
     it("Should emit events for all operations", async () => {
       // Note: Event testing requires additional setup with event listeners
@@ -578,12 +921,29 @@ describe("ShadowVault Protocol Tests", () => {
       console.log("   ℹ️  Event emission verified through transaction logs");
       console.log("   ✅ All events emitted correctly");
     });
+
+    it("Should advance the vault event sequence once per event", async () => {
+      const before = (await program.account.vaultAccount.fetch(vaultPDA)).eventSeq;
+
+      await program.methods
+        .setGuardian(vaultOwner.publicKey)
+        .accounts({
+          owner: vaultOwner.publicKey,
+          vault: vaultPDA,
+        })
+        .signers([vaultOwner])
+        .rpc();
+
+      const after = (await program.account.vaultAccount.fetch(vaultPDA)).eventSeq;
+      expect(after.toNumber()).to.equal(before.toNumber() + 1);
+      console.log("   ✅ Event sequence advanced to", after.toString());
+    });
   });
 
   describe("8. Security Tests", () => {
     it("Should verify PDA derivation", async () => {
       const [derivedVaultPDA, derivedBump] = PublicKey.findProgramAddressSync(
-        [Buffer.from("vault"), vaultOwner.publicKey.toBuffer()],
+        [Buffer.from("vault"), vaultOwner.publicKey.toBuffer(), vaultIdSeed(0)],
         program.programId
       );
 
@@ -610,7 +970,7 @@ describe("ShadowVault Protocol Tests", () => {
     const vault = await program.account.vaultAccount.fetch(vaultPDA);
     console.log("   Final TVL:", vault.totalValueLocked.toString());
     console.log("   Total Executions:", vault.executionCount.toString());
-    console.log("   Vault Status:", vault.isPaused ? "Paused" : "Active");
+    console.log("   Vault Status:", vault.pauseFlags ? `Paused (flags ${vault.pauseFlags})` : "Active");
     console.log("\n✅ All tests completed successfully!\n");
   });
 });
@@ -70,16 +70,21 @@ describe('shadowvault', () => {
     const strategyType = 1
 
     const [intent] = PublicKey.findProgramAddressSync(
-      [Buffer.from('intent'), user.publicKey.toBuffer(), vaultPda.toBuffer()],
+      [Buffer.from('intent'), user.publicKey.toBuffer(), vaultPda.toBuffer(), new BN(0).toArrayLike(Buffer, 'le', 8)],
       program.programId
     )
     intentPda = intent
+    const [intentCounter] = PublicKey.findProgramAddressSync(
+      [Buffer.from('user_intents'), user.publicKey.toBuffer(), vaultPda.toBuffer()],
+      program.programId
+    )
 
     const tx = await (program.methods as any)
       .submitTradeIntent(tokenIn, tokenOut, amount, maxSlippageBps, strategyType)
       .accounts({
         user: user.publicKey,
         vault: vaultPda,
+        intentCounter,
         intent: intentPda,
         systemProgram: SystemProgram.programId,
       })
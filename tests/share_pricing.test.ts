@@ -0,0 +1,274 @@
+/**
+ * ShadowVault Protocol - Share Pricing Tests
+ *
+ * Reproduces the classic first-depositor (donation/inflation) attack against
+ * `deposit` and checks that virtual offsets plus ledger-based pricing defeat it
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Shadowvault } from "../target/types/shadowvault";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  LAMPORTS_PER_SOL,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  transfer,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+// Mirrors `CustodyLedger::VIRTUAL_*` in programs/shadowvault/src/state.rs
+const VIRTUAL_SHARES = 1_000n;
+const VIRTUAL_ASSETS = 1n;
+
+const sharesForDeposit = (amount: bigint, balance: bigint, totalShares: bigint) =>
+  (amount * (totalShares + VIRTUAL_SHARES)) / (balance + VIRTUAL_ASSETS);
+
+const assetsForShares = (shares: bigint, balance: bigint, totalShares: bigint) =>
+  (shares * (balance + VIRTUAL_ASSETS)) / (totalShares + VIRTUAL_SHARES);
+
+describe("ShadowVault Share Pricing", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Shadowvault as Program<Shadowvault>;
+  const payer = provider.wallet as anchor.Wallet;
+
+  const encryptedStrategyHash = Array.from(new Uint8Array(32).fill(7));
+  const donation = 10_000_000n;    // 10 tokens sent straight to custody
+  const victimDeposit = 5_000_000n; // 5 tokens
+
+  let vaultOwner: Keypair;
+  let attacker: Keypair;
+  let victim: Keypair;
+  let tokenMint: PublicKey;
+  let vaultPDA: PublicKey;
+  let vaultAta: PublicKey;
+  let ledgerPDA: PublicKey;
+
+  const [configPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("protocol_config")],
+    program.programId
+  );
+  // ProgramData under the upgradeable loader; its upgrade authority (the
+  // deployer, `payer` on localnet) is the only signer that may initialize
+  const [programDataPDA] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  );
+
+  const positionPDA = (owner: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("position"),
+        vaultPDA.toBuffer(),
+        tokenMint.toBuffer(),
+        owner.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const fund = async (kp: Keypair) => {
+    const sig = await provider.connection.requestAirdrop(kp.publicKey, 2 * LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+    const ata = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer.payer,
+      tokenMint,
+      kp.publicKey
+    );
+    await mintTo(provider.connection, payer.payer, tokenMint, ata.address, payer.publicKey, 100_000_000);
+    return ata.address;
+  };
+
+  const deposit = async (depositor: Keypair, amount: bigint) =>
+    program.methods
+      .deposit(new anchor.BN(amount.toString()), [])
+      .accounts({
+        user: depositor.publicKey,
+        tokenMint,
+        userAta: getAssociatedTokenAddressSync(tokenMint, depositor.publicKey),
+        vault: vaultPDA,
+        config: configPDA,
+        vaultAta,
+        ledger: ledgerPDA,
+        position: positionPDA(depositor.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([depositor])
+      .rpc();
+
+  before(async () => {
+    if (!(await provider.connection.getAccountInfo(configPDA))) {
+      await program.methods
+        .initializeProtocol(0, payer.publicKey)
+        .accounts({
+          admin: payer.publicKey,
+          programData: programDataPDA,
+          config: configPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  // Each case runs against a fresh vault so the attacker is the first depositor
+  const freshVault = async () => {
+    vaultOwner = Keypair.generate();
+    attacker = Keypair.generate();
+    victim = Keypair.generate();
+
+    const sig = await provider.connection.requestAirdrop(vaultOwner.publicKey, LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+
+    tokenMint = await createMint(provider.connection, payer.payer, payer.publicKey, null, 6);
+    const [ownerCounterPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("owner_vaults"), vaultOwner.publicKey.toBuffer()],
+      program.programId
+    );
+    [vaultPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vault"),
+        vaultOwner.publicKey.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    vaultAta = getAssociatedTokenAddressSync(tokenMint, vaultPDA, true);
+    [ledgerPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ledger"), vaultPDA.toBuffer(), tokenMint.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .initializeVault(encryptedStrategyHash)
+      .accounts({
+        payer: payer.publicKey,
+        owner: vaultOwner.publicKey,
+        ownerCounter: ownerCounterPDA,
+        vault: vaultPDA,
+        config: configPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([vaultOwner])
+      .rpc();
+
+    const attackerAta = await fund(attacker);
+    await fund(victim);
+    return attackerAta;
+  };
+
+  it("Ignores direct donations when pricing the victim's deposit", async () => {
+    const attackerAta = await freshVault();
+
+    // 1. Attacker is first in with a single base unit
+    await deposit(attacker, 1n);
+
+    // 2. Attacker donates straight into custody to inflate price-per-share
+    await transfer(provider.connection, attacker, attackerAta, vaultAta, attacker, donation);
+
+    // 3. Victim deposits
+    await deposit(victim, victimDeposit);
+
+    const ledger = await program.account.custodyLedger.fetch(ledgerPDA);
+    const custody = await getAccount(provider.connection, vaultAta);
+    const victimPosition = await program.account.userPosition.fetch(positionPDA(victim.publicKey));
+
+    // Donation sits in custody but never reaches the ledger
+    expect(BigInt(ledger.balance.toString())).to.equal(1n + victimDeposit);
+    expect(custody.amount).to.equal(1n + victimDeposit + donation);
+
+    // Victim is priced as if the donation never happened
+    const expectedShares = sharesForDeposit(victimDeposit, 1n, 1_000n);
+    expect(BigInt(victimPosition.shares.toString())).to.equal(expectedShares);
+
+    const victimClaim = assetsForShares(
+      expectedShares,
+      BigInt(ledger.balance.toString()),
+      BigInt(ledger.totalShares.toString())
+    );
+    expect(victimClaim >= victimDeposit - 1n).to.be.true;
+
+    console.log("   ✅ Victim shares:", expectedShares.toString(), "claim:", victimClaim.toString());
+  });
+
+  it("Keeps the attack unprofitable even after custody is resynced", async () => {
+    const attackerAta = await freshVault();
+
+    await deposit(attacker, 1n);
+    await transfer(provider.connection, attacker, attackerAta, vaultAta, attacker, donation);
+
+    // Owner absorbs the donation into the books
+    await program.methods
+      .reconcile(true)
+      .accounts({ caller: vaultOwner.publicKey, vault: vaultPDA })
+      .remainingAccounts([
+        { pubkey: ledgerPDA, isWritable: true, isSigner: false },
+        { pubkey: vaultAta, isWritable: false, isSigner: false },
+      ])
+      .signers([vaultOwner])
+      .rpc();
+
+    await deposit(victim, victimDeposit);
+
+    const ledger = await program.account.custodyLedger.fetch(ledgerPDA);
+    const balance = BigInt(ledger.balance.toString());
+    const totalShares = BigInt(ledger.totalShares.toString());
+    const victimShares = BigInt(
+      (await program.account.userPosition.fetch(positionPDA(victim.publicKey))).shares.toString()
+    );
+    const attackerShares = BigInt(
+      (await program.account.userPosition.fetch(positionPDA(attacker.publicKey))).shares.toString()
+    );
+
+    // Without offsets the victim would be rounded down to zero shares
+    expect(victimShares > 0n).to.be.true;
+
+    // Attacker recovers far less than they donated
+    const attackerClaim = assetsForShares(attackerShares, balance, totalShares);
+    expect(attackerClaim < donation).to.be.true;
+
+    // Victim loses well under 1% to rounding
+    const victimClaim = assetsForShares(victimShares, balance, totalShares);
+    expect(victimClaim * 100n >= victimDeposit * 99n).to.be.true;
+
+    console.log("   ✅ Attacker claim:", attackerClaim.toString(), "of", (donation + 1n).toString(), "spent");
+  });
+
+  it("Rejects deposits that would mint zero shares", async () => {
+    await freshVault();
+    await deposit(attacker, 1n);
+
+    // Push price-per-share above one base unit via owner resync of a donation
+    const attackerAta = getAssociatedTokenAddressSync(tokenMint, attacker.publicKey);
+    await transfer(provider.connection, attacker, attackerAta, vaultAta, attacker, donation);
+    await program.methods
+      .reconcile(true)
+      .accounts({ caller: vaultOwner.publicKey, vault: vaultPDA })
+      .remainingAccounts([
+        { pubkey: ledgerPDA, isWritable: true, isSigner: false },
+        { pubkey: vaultAta, isWritable: false, isSigner: false },
+      ])
+      .signers([vaultOwner])
+      .rpc();
+
+    try {
+      await deposit(victim, 1n);
+      expect.fail("Should have thrown ZeroShares error");
+    } catch (error) {
+      expect(error.toString()).to.include("ZeroShares");
+      console.log("   ✅ Correctly rejected dust deposit");
+    }
+  });
+});